@@ -2,20 +2,16 @@
 // Licensed under the MIT License.
 
 use clap::Parser;
-use dsc_lib::{
-    configure::config_doc::ExecutionKind,
-    discovery::discovery_trait::DiscoveryFilter,
-    dscresources::{
-        dscresource::Invoke,
-        invoke_result::{GetResult, SetResult},
-    },
-    types::{FullyQualifiedTypeName, ResourceVersionReq},
-    DscManager,
-};
+use dsc_lib::configure::config_doc::ExecutionKind;
+use resource::{BicepResource, error_response, resource_response};
 use rust_i18n::{i18n, t};
+use serde_json::{Map, Value};
 use std::{env, io, process};
 use tonic::{transport::Server, Request, Response, Status};
 
+mod resource;
+mod types;
+
 // Include the generated protobuf code
 pub mod proto {
     tonic::include_proto!("extension");
@@ -55,46 +51,17 @@ impl BicepExtension for BicepExtensionService {
             )
         );
 
-        let type_name = FullyQualifiedTypeName::parse(&resource_type)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        let version_req = version.as_deref()
-            .map(|v| ResourceVersionReq::parse(&format!("={v}")))
-            .transpose()
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        let mut dsc = DscManager::new();
-        let Some(resource) = dsc
-            .find_resource(&DiscoveryFilter::new(
-                &type_name,
-                version_req,
-                None,
-            ))
-            .unwrap_or(None)
-        else {
-            return Err(Status::not_found(
-                t!("dscerror.resourceNotFound").to_string(),
-            ));
-        };
-
-        let SetResult::Resource(result) = resource
-            .set(&properties, false, &ExecutionKind::Actual)
-            .map_err(|e| Status::aborted(e.to_string()))?
-        else {
-            return Err(Status::unimplemented(
-                t!("dscerror.notSupported").to_string(),
-            ));
+        let resource = BicepResource::find(&resource_type, version.as_deref(), spec.config.as_deref())?;
+        let input = parse_json(&properties)?;
+        let response = match resource.set(&properties, &ExecutionKind::Actual) {
+            Ok(state) => {
+                let identifiers = resource.identifiers(&input, Some(&state));
+                resource_response(resource_type, version, &identifiers, state)
+            },
+            Err(e) => error_response(&resource_type, &e),
         };
 
-        Ok(Response::new(LocalExtensibilityOperationResponse {
-            resource: Some(proto::Resource {
-                r#type: resource_type,
-                api_version: version,
-                identifiers: properties,
-                properties: result.after_state.to_string(),
-                status: None,
-            }),
-            error_data: None,
-        }))
+        Ok(Response::new(response))
     }
 
     async fn preview(
@@ -117,46 +84,17 @@ impl BicepExtension for BicepExtensionService {
             )
         );
 
-        let type_name = FullyQualifiedTypeName::parse(&resource_type)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        let version_req = version.as_deref()
-            .map(|v| ResourceVersionReq::parse(&format!("={v}")))
-            .transpose()
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        let mut dsc = DscManager::new();
-        let Some(resource) = dsc
-            .find_resource(&DiscoveryFilter::new(
-                &type_name,
-                version_req,
-                None,
-            ))
-            .unwrap_or(None)
-        else {
-            return Err(Status::not_found(
-                t!("dscerror.resourceNotFound").to_string(),
-            ));
-        };
-
-        let SetResult::Resource(result) = resource
-            .set(&properties, false, &ExecutionKind::WhatIf)
-            .map_err(|e| Status::aborted(e.to_string()))?
-        else {
-            return Err(Status::unimplemented(
-                t!("dscerror.notSupported").to_string(),
-            ));
+        let resource = BicepResource::find(&resource_type, version.as_deref(), spec.config.as_deref())?;
+        let input = parse_json(&properties)?;
+        let response = match resource.set(&properties, &ExecutionKind::WhatIf) {
+            Ok(state) => {
+                let identifiers = resource.identifiers(&input, Some(&state));
+                resource_response(resource_type, version, &identifiers, state)
+            },
+            Err(e) => error_response(&resource_type, &e),
         };
 
-        Ok(Response::new(LocalExtensibilityOperationResponse {
-            resource: Some(proto::Resource {
-                r#type: resource_type,
-                api_version: version,
-                identifiers: properties,
-                properties: result.after_state.to_string(),
-                status: None,
-            }),
-            error_data: None,
-        }))
+        Ok(Response::new(response))
     }
 
     async fn get(
@@ -164,9 +102,9 @@ impl BicepExtension for BicepExtensionService {
         request: Request<ResourceReference>,
     ) -> Result<Response<LocalExtensibilityOperationResponse>, Status> {
         let reference = request.into_inner();
-        let resource_type = reference.r#type.clone();
-        let version = reference.api_version.clone();
-        let identifiers = reference.identifiers.clone();
+        let resource_type = reference.r#type;
+        let version = reference.api_version;
+        let identifiers = reference.identifiers;
 
         tracing::debug!(
             "{}",
@@ -179,46 +117,17 @@ impl BicepExtension for BicepExtensionService {
             )
         );
 
-        let type_name = FullyQualifiedTypeName::parse(&resource_type)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        let version_req = version.as_deref()
-            .map(|v| ResourceVersionReq::parse(&format!("={v}")))
-            .transpose()
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        let mut dsc = DscManager::new();
-        let Some(resource) = dsc
-            .find_resource(&DiscoveryFilter::new(
-                &type_name,
-                version_req,
-                None,
-            ))
-            .unwrap_or(None)
-        else {
-            return Err(Status::not_found(
-                t!("dscerror.resourceNotFound").to_string(),
-            ));
+        let resource = BicepResource::find(&resource_type, version.as_deref(), reference.config.as_deref())?;
+        let input = parse_json(&identifiers)?;
+        let response = match resource.get(&identifiers) {
+            Ok(state) => {
+                let identifiers = resource.identifiers(&input, Some(&state));
+                resource_response(resource_type, version, &identifiers, state)
+            },
+            Err(e) => error_response(&resource_type, &e),
         };
 
-        let GetResult::Resource(result) = resource
-            .get(&identifiers)
-            .map_err(|e| Status::aborted(e.to_string()))?
-        else {
-            return Err(Status::unimplemented(
-                t!("dscerror.notSupported").to_string(),
-            ));
-        };
-
-        Ok(Response::new(LocalExtensibilityOperationResponse {
-            resource: Some(proto::Resource {
-                r#type: resource_type,
-                api_version: version,
-                identifiers,
-                properties: result.actual_state.to_string(),
-                status: None,
-            }),
-            error_data: None,
-        }))
+        Ok(Response::new(response))
     }
 
     async fn delete(
@@ -226,9 +135,9 @@ impl BicepExtension for BicepExtensionService {
         request: Request<ResourceReference>,
     ) -> Result<Response<LocalExtensibilityOperationResponse>, Status> {
         let reference = request.into_inner();
-        let resource_type = reference.r#type.clone();
-        let version = reference.api_version.clone();
-        let identifiers = reference.identifiers.clone();
+        let resource_type = reference.r#type;
+        let version = reference.api_version;
+        let identifiers = reference.identifiers;
 
         tracing::debug!(
             "{}",
@@ -241,51 +150,30 @@ impl BicepExtension for BicepExtensionService {
             )
         );
 
-        let type_name = FullyQualifiedTypeName::parse(&resource_type)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        let version_req = version.as_deref()
-            .map(|v| ResourceVersionReq::parse(&format!("={v}")))
-            .transpose()
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        let mut dsc = DscManager::new();
-        let Some(resource) = dsc
-            .find_resource(&DiscoveryFilter::new(
-                &type_name,
-                version_req,
-                None,
-            ))
-            .unwrap_or(None)
-        else {
-            return Err(Status::not_found(
-                t!("dscerror.resourceNotFound").to_string(),
-            ));
+        let resource = BicepResource::find(&resource_type, version.as_deref(), reference.config.as_deref())?;
+        let input = parse_json(&identifiers)?;
+        let response = match resource.delete(&identifiers) {
+            Ok(()) => {
+                let identifiers = resource.identifiers(&input, None);
+                resource_response(resource_type, version, &identifiers, Value::Object(Map::new()))
+            },
+            Err(e) => error_response(&resource_type, &e),
         };
 
-        resource
-            .delete(&identifiers, &ExecutionKind::Actual)
-            .map_err(|e| Status::aborted(e.to_string()))?;
-
-        Ok(Response::new(LocalExtensibilityOperationResponse {
-            resource: Some(proto::Resource {
-                r#type: resource_type,
-                api_version: version,
-                identifiers,
-                properties: "{}".to_string(),
-                status: None,
-            }),
-            error_data: None,
-        }))
+        Ok(Response::new(response))
     }
 
     async fn get_type_files(
         &self,
         _request: Request<Empty>,
     ) -> Result<Response<TypeFilesResponse>, Status> {
-        // TODO: Dynamically return type definitions for DSC resources found on the system.
-        Err(Status::unimplemented(
-            t!("dscerror.notImplemented").to_string(),
-        ))
+        let (index_file, type_files) = types::generate_type_files()
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(TypeFilesResponse {
+            index_file,
+            type_files,
+        }))
     }
 
     async fn ping(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
@@ -293,6 +181,10 @@ impl BicepExtension for BicepExtensionService {
     }
 }
 
+fn parse_json(json: &str) -> Result<Value, Status> {
+    serde_json::from_str(json).map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "dsc-bicep-ext")]
 #[command(about = "DSC Bicep Local Deploy Extension", long_about = None)]
@@ -0,0 +1,362 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::proto::{self, ErrorData, ErrorDetail, LocalExtensibilityOperationResponse};
+use crate::types::key_properties;
+use dsc_lib::{
+    DscManager,
+    configure::config_doc::ExecutionKind,
+    discovery::discovery_trait::DiscoveryFilter,
+    dscerror::DscError,
+    dscresources::{
+        dscresource::{Capability, DscResource, Invoke},
+        invoke_result::{GetResult, SetResult},
+    },
+    types::{FullyQualifiedTypeName, ResourceVersionReq},
+};
+use rust_i18n::t;
+use serde_json::{Map, Value, json};
+use tonic::Status;
+
+/// The extension configuration property that selects the adapter for adapted resources.
+const REQUIRE_ADAPTER_CONFIG: &str = "requireAdapter";
+
+/// A DSC resource resolved for a Bicep resource type along with its key properties.
+pub struct BicepResource {
+    pub resource: DscResource,
+    pub key_properties: Vec<String>,
+}
+
+impl BicepResource {
+    /// Find the DSC resource for a Bicep resource type.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_type` - The type name of the resource.
+    /// * `version` - The optional exact version of the resource.
+    /// * `config` - The optional extension configuration as JSON, which can specify `requireAdapter`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error status if the arguments are invalid or the resource isn't found.
+    pub fn find(resource_type: &str, version: Option<&str>, config: Option<&str>) -> Result<Self, Status> {
+        let type_name = FullyQualifiedTypeName::parse(resource_type)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let version_req = version
+            .map(|v| ResourceVersionReq::parse(&format!("={v}")))
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let require_adapter = match config {
+            Some(config) if !config.trim().is_empty() => {
+                let config: Value = serde_json::from_str(config)
+                    .map_err(|e| Status::invalid_argument(t!("resource.invalidConfig", error = e.to_string()).to_string()))?;
+                config.get(REQUIRE_ADAPTER_CONFIG)
+                    .and_then(Value::as_str)
+                    .map(FullyQualifiedTypeName::parse)
+                    .transpose()
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?
+            },
+            _ => None,
+        };
+
+        let mut dsc = DscManager::new();
+        let Some(resource) = dsc
+            .find_resource(&DiscoveryFilter::new(&type_name, version_req, require_adapter))
+            .unwrap_or(None)
+        else {
+            return Err(Status::not_found(
+                t!("resource.resourceNotFound", resourceType = resource_type).to_string(),
+            ));
+        };
+
+        let key_properties = match resource.schema() {
+            Ok(schema) => serde_json::from_str::<Value>(&schema)
+                .map(|schema| key_properties(&schema))
+                .unwrap_or_default(),
+            Err(err) => {
+                tracing::debug!("{}", t!("resource.schemaNotAvailable", resourceType = resource_type, error = err.to_string()));
+                Vec::new()
+            },
+        };
+
+        Ok(Self { resource: resource.clone(), key_properties })
+    }
+
+    /// Get the identifiers of a resource instance from its key properties.
+    ///
+    /// Key properties are taken from the state returned by the resource and fall back to the
+    /// input, if the resource doesn't have key properties the input is returned as-is.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The properties or identifiers supplied by Bicep.
+    /// * `state` - The state returned by the resource, if any.
+    #[must_use]
+    pub fn identifiers(&self, input: &Value, state: Option<&Value>) -> Value {
+        if self.key_properties.is_empty() {
+            return input.clone();
+        }
+
+        let mut identifiers = Map::new();
+        for key in &self.key_properties {
+            let value = state.and_then(|state| state.get(key)).or_else(|| input.get(key));
+            if let Some(value) = value {
+                identifiers.insert(key.clone(), value.clone());
+            }
+        }
+        Value::Object(identifiers)
+    }
+
+    /// Invoke the set operation, or simulate it when `execution_type` is what-if.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails.
+    pub fn set(&self, properties: &str, execution_type: &ExecutionKind) -> Result<Value, DscError> {
+        match self.resource.set(properties, false, execution_type)? {
+            SetResult::Resource(response) => Ok(response.after_state),
+            SetResult::Group(results) => Ok(json!({ "resources": results })),
+        }
+    }
+
+    /// Invoke the get operation, using export for resources that only implement export.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails.
+    pub fn get(&self, identifiers: &str) -> Result<Value, DscError> {
+        let capabilities = &self.resource.capabilities;
+        if !capabilities.contains(&Capability::Get) && capabilities.contains(&Capability::Export) {
+            return self.get_from_export(identifiers);
+        }
+
+        match self.resource.get(identifiers)? {
+            GetResult::Resource(response) => Ok(response.actual_state),
+            GetResult::Group(results) => Ok(json!({ "resources": results })),
+        }
+    }
+
+    /// Find the instance with matching key properties in the exported instances of the resource.
+    ///
+    /// If the resource doesn't have key properties, every property supplied in the identifiers must match.
+    fn get_from_export(&self, identifiers: &str) -> Result<Value, DscError> {
+        let filter: Value = serde_json::from_str(identifiers)?;
+        let match_properties: Vec<&String> = if self.key_properties.is_empty() {
+            filter.as_object().map(|filter| filter.keys().collect()).unwrap_or_default()
+        } else {
+            self.key_properties.iter().collect()
+        };
+        if match_properties.is_empty() {
+            return Err(DscError::Validation(t!("resource.identifiersRequired", resourceType = self.resource.type_name).to_string()));
+        }
+
+        let exported = self.resource.export(identifiers)?;
+        let instance = exported.actual_state.into_iter().find(|instance| {
+            match_properties.iter().all(|key| instance.get(key.as_str()) == filter.get(key.as_str()))
+        });
+
+        Ok(instance.unwrap_or_else(|| {
+            // the instance doesn't exist so the identifiers are returned as its state
+            let mut state = filter.as_object().cloned().unwrap_or_default();
+            state.insert("_exist".to_string(), Value::Bool(false));
+            Value::Object(state)
+        }))
+    }
+
+    /// Invoke the delete operation, using set with `_exist` as `false` for resources that handle it instead.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails or doesn't support deleting.
+    pub fn delete(&self, identifiers: &str) -> Result<(), DscError> {
+        let capabilities = &self.resource.capabilities;
+        if capabilities.contains(&Capability::Delete) {
+            self.resource.delete(identifiers, &ExecutionKind::Actual)?;
+            return Ok(());
+        }
+
+        if capabilities.contains(&Capability::SetHandlesExist) {
+            let mut desired: Map<String, Value> = serde_json::from_str(identifiers)?;
+            desired.insert("_exist".to_string(), Value::Bool(false));
+            self.resource.set(&Value::Object(desired).to_string(), true, &ExecutionKind::Actual)?;
+            return Ok(());
+        }
+
+        Err(DscError::NotSupported(t!("resource.deleteNotSupported", resourceType = self.resource.type_name).to_string()))
+    }
+}
+
+/// Create a successful response for a resource instance.
+///
+/// The `_metadata` returned by the resource isn't part of the Bicep type so it's removed from the properties.
+#[must_use]
+pub fn resource_response(resource_type: String, version: Option<String>, identifiers: &Value, mut state: Value) -> LocalExtensibilityOperationResponse {
+    if let Some(state) = state.as_object_mut()
+        && let Some(metadata) = state.remove("_metadata")
+        && let Some(Value::Array(what_if)) = metadata.get("whatIf") {
+        for message in what_if.iter().filter_map(Value::as_str) {
+            tracing::info!("{}", t!("resource.whatIf", resourceType = resource_type, message = message));
+        }
+    }
+
+    LocalExtensibilityOperationResponse {
+        resource: Some(proto::Resource {
+            r#type: resource_type,
+            api_version: version,
+            identifiers: identifiers.to_string(),
+            properties: state.to_string(),
+            status: None,
+        }),
+        error_data: None,
+    }
+}
+
+/// Create a response describing why an operation on a resource instance failed.
+#[must_use]
+pub fn error_response(resource_type: &str, error: &DscError) -> LocalExtensibilityOperationResponse {
+    LocalExtensibilityOperationResponse {
+        resource: None,
+        error_data: Some(ErrorData {
+            error: Some(proto::Error {
+                code: error_code(error).to_string(),
+                target: Some(resource_type.to_string()),
+                message: error.to_string(),
+                details: error_details(error),
+                inner_error: None,
+            }),
+        }),
+    }
+}
+
+fn error_code(error: &DscError) -> &'static str {
+    match error {
+        DscError::Command(..) | DscError::CommandExit(..) | DscError::CommandExitFromManifest(..) => "ResourceCommandFailed",
+        DscError::NotImplemented(_) | DscError::NotSupported(_) | DscError::UnsupportedCapability(..) => "NotSupported",
+        DscError::Schema(_) | DscError::Validation(_) | DscError::Json(_) => "InvalidProperties",
+        DscError::AdapterNotFound(_) | DscError::MissingRequires(..) => "AdapterNotFound",
+        _ => "OperationFailed",
+    }
+}
+
+/// Each line written by a failed resource command becomes a detail with the exit code.
+fn error_details(error: &DscError) -> Vec<ErrorDetail> {
+    match error {
+        DscError::Command(target, exit_code, message)
+        | DscError::CommandExit(target, exit_code, message)
+        | DscError::CommandExitFromManifest(target, exit_code, message) => {
+            message.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| ErrorDetail {
+                    code: exit_code.to_string(),
+                    target: Some(target.clone()),
+                    message: line.to_string(),
+                })
+                .collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsc_lib::dscresources::native_resource::{NativeResource, to_dsc_resource};
+    use dsc_lib::types::ResourceVersion;
+    use std::sync::{Arc, Mutex};
+
+    struct TestExport {
+        type_name: &'static str,
+        capabilities: Vec<Capability>,
+        instances: Mutex<Vec<Value>>,
+    }
+
+    impl NativeResource for TestExport {
+        fn type_name(&self) -> FullyQualifiedTypeName {
+            FullyQualifiedTypeName::parse(self.type_name).unwrap()
+        }
+
+        fn version(&self) -> ResourceVersion {
+            ResourceVersion::parse("1.0.0").unwrap()
+        }
+
+        fn capabilities(&self) -> Vec<Capability> {
+            self.capabilities.clone()
+        }
+
+        fn schema(&self) -> Result<Value, DscError> {
+            Ok(json!({ "type": "object" }))
+        }
+
+        fn get(&self, filter: &Value) -> Result<Value, DscError> {
+            Ok(filter.clone())
+        }
+
+        fn delete(&self, filter: &Value, _execution_type: &ExecutionKind) -> Result<(), DscError> {
+            self.instances.lock().unwrap().retain(|instance| instance.get("name") != filter.get("name"));
+            Ok(())
+        }
+
+        fn export(&self, _filter: Option<&Value>) -> Result<Vec<Value>, DscError> {
+            Ok(self.instances.lock().unwrap().clone())
+        }
+    }
+
+    fn bicep_resource(type_name: &'static str, capabilities: Vec<Capability>, key_properties: &[&str]) -> BicepResource {
+        let resource = to_dsc_resource(Arc::new(TestExport {
+            type_name,
+            capabilities,
+            instances: Mutex::new(vec![
+                json!({ "name": "first", "value": 1 }),
+                json!({ "name": "second", "value": 2 }),
+            ]),
+        }));
+        BicepResource { resource, key_properties: key_properties.iter().map(ToString::to_string).collect() }
+    }
+
+    #[test]
+    fn get_uses_get_when_implemented() {
+        let resource = bicep_resource("Test/BicepGet", vec![Capability::Get, Capability::Export], &["name"]);
+        assert_eq!(resource.get(r#"{"name":"missing"}"#).unwrap(), json!({ "name": "missing" }));
+    }
+
+    #[test]
+    fn get_from_export_matches_key_properties() {
+        let resource = bicep_resource("Test/BicepExportKeys", vec![Capability::Export], &["name"]);
+        assert_eq!(resource.get(r#"{"name":"second"}"#).unwrap(), json!({ "name": "second", "value": 2 }));
+        assert_eq!(resource.get(r#"{"name":"third"}"#).unwrap(), json!({ "name": "third", "_exist": false }));
+    }
+
+    #[test]
+    fn get_from_export_without_key_properties_matches_identifiers() {
+        let resource = bicep_resource("Test/BicepExportNoKeys", vec![Capability::Export], &[]);
+        assert_eq!(resource.get(r#"{"value":2}"#).unwrap(), json!({ "name": "second", "value": 2 }));
+        assert_eq!(resource.get(r#"{"name":"first","value":2}"#).unwrap(), json!({ "name": "first", "value": 2, "_exist": false }));
+        assert!(matches!(resource.get("{}"), Err(DscError::Validation(_))));
+    }
+
+    #[test]
+    fn delete_uses_delete_when_implemented() {
+        let resource = bicep_resource("Test/BicepDelete", vec![Capability::Delete, Capability::Export], &["name"]);
+        resource.delete(r#"{"name":"first"}"#).unwrap();
+        assert_eq!(resource.get(r#"{"name":"first"}"#).unwrap(), json!({ "name": "first", "_exist": false }));
+    }
+
+    #[test]
+    fn delete_not_supported() {
+        let resource = bicep_resource("Test/BicepNoDelete", vec![Capability::Get], &["name"]);
+        let err = resource.delete(r#"{"name":"first"}"#).unwrap_err();
+        assert!(matches!(err, DscError::NotSupported(_)));
+        assert_eq!(error_code(&err), "NotSupported");
+    }
+
+    #[test]
+    fn command_error_details() {
+        let err = DscError::Command("Test/Bicep".to_string(), 2, "first line\n\n  second line  \n".to_string());
+        let response = error_response("Test/Bicep", &err);
+        let error = response.error_data.unwrap().error.unwrap();
+        assert_eq!(error.code, "ResourceCommandFailed");
+        assert_eq!(error.details.iter().map(|d| d.message.as_str()).collect::<Vec<_>>(), vec!["first line", "second line"]);
+        assert!(error.details.iter().all(|d| d.code == "2"));
+    }
+}
@@ -0,0 +1,607 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Generates Bicep type files from the JSON schemas of DSC resources.
+//!
+//! The types are serialized in the format used by `Azure.Bicep.Types`: a `types.json` file
+//! containing an array of types that reference each other by index, and an `index.json` file
+//! mapping each `type@version` resource to its `ResourceType` in the types file.
+
+use dsc_lib::{
+    DscManager,
+    discovery::{command_discovery::ImportedManifest, discovery_trait::DiscoveryKind},
+    dscresources::dscresource::{DscResource, Invoke},
+    progress::ProgressFormat,
+    types::TypeNameFilter,
+};
+use rust_i18n::t;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// The name of the file containing the types.
+pub const TYPES_FILE: &str = "types.json";
+/// The name of the extension in the generated index.
+const EXTENSION_NAME: &str = "DSC";
+
+/// The property is required.
+const PROPERTY_FLAG_REQUIRED: u32 = 1;
+/// The property is returned by the resource but can't be set.
+const PROPERTY_FLAG_READ_ONLY: u32 = 2;
+/// The property can be set but isn't returned by the resource.
+const PROPERTY_FLAG_WRITE_ONLY: u32 = 4;
+/// The property identifies the resource instance.
+const PROPERTY_FLAG_IDENTIFIER: u32 = 16;
+
+/// The schema keyword that marks a property as a key property of the resource.
+const KEY_PROPERTY_KEYWORD: &str = "x-dsc-key";
+
+/// The resource can be declared at any scope.
+const SCOPE_TYPE_UNKNOWN: u32 = 0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeReference {
+    #[serde(rename = "$ref")]
+    pub reference: String,
+}
+
+impl TypeReference {
+    fn new(index: usize) -> Self {
+        Self { reference: format!("#/{index}") }
+    }
+
+    fn in_file(&self, file: &str) -> Self {
+        Self { reference: format!("{file}{}", self.reference) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectTypeProperty {
+    pub r#type: TypeReference,
+    pub flags: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "$type", rename_all_fields = "camelCase")]
+pub enum BicepType {
+    AnyType,
+    NullType,
+    BooleanType,
+    IntegerType {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_value: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_value: Option<i64>,
+    },
+    StringType {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_length: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_length: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+    StringLiteralType {
+        value: String,
+    },
+    ArrayType {
+        item_type: TypeReference,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_length: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_length: Option<u64>,
+    },
+    ObjectType {
+        name: String,
+        properties: BTreeMap<String, ObjectTypeProperty>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        additional_properties: Option<TypeReference>,
+    },
+    UnionType {
+        elements: Vec<TypeReference>,
+    },
+    ResourceType {
+        name: String,
+        body: TypeReference,
+        readable_scopes: u32,
+        writable_scopes: u32,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeSettings {
+    pub name: String,
+    pub version: String,
+    pub is_singleton: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeIndex {
+    pub resources: BTreeMap<String, TypeReference>,
+    pub resource_functions: BTreeMap<String, Value>,
+    pub settings: TypeSettings,
+}
+
+/// Builds the array of Bicep types, converting JSON schemas into types that reference each other by index.
+#[derive(Debug, Default)]
+pub struct TypeFactory {
+    types: Vec<BicepType>,
+    resources: BTreeMap<String, TypeReference>,
+    /// Shared types like `string` are only added once.
+    primitives: HashMap<String, TypeReference>,
+    /// Definitions referenced by `$ref` in the schema currently being converted.
+    definitions: HashMap<String, TypeReference>,
+}
+
+impl TypeFactory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a resource type with a body converted from the JSON schema of the resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name` - The type name of the resource.
+    /// * `version` - The version of the resource.
+    /// * `schema` - The JSON schema for the properties of the resource.
+    pub fn add_resource(&mut self, type_name: &str, version: &str, schema: &Value) {
+        self.definitions.clear();
+        let body = match self.build(body_schema(schema), schema, type_name) {
+            BicepType::ObjectType { name, mut properties, additional_properties } => {
+                for key in key_properties(schema) {
+                    if let Some(property) = properties.get_mut(&key) {
+                        property.flags |= PROPERTY_FLAG_IDENTIFIER;
+                    }
+                }
+                self.push(BicepType::ObjectType { name, properties, additional_properties })
+            },
+            // the body of a resource must be an object so anything else accepts any properties
+            _ => {
+                let any = self.any();
+                self.push(BicepType::ObjectType {
+                    name: type_name.to_string(),
+                    properties: BTreeMap::new(),
+                    additional_properties: Some(any),
+                })
+            },
+        };
+        let name = format!("{type_name}@{version}");
+        let resource = self.push(BicepType::ResourceType {
+            name: name.clone(),
+            body,
+            readable_scopes: SCOPE_TYPE_UNKNOWN,
+            writable_scopes: SCOPE_TYPE_UNKNOWN,
+        });
+        self.resources.insert(name, resource);
+    }
+
+    /// Get the types that have been added.
+    #[must_use]
+    pub fn types(&self) -> &[BicepType] {
+        &self.types
+    }
+
+    /// Create the index of the resource types that have been added.
+    #[must_use]
+    pub fn index(&self) -> TypeIndex {
+        TypeIndex {
+            resources: self.resources.iter().map(|(name, reference)| (name.clone(), reference.in_file(TYPES_FILE))).collect(),
+            resource_functions: BTreeMap::new(),
+            settings: TypeSettings {
+                name: EXTENSION_NAME.to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                is_singleton: false,
+            },
+        }
+    }
+
+    fn push(&mut self, bicep_type: BicepType) -> TypeReference {
+        self.types.push(bicep_type);
+        TypeReference::new(self.types.len() - 1)
+    }
+
+    fn primitive(&mut self, key: &str, bicep_type: BicepType) -> TypeReference {
+        if let Some(reference) = self.primitives.get(key) {
+            return reference.clone();
+        }
+        let reference = self.push(bicep_type);
+        self.primitives.insert(key.to_string(), reference.clone());
+        reference
+    }
+
+    fn any(&mut self) -> TypeReference {
+        self.primitive("any", BicepType::AnyType)
+    }
+
+    /// Convert a schema and add the resulting type, returning the reference to it.
+    fn add(&mut self, schema: &Value, root: &Value, name: &str) -> TypeReference {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(existing) = self.definitions.get(reference) {
+                return existing.clone();
+            }
+            let Some(definition) = resolve_reference(root, reference) else {
+                return self.any();
+            };
+            // reserve the slot before converting so recursive definitions reference it
+            let index = self.types.len();
+            self.types.push(BicepType::AnyType);
+            let slot = TypeReference::new(index);
+            self.definitions.insert(reference.to_string(), slot.clone());
+            let definition_name = reference.rsplit('/').next().unwrap_or(name);
+            self.types[index] = self.build(definition, root, definition_name);
+            return slot;
+        }
+
+        match self.build(schema, root, name) {
+            BicepType::AnyType => self.any(),
+            BicepType::NullType => self.primitive("null", BicepType::NullType),
+            BicepType::BooleanType => self.primitive("bool", BicepType::BooleanType),
+            bicep_type @ (BicepType::StringType { min_length: None, max_length: None, pattern: None }) => self.primitive("string", bicep_type),
+            bicep_type @ (BicepType::IntegerType { min_value: None, max_value: None }) => self.primitive("int", bicep_type),
+            bicep_type => self.push(bicep_type),
+        }
+    }
+
+    /// Convert a schema into a type, adding any types it references.
+    fn build(&mut self, schema: &Value, root: &Value, name: &str) -> BicepType {
+        let Some(schema) = schema.as_object() else {
+            // `true` and other non-object schemas accept any value
+            return BicepType::AnyType;
+        };
+
+        if schema.contains_key("$ref") {
+            let reference = self.add(&Value::Object(schema.clone()), root, name);
+            return BicepType::UnionType { elements: vec![reference] };
+        }
+
+        if let Some(value) = schema.get("const") {
+            return match value {
+                Value::String(value) => BicepType::StringLiteralType { value: value.clone() },
+                Value::Bool(_) => BicepType::BooleanType,
+                Value::Null => BicepType::NullType,
+                _ => BicepType::AnyType,
+            };
+        }
+
+        // non-string enums can't be expressed as literals so the declared type is used instead
+        if let Some(Value::Array(values)) = schema.get("enum")
+            && values.iter().all(|value| value.is_string() || value.is_null()) {
+            let elements = values.iter().map(|value| match value {
+                Value::String(value) => self.push(BicepType::StringLiteralType { value: value.clone() }),
+                _ => self.primitive("null", BicepType::NullType),
+            }).collect();
+            return BicepType::UnionType { elements };
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(variants)) = schema.get(keyword) {
+                let elements = variants.iter().enumerate()
+                    .map(|(i, variant)| self.add(variant, root, &format!("{name}{i}")))
+                    .collect();
+                return BicepType::UnionType { elements };
+            }
+        }
+
+        self.build_declared_type(schema, root, name)
+    }
+
+    fn build_declared_type(&mut self, schema: &Map<String, Value>, root: &Value, name: &str) -> BicepType {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(schema_type)) => vec![schema_type.as_str()],
+            Some(Value::Array(schema_types)) => schema_types.iter().filter_map(Value::as_str).collect(),
+            _ if schema.contains_key("properties") => vec!["object"],
+            _ => vec![],
+        };
+
+        match types.as_slice() {
+            [] => BicepType::AnyType,
+            [schema_type] => self.build_type(schema_type, schema, root, name),
+            // nullable types are common in resource schemas so those keep the non-null type
+            [schema_type, "null"] | ["null", schema_type] => self.build_type(schema_type, schema, root, name),
+            schema_types => {
+                let elements = schema_types.iter().map(|schema_type| {
+                    let bicep_type = self.build_type(schema_type, schema, root, name);
+                    self.push(bicep_type)
+                }).collect();
+                BicepType::UnionType { elements }
+            },
+        }
+    }
+
+    fn build_type(&mut self, schema_type: &str, schema: &Map<String, Value>, root: &Value, name: &str) -> BicepType {
+        match schema_type {
+            "string" => BicepType::StringType {
+                min_length: schema.get("minLength").and_then(Value::as_u64),
+                max_length: schema.get("maxLength").and_then(Value::as_u64),
+                pattern: schema.get("pattern").and_then(Value::as_str).map(ToString::to_string),
+            },
+            "integer" => BicepType::IntegerType {
+                min_value: schema.get("minimum").and_then(Value::as_i64),
+                max_value: schema.get("maximum").and_then(Value::as_i64),
+            },
+            "boolean" => BicepType::BooleanType,
+            "null" => BicepType::NullType,
+            "array" => {
+                let item_type = match schema.get("items") {
+                    Some(items) => self.add(items, root, &format!("{name}Item")),
+                    None => self.any(),
+                };
+                BicepType::ArrayType {
+                    item_type,
+                    min_length: schema.get("minItems").and_then(Value::as_u64),
+                    max_length: schema.get("maxItems").and_then(Value::as_u64),
+                }
+            },
+            "object" => self.build_object(schema, root, name),
+            // Bicep doesn't have a floating point type
+            _ => BicepType::AnyType,
+        }
+    }
+
+    fn build_object(&mut self, schema: &Map<String, Value>, root: &Value, name: &str) -> BicepType {
+        let required: Vec<&str> = schema.get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut properties = BTreeMap::new();
+        if let Some(Value::Object(schema_properties)) = schema.get("properties") {
+            for (property_name, property_schema) in schema_properties {
+                let mut flags = 0;
+                if required.contains(&property_name.as_str()) {
+                    flags |= PROPERTY_FLAG_REQUIRED;
+                }
+                if property_schema.get("readOnly").and_then(Value::as_bool) == Some(true) {
+                    flags |= PROPERTY_FLAG_READ_ONLY;
+                }
+                if property_schema.get("writeOnly").and_then(Value::as_bool) == Some(true) {
+                    flags |= PROPERTY_FLAG_WRITE_ONLY;
+                }
+                let r#type = self.add(property_schema, root, &format!("{name}{}", pascal_case(property_name)));
+                properties.insert(property_name.clone(), ObjectTypeProperty {
+                    r#type,
+                    flags,
+                    description: property_schema.get("description").and_then(Value::as_str).map(ToString::to_string),
+                });
+            }
+        }
+
+        let additional_properties = match schema.get("additionalProperties") {
+            Some(Value::Bool(true)) => Some(self.any()),
+            Some(additional @ Value::Object(_)) => Some(self.add(additional, root, &format!("{name}AdditionalProperties"))),
+            _ => None,
+        };
+
+        BicepType::ObjectType {
+            name: schema.get("title").and_then(Value::as_str).unwrap_or(name).to_string(),
+            properties,
+            additional_properties,
+        }
+    }
+}
+
+/// Get the key properties that identify an instance of a resource from its schema.
+///
+/// Properties marked with `x-dsc-key` are the key properties, if none are marked then the
+/// required properties that aren't read-only are used.
+///
+/// # Arguments
+///
+/// * `schema` - The JSON schema for the properties of the resource.
+#[must_use]
+pub fn key_properties(schema: &Value) -> Vec<String> {
+    let body = body_schema(schema);
+    let Some(Value::Object(properties)) = body.get("properties") else {
+        return Vec::new();
+    };
+
+    let marked: Vec<String> = properties.iter()
+        .filter(|(_, property)| property.get(KEY_PROPERTY_KEYWORD).and_then(Value::as_bool) == Some(true))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if !marked.is_empty() {
+        return marked;
+    }
+
+    body.get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter()
+            .filter_map(Value::as_str)
+            .filter(|name| properties.get(*name).and_then(|property| property.get("readOnly")).and_then(Value::as_bool) != Some(true))
+            .map(ToString::to_string)
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Get the schema for the properties of a resource, following a `$ref` at the root.
+fn body_schema(schema: &Value) -> &Value {
+    schema.get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| resolve_reference(schema, reference))
+        .unwrap_or(schema)
+}
+
+/// Resolve a local `$ref` like `#/$defs/name` against the root schema.
+fn resolve_reference<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Generate the Bicep index and type files for every DSC resource found on the system.
+///
+/// Resources whose schema can't be retrieved or parsed are skipped with a warning.
+///
+/// # Errors
+///
+/// This function will return an error if the types can't be serialized.
+pub fn generate_type_files() -> Result<(String, HashMap<String, String>), serde_json::Error> {
+    let mut dsc = DscManager::new();
+    let mut resources = dsc.list_available(&DiscoveryKind::Resource, &TypeNameFilter::default(), None, ProgressFormat::None);
+    resources.extend(dsc.list_available(&DiscoveryKind::Resource, &TypeNameFilter::default(), Some(&TypeNameFilter::default()), ProgressFormat::None));
+
+    let mut factory = TypeFactory::new();
+    for resource in &resources {
+        if let ImportedManifest::Resource(resource) = resource {
+            add_resource_types(&mut factory, resource);
+        }
+    }
+
+    tracing::debug!("{}", t!("types.typeFilesGenerated", count = factory.resources.len()));
+    let index = serde_json::to_string(&factory.index())?;
+    let types = serde_json::to_string(factory.types())?;
+    Ok((index, HashMap::from([(TYPES_FILE.to_string(), types)])))
+}
+
+fn add_resource_types(factory: &mut TypeFactory, resource: &DscResource) {
+    let schema = match resource.schema() {
+        Ok(schema) => schema,
+        Err(err) => {
+            tracing::warn!("{}", t!("types.schemaNotAvailable", resourceType = resource.type_name, error = err.to_string()));
+            return;
+        },
+    };
+    let schema = match serde_json::from_str::<Value>(&schema) {
+        Ok(schema) => schema,
+        Err(err) => {
+            tracing::warn!("{}", t!("types.invalidSchema", resourceType = resource.type_name, error = err.to_string()));
+            return;
+        },
+    };
+    tracing::debug!("{}", t!("types.addingResource", resourceType = resource.type_name, version = resource.version.to_string()));
+    factory.add_resource(resource.type_name.as_ref(), &resource.version.to_string(), &schema);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn property<'a>(factory: &'a TypeFactory, object: &TypeReference, name: &str) -> &'a ObjectTypeProperty {
+        let BicepType::ObjectType { properties, .. } = resolve(factory, object) else {
+            panic!("expected object type");
+        };
+        &properties[name]
+    }
+
+    fn resolve<'a>(factory: &'a TypeFactory, reference: &TypeReference) -> &'a BicepType {
+        let index = reference.reference.rsplit('/').next().unwrap().parse::<usize>().unwrap();
+        &factory.types()[index]
+    }
+
+    #[test]
+    fn resource_schema_properties() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string", "description": "The name." },
+                "id": { "type": "integer", "readOnly": true },
+                "secret": { "type": ["string", "null"], "writeOnly": true },
+                "ensure": { "type": "string", "enum": ["present", "absent"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "additionalProperties": false
+        });
+
+        let mut factory = TypeFactory::new();
+        factory.add_resource("Test/Resource", "1.0.0", &schema);
+        let index = factory.index();
+        let resource = &index.resources["Test/Resource@1.0.0"];
+        assert!(resource.reference.starts_with("types.json#/"));
+        let BicepType::ResourceType { body, .. } = resolve(&factory, resource) else {
+            panic!("expected resource type");
+        };
+
+        let name = property(&factory, body, "name");
+        assert_eq!(name.flags, PROPERTY_FLAG_REQUIRED | PROPERTY_FLAG_IDENTIFIER);
+        assert_eq!(name.description.as_deref(), Some("The name."));
+        assert_eq!(property(&factory, body, "id").flags, PROPERTY_FLAG_READ_ONLY);
+        let secret = property(&factory, body, "secret");
+        assert_eq!(secret.flags, PROPERTY_FLAG_WRITE_ONLY);
+        assert!(matches!(resolve(&factory, &secret.r#type), BicepType::StringType { .. }));
+
+        let BicepType::UnionType { elements } = resolve(&factory, &property(&factory, body, "ensure").r#type) else {
+            panic!("expected union type");
+        };
+        assert_eq!(resolve(&factory, &elements[1]), &BicepType::StringLiteralType { value: "absent".to_string() });
+
+        let BicepType::ArrayType { item_type, .. } = resolve(&factory, &property(&factory, body, "tags").r#type) else {
+            panic!("expected array type");
+        };
+        assert_eq!(item_type, &property(&factory, body, "name").r#type);
+    }
+
+    #[test]
+    fn marked_key_properties() {
+        let schema = json!({
+            "type": "object",
+            "required": ["path", "id"],
+            "properties": {
+                "path": { "type": "string" },
+                "name": { "type": "string", "x-dsc-key": true },
+                "id": { "type": "integer", "readOnly": true }
+            }
+        });
+        assert_eq!(key_properties(&schema), vec!["name".to_string()]);
+
+        let unmarked = json!({
+            "type": "object",
+            "required": ["path", "id"],
+            "properties": {
+                "path": { "type": "string" },
+                "id": { "type": "integer", "readOnly": true }
+            }
+        });
+        assert_eq!(key_properties(&unmarked), vec!["path".to_string()]);
+    }
+
+    #[test]
+    fn nested_and_recursive_definitions() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "node": { "$ref": "#/$defs/node" }
+            },
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "string" },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/node" } }
+                    }
+                }
+            }
+        });
+
+        let mut factory = TypeFactory::new();
+        factory.add_resource("Test/Tree", "0.1.0", &schema);
+        let resource = factory.index().resources["Test/Tree@0.1.0"].clone();
+        let BicepType::ResourceType { body, .. } = resolve(&factory, &resource) else {
+            panic!("expected resource type");
+        };
+        let node = property(&factory, body, "node").r#type.clone();
+        let BicepType::ObjectType { name, .. } = resolve(&factory, &node) else {
+            panic!("expected object type");
+        };
+        assert_eq!(name, "node");
+        let BicepType::ArrayType { item_type, .. } = resolve(&factory, &property(&factory, &node, "children").r#type) else {
+            panic!("expected array type");
+        };
+        assert_eq!(item_type, &node);
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! This build script generates the list of example configurations embedded in the MCP server
+//! from the `examples` folder, so examples added to the folder are exposed without updating a
+//! hand-maintained list.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// The file name suffix of the example configurations to embed.
+const EXAMPLE_SUFFIX: &str = ".dsc.yaml";
+
+/// The file name prefix of examples that are intentionally invalid and only used by tests.
+const INVALID_EXAMPLE_PREFIX: &str = "invalid_";
+
+fn main() {
+    let project_dir = env::var_os("CARGO_MANIFEST_DIR")
+        .expect("env var 'CARGO_MANIFEST_DIR' not defined");
+    let examples_dir = Path::new(&project_dir).join("examples");
+    let out_dir = env::var_os("OUT_DIR")
+        .expect("env var 'OUT_DIR' not defined");
+    let dest_path = Path::new(&out_dir).join("examples.rs");
+
+    let mut examples: Vec<String> = fs::read_dir(&examples_dir)
+        .expect("Failed to read examples folder")
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(EXAMPLE_SUFFIX) && !name.starts_with(INVALID_EXAMPLE_PREFIX))
+        .collect();
+    examples.sort();
+
+    let mut contents = String::from("&[\n");
+    for name in &examples {
+        let path = examples_dir.join(name);
+        contents.push_str(&format!("    ({name:?}, include_str!({:?})),\n", path.display().to_string()));
+    }
+    contents.push_str("]\n");
+
+    fs::write(&dest_path, contents).expect("Failed to write examples.rs");
+
+    println!("cargo::rerun-if-changed=build.rs");
+    println!("cargo::rerun-if-changed=examples");
+}
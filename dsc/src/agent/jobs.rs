@@ -0,0 +1,507 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use dsc_lib::{
+    DscManager,
+    configure::{
+        Configurator,
+        config_doc::ExecutionKind,
+        config_progress::{
+            ConfigurationProgressListener,
+            ConfigurationResourceCompletedEvent,
+            ConfigurationResourceCompletionStatus,
+            ConfigurationResourceStartedEvent,
+        },
+    },
+    discovery::discovery_trait::DiscoveryFilter,
+    dscerror::DscError,
+    dscresources::dscresource::Invoke,
+    progress::{Failure, Progress, ProgressBar, ProgressFormat},
+    scrub::{SecureValueScope, scrub, scrub_result, show_secure_values},
+    types::FullyQualifiedTypeName,
+};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, atomic::{AtomicBool, Ordering}},
+};
+use tokio::{sync::{broadcast, mpsc}, task};
+use tracing::{debug, info};
+
+/// The number of events buffered for each client streaming the events of a job.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// The number of events kept for each job, older events are dropped once the limit is reached.
+const MAX_JOB_EVENTS: usize = 1000;
+
+/// The number of finished jobs kept by the queue, the oldest are removed once the limit is reached.
+const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobOperation {
+    Get,
+    Set,
+    Test,
+    Export,
+}
+
+/// What a job operates on.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JobTarget {
+    /// A single resource instance.
+    Resource {
+        #[serde(rename = "type")]
+        resource_type: FullyQualifiedTypeName,
+        #[serde(default)]
+        properties: Option<Value>,
+    },
+    /// A configuration document as an object or a YAML or JSON string.
+    Configuration {
+        configuration: Value,
+        #[serde(default)]
+        parameters: Option<Value>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRequest {
+    pub operation: JobOperation,
+    #[serde(flatten)]
+    pub target: JobTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    #[must_use]
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum JobEventKind {
+    StatusChanged {
+        status: JobStatus,
+    },
+    ResourceStarted {
+        resource: String,
+        progress: Progress,
+    },
+    ResourceCompleted {
+        resource: String,
+        status: ConfigurationResourceCompletionStatus,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        errors: Option<Vec<String>>,
+        progress: Progress,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEvent {
+    /// The position of the event in the events of the job, starting at zero.
+    pub sequence: usize,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub kind: JobEventKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSummary {
+    pub id: String,
+    pub operation: JobOperation,
+    pub status: JobStatus,
+    pub submitted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<Progress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobResult {
+    pub id: String,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct JobState {
+    status: JobStatus,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+    progress: Option<Progress>,
+    /// The most recent events, limited to `MAX_JOB_EVENTS`.
+    events: VecDeque<JobEvent>,
+    next_sequence: usize,
+    /// Dropped when the job finishes so clients streaming events see the end of the stream.
+    sender: Option<broadcast::Sender<JobEvent>>,
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+pub struct Job {
+    pub id: String,
+    request: JobRequest,
+    submitted_at: String,
+    cancelled: Arc<AtomicBool>,
+    state: Mutex<JobState>,
+}
+
+impl Job {
+    fn new(request: JobRequest) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            submitted_at: now(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            state: Mutex::new(JobState {
+                status: JobStatus::Queued,
+                started_at: None,
+                completed_at: None,
+                progress: None,
+                events: VecDeque::new(),
+                next_sequence: 0,
+                sender: Some(sender),
+                result: None,
+                error: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, JobState> {
+        // a panic while holding the lock can't leave the state inconsistent so it's still used
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Get the current status of the job.
+    #[must_use]
+    pub fn summary(&self) -> JobSummary {
+        let state = self.lock();
+        JobSummary {
+            id: self.id.clone(),
+            operation: self.request.operation,
+            status: state.status,
+            submitted_at: self.submitted_at.clone(),
+            started_at: state.started_at.clone(),
+            completed_at: state.completed_at.clone(),
+            progress: state.progress.clone(),
+            error: state.error.clone(),
+        }
+    }
+
+    /// Get the result of the job, or `None` if the job hasn't finished.
+    #[must_use]
+    pub fn result(&self) -> Option<JobResult> {
+        let state = self.lock();
+        if !state.status.is_finished() {
+            return None;
+        }
+        Some(JobResult {
+            id: self.id.clone(),
+            status: state.status,
+            result: state.result.clone(),
+            error: state.error.clone(),
+        })
+    }
+
+    /// Get the retained events of the job so far and a receiver for the events that follow.
+    ///
+    /// Only the most recent events are retained, so the first event may not have sequence zero.
+    /// The receiver is closed once the job finishes.
+    #[must_use]
+    pub fn subscribe(&self) -> (Vec<JobEvent>, broadcast::Receiver<JobEvent>) {
+        let state = self.lock();
+        let receiver = match &state.sender {
+            Some(sender) => sender.subscribe(),
+            None => broadcast::channel(1).1,
+        };
+        (state.events.iter().cloned().collect(), receiver)
+    }
+
+    /// Request the job to stop, a running configuration job stops after the current resource instance.
+    ///
+    /// Returns `false` if the job has already finished or is a running resource job, which can't be stopped.
+    pub fn cancel(&self) -> bool {
+        let mut state = self.lock();
+        match state.status {
+            JobStatus::Queued => {
+                self.cancelled.store(true, Ordering::SeqCst);
+                Self::finish_locked(&mut state, JobStatus::Cancelled, None, Some(t!("agent.jobs.cancelled").to_string()));
+                true
+            },
+            JobStatus::Running if matches!(self.request.target, JobTarget::Configuration { .. }) => {
+                self.cancelled.store(true, Ordering::SeqCst);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn add_event(state: &mut JobState, kind: JobEventKind) {
+        let event = JobEvent {
+            sequence: state.next_sequence,
+            timestamp: now(),
+            kind,
+        };
+        state.next_sequence += 1;
+        if let Some(sender) = &state.sender {
+            // there may not be any clients streaming the events
+            let _ = sender.send(event.clone());
+        }
+        if state.events.len() == MAX_JOB_EVENTS {
+            state.events.pop_front();
+        }
+        state.events.push_back(event);
+    }
+
+    fn start(&self) -> bool {
+        let mut state = self.lock();
+        if state.status != JobStatus::Queued {
+            return false;
+        }
+        state.status = JobStatus::Running;
+        state.started_at = Some(now());
+        Self::add_event(&mut state, JobEventKind::StatusChanged { status: JobStatus::Running });
+        true
+    }
+
+    fn finish(&self, status: JobStatus, result: Option<Value>, error: Option<String>) {
+        Self::finish_locked(&mut self.lock(), status, result, error);
+    }
+
+    fn finish_locked(state: &mut JobState, status: JobStatus, result: Option<Value>, error: Option<String>) {
+        if state.status.is_finished() {
+            return;
+        }
+        state.status = status;
+        state.completed_at = Some(now());
+        state.result = result;
+        state.error = error;
+        Self::add_event(state, JobEventKind::StatusChanged { status });
+        state.sender = None;
+    }
+
+    fn run(self: &Arc<Self>) -> Result<Value, DscError> {
+        match &self.request.target {
+            JobTarget::Resource { resource_type, properties } => self.run_resource(resource_type, properties.as_ref()),
+            JobTarget::Configuration { configuration, parameters } => self.run_configuration(configuration, parameters.as_ref()),
+        }
+    }
+
+    fn run_resource(self: &Arc<Self>, resource_type: &FullyQualifiedTypeName, properties: Option<&Value>) -> Result<Value, DscError> {
+        let mut dsc = DscManager::new();
+        let Some(resource) = dsc.find_resource(&DiscoveryFilter::new(resource_type, None, None))? else {
+            return Err(DscError::ResourceNotFound(resource_type.to_string(), String::new()));
+        };
+        let input = properties.map_or_else(|| "{}".to_string(), Value::to_string);
+
+        // a single resource is reported with the same events as a configuration with one resource
+        let mut progress = ProgressBar::new(1, ProgressFormat::None)?;
+        progress.set_listener(Some(Arc::new(JobProgressListener { job: self.clone() })));
+        progress.set_resource(resource_type.as_ref(), resource_type);
+        let result = match self.request.operation {
+            JobOperation::Get => to_value(resource.get(&input)),
+            JobOperation::Set => to_value(resource.set(&input, false, &ExecutionKind::Actual)),
+            JobOperation::Test => to_value(resource.test(&input)),
+            JobOperation::Export => to_value(resource.export(&input)),
+        };
+        if let Err(err) = &result {
+            progress.set_failure(Some(Failure { message: err.to_string(), exit_code: 0 }));
+        }
+        progress.write_increment(1);
+        result
+    }
+
+    fn run_configuration(self: &Arc<Self>, configuration: &Value, parameters: Option<&Value>) -> Result<Value, DscError> {
+        let configuration = match configuration {
+            Value::String(document) => serde_json::to_string(&serde_yaml::from_str::<Value>(document)?)?,
+            document => document.to_string(),
+        };
+        let mut configurator = Configurator::new(&configuration, ProgressFormat::None)?;
+        configurator.context.dsc_version = Some(env!("CARGO_PKG_VERSION").to_string());
+        let parameters = parameters.map(|parameters| serde_json::json!({ "parameters": parameters }));
+        configurator.set_context(parameters.as_ref())?;
+        configurator.set_progress_listener(Arc::new(JobProgressListener { job: self.clone() }));
+        configurator.set_cancellation_flag(self.cancelled.clone());
+
+        match self.request.operation {
+            JobOperation::Get => to_value(configurator.invoke_get()),
+            JobOperation::Set => to_value(configurator.invoke_set(false)),
+            JobOperation::Test => to_value(configurator.invoke_test()),
+            JobOperation::Export => to_value(configurator.invoke_export()),
+        }
+    }
+}
+
+/// Records the progress of the resources processed by a job as events.
+struct JobProgressListener {
+    job: Arc<Job>,
+}
+
+impl JobProgressListener {
+    fn record(&self, progress: &Progress, kind: JobEventKind) {
+        let mut state = self.job.lock();
+        state.progress = Some(progress.clone());
+        Job::add_event(&mut state, kind);
+    }
+}
+
+impl ConfigurationProgressListener for JobProgressListener {
+    fn resource_started(&self, event: &ConfigurationResourceStartedEvent, progress: &Progress) {
+        self.record(progress, JobEventKind::ResourceStarted {
+            resource: event.resource.clone(),
+            progress: progress.clone(),
+        });
+    }
+
+    fn resource_completed(&self, event: &ConfigurationResourceCompletedEvent, progress: &Progress) {
+        self.record(progress, JobEventKind::ResourceCompleted {
+            resource: event.resource.clone(),
+            status: event.status.clone(),
+            errors: event.errors.as_ref().map(|errors| errors.iter().map(|error| scrub_error(error)).collect()),
+            progress: progress.clone(),
+        });
+    }
+}
+
+/// Runs submitted jobs one at a time in the order they were submitted.
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, Arc<Job>>>,
+    sender: mpsc::UnboundedSender<Arc<Job>>,
+}
+
+impl JobQueue {
+    /// Create the queue and start the task that runs its jobs.
+    ///
+    /// This must be called from within a tokio runtime.
+    #[must_use]
+    pub fn start() -> Arc<Self> {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Arc<Job>>();
+        tokio::spawn(async move {
+            while let Some(job) = receiver.recv().await {
+                if !job.start() {
+                    // cancelled while queued
+                    continue;
+                }
+                info!("{}", t!("agent.jobs.jobStarted", id = job.id));
+                let running = job.clone();
+                let result = task::spawn_blocking(move || {
+                    // the secure values used by the job are scrubbed from its result and forgotten once it completes
+                    let _scope = SecureValueScope::new();
+                    running.run().map(scrub_result).map_err(|err| scrub_error(&err.to_string()))
+                }).await;
+                match result {
+                    Ok(Ok(result)) => job.finish(JobStatus::Succeeded, Some(result), None),
+                    Ok(Err(err)) if job.cancelled.load(Ordering::SeqCst) => job.finish(JobStatus::Cancelled, None, Some(err)),
+                    Ok(Err(err)) => job.finish(JobStatus::Failed, None, Some(err)),
+                    Err(err) => job.finish(JobStatus::Failed, None, Some(err.to_string())),
+                }
+                info!("{}", t!("agent.jobs.jobFinished", id = job.id, status = job.summary().status : {:?}));
+            }
+        });
+
+        Arc::new(Self {
+            jobs: Mutex::new(HashMap::new()),
+            sender,
+        })
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Arc<Job>>> {
+        self.jobs.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Queue a job to run after the jobs submitted before it.
+    ///
+    /// The oldest finished jobs are removed so at most `MAX_FINISHED_JOBS` are kept.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the task running the jobs has stopped.
+    pub fn submit(&self, request: JobRequest) -> Result<Arc<Job>, DscError> {
+        let job = Arc::new(Job::new(request));
+        debug!("{}", t!("agent.jobs.jobSubmitted", id = job.id));
+        let mut jobs = self.jobs();
+        Self::prune(&mut jobs);
+        jobs.insert(job.id.clone(), job.clone());
+        drop(jobs);
+        self.sender.send(job.clone())
+            .map_err(|_| DscError::Operation(t!("agent.jobs.queueStopped").to_string()))?;
+        Ok(job)
+    }
+
+    fn prune(jobs: &mut HashMap<String, Arc<Job>>) {
+        let mut finished: Vec<(String, String)> = jobs.values()
+            .filter_map(|job| {
+                let state = job.lock();
+                if state.status.is_finished() {
+                    state.completed_at.clone().map(|completed_at| (completed_at, job.id.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if finished.len() < MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        for (_, id) in finished.iter().take(finished.len() + 1 - MAX_FINISHED_JOBS) {
+            debug!("{}", t!("agent.jobs.jobRemoved", id = id));
+            jobs.remove(id);
+        }
+    }
+
+    /// Get a job by its identifier.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.jobs().get(id).cloned()
+    }
+
+    /// Get the status of every job, oldest first.
+    #[must_use]
+    pub fn list(&self) -> Vec<JobSummary> {
+        let mut jobs: Vec<JobSummary> = self.jobs().values().map(|job| job.summary()).collect();
+        jobs.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at));
+        jobs
+    }
+}
+
+fn scrub_error(error: &str) -> String {
+    if show_secure_values() {
+        error.to_string()
+    } else {
+        scrub(error).into_owned()
+    }
+}
+
+fn to_value<T: Serialize>(result: Result<T, DscError>) -> Result<Value, DscError> {
+    Ok(serde_json::to_value(result?)?)
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
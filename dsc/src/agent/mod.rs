@@ -0,0 +1,114 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use dsc_lib::dscerror::DscError;
+use rust_i18n::t;
+use std::path::PathBuf;
+
+pub mod jobs;
+pub mod routes;
+
+/// The file name of the agent socket in the runtime directory.
+const SOCKET_FILE_NAME: &str = "dsc-agent.sock";
+
+/// Get the default path of the agent socket.
+///
+/// The socket is created in `XDG_RUNTIME_DIR` if set, otherwise in the temporary directory.
+#[must_use]
+pub fn default_socket_path() -> PathBuf {
+    let directory = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(directory) if !directory.is_empty() => PathBuf::from(directory),
+        _ => std::env::temp_dir(),
+    };
+    directory.join(SOCKET_FILE_NAME)
+}
+
+/// Serve the agent API on a Unix socket until the process is stopped.
+///
+/// # Arguments
+///
+/// * `socket` - The path of the socket, the default path is used if `None`.
+///
+/// # Errors
+///
+/// This function will return an error if the socket can't be created or the server fails.
+#[cfg(unix)]
+pub async fn start_agent_async(socket: Option<&str>) -> Result<(), DscError> {
+    use tracing::info;
+
+    let path = socket.map_or_else(default_socket_path, PathBuf::from);
+    let listener = bind_socket(&path)?;
+
+    let router = routes::router(jobs::JobQueue::start());
+    info!("{}", t!("agent.mod.listening", path = path.display()));
+    let result = axum::serve(listener, router).await
+        .map_err(|err| DscError::Operation(t!("agent.mod.serverFailed", error = err.to_string()).to_string()));
+    let _ = std::fs::remove_file(&path);
+    result?;
+
+    info!("{}", t!("agent.mod.agentStopped"));
+    Ok(())
+}
+
+/// Create the socket so only the user running the agent can ever connect to it.
+///
+/// The socket is bound inside a new directory only the user can access and moved
+/// to its path once its permissions are restricted.
+#[cfg(unix)]
+fn bind_socket(path: &std::path::Path) -> Result<tokio::net::UnixListener, DscError> {
+    use std::fs::{DirBuilder, Permissions};
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use tokio::net::UnixListener;
+    use tracing::debug;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            // a socket left behind by an agent that didn't shut down cleanly
+            debug!("{}", t!("agent.mod.removingSocket", path = path.display()));
+            std::fs::remove_file(path)?;
+        },
+        Ok(_) => return Err(DscError::Operation(t!("agent.mod.notASocket", path = path.display()).to_string())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {},
+        Err(err) => return Err(err.into()),
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let private_directory = parent.join(format!(".{SOCKET_FILE_NAME}.{}", std::process::id()));
+    DirBuilder::new().mode(0o700).create(&private_directory)?;
+    let private_path = private_directory.join(SOCKET_FILE_NAME);
+    let result = UnixListener::bind(&private_path)
+        .map_err(|err| DscError::Operation(t!("agent.mod.failedToBind", path = path.display(), error = err.to_string()).to_string()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&private_path, Permissions::from_mode(0o600))?;
+            std::fs::rename(&private_path, path)?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_file(&private_path);
+    let _ = std::fs::remove_dir(&private_directory);
+    result
+}
+
+/// Serve the agent API on a Unix socket until the process is stopped.
+///
+/// # Errors
+///
+/// This function always returns an error as the agent requires Unix sockets.
+#[cfg(not(unix))]
+pub async fn start_agent_async(_socket: Option<&str>) -> Result<(), DscError> {
+    Err(DscError::NotSupported(t!("agent.mod.unixSocketRequired").to_string()))
+}
+
+/// Synchronous wrapper to start the agent
+///
+/// # Errors
+///
+/// This function will return an error if the agent fails to start or if the tokio runtime cannot be created.
+pub fn start_agent(socket: Option<&str>) -> Result<(), DscError> {
+    let rt = tokio::runtime::Runtime::new()
+        .map_err(|err| DscError::Operation(t!("agent.mod.failedToCreateRuntime", error = err.to_string()).to_string()))?;
+
+    rt.block_on(start_agent_async(socket))
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::agent::jobs::{JobQueue, JobRequest};
+use axum::{
+    Json,
+    Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response, sse::{Event, Sse}},
+    routing::{get, post},
+};
+use rust_i18n::t;
+use serde_json::json;
+use std::{convert::Infallible, sync::Arc};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+
+/// The path jobs are submitted to and listed from.
+pub const JOBS_ENDPOINT: &str = "/v1/jobs";
+
+/// Create the routes of the agent API.
+///
+/// * `POST /v1/jobs` - Submit a job, returns its status.
+/// * `GET /v1/jobs` - List the status of every job.
+/// * `GET /v1/jobs/{id}` - Get the status of a job.
+/// * `DELETE /v1/jobs/{id}` - Cancel a job, a running job stops after the current resource instance.
+/// * `GET /v1/jobs/{id}/events` - Stream the progress events of a job as server-sent events.
+/// * `GET /v1/jobs/{id}/result` - Get the result of a finished job.
+pub fn router(queue: Arc<JobQueue>) -> Router {
+    Router::new()
+        .route(JOBS_ENDPOINT, post(submit_job).get(list_jobs))
+        .route(&format!("{JOBS_ENDPOINT}/{{id}}"), get(get_job).delete(cancel_job))
+        .route(&format!("{JOBS_ENDPOINT}/{{id}}/events"), get(job_events))
+        .route(&format!("{JOBS_ENDPOINT}/{{id}}/result"), get(job_result))
+        .with_state(queue)
+}
+
+async fn submit_job(State(queue): State<Arc<JobQueue>>, Json(request): Json<JobRequest>) -> Response {
+    match queue.submit(request) {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job.summary())).into_response(),
+        Err(err) => error(StatusCode::SERVICE_UNAVAILABLE, &err.to_string()),
+    }
+}
+
+async fn list_jobs(State(queue): State<Arc<JobQueue>>) -> Response {
+    Json(queue.list()).into_response()
+}
+
+async fn get_job(State(queue): State<Arc<JobQueue>>, Path(id): Path<String>) -> Response {
+    match queue.get(&id) {
+        Some(job) => Json(job.summary()).into_response(),
+        None => not_found(&id),
+    }
+}
+
+async fn cancel_job(State(queue): State<Arc<JobQueue>>, Path(id): Path<String>) -> Response {
+    let Some(job) = queue.get(&id) else {
+        return not_found(&id);
+    };
+    if !job.cancel() {
+        return (StatusCode::CONFLICT, Json(job.summary())).into_response();
+    }
+    (StatusCode::ACCEPTED, Json(job.summary())).into_response()
+}
+
+async fn job_result(State(queue): State<Arc<JobQueue>>, Path(id): Path<String>) -> Response {
+    let Some(job) = queue.get(&id) else {
+        return not_found(&id);
+    };
+    match job.result() {
+        Some(result) => Json(result).into_response(),
+        // the job hasn't finished so its status is returned instead
+        None => (StatusCode::CONFLICT, Json(job.summary())).into_response(),
+    }
+}
+
+async fn job_events(State(queue): State<Arc<JobQueue>>, Path(id): Path<String>) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    let Some(job) = queue.get(&id) else {
+        return Err(not_found(&id));
+    };
+
+    // events that already happened are sent first, the stream ends when the job finishes
+    let (history, receiver) = job.subscribe();
+    let events = tokio_stream::iter(history)
+        .chain(BroadcastStream::new(receiver).filter_map(Result::ok))
+        .map(|event| {
+            let data = serde_json::to_string(&event).unwrap_or_default();
+            Ok(Event::default().id(event.sequence.to_string()).data(data))
+        });
+    Ok(Sse::new(events))
+}
+
+fn not_found(id: &str) -> Response {
+    error(StatusCode::NOT_FOUND, &t!("agent.routes.jobNotFound", id = id))
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
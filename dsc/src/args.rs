@@ -7,9 +7,11 @@ use dsc_lib::dscresources::command_resource::TraceLevel;
 use dsc_lib::functions::FunctionCategory;
 use dsc_lib::progress::ProgressFormat;
 use dsc_lib::types::{FullyQualifiedTypeName, ResourceVersionReq, TypeNameFilter};
+use crate::audit::AuditOperation;
+use crate::server::policy::ServerMode;
 use rust_i18n::t;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
 pub enum OutputFormat {
@@ -44,6 +46,12 @@ pub enum TraceFormat {
     PassThrough,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ServerTransport {
+    Stdio,
+    Http,
+}
+
 #[derive(Debug, Parser)]
 #[clap(name = "dsc", version = env!("CARGO_PKG_VERSION"), about = t!("args.about").to_string(), long_about = None)]
 pub struct Args {
@@ -74,9 +82,13 @@ pub enum SubCommand {
         #[clap(short, long, help = t!("args.parameters").to_string())]
         parameters: Option<String>,
         #[clap(short = 'f', long, help = t!("args.parametersFile").to_string())]
-        parameters_file: Option<String>,
+        parameters_file: Vec<String>,
+        #[clap(long, help = t!("args.printEffectiveParameters").to_string())]
+        print_effective_parameters: bool,
         #[clap(short = 'r', long, help = t!("args.systemRoot").to_string())]
         system_root: Option<String>,
+        #[clap(long, help = t!("args.lockFile").to_string())]
+        lock_file: Option<String>,
         // Used to inform when DSC is used as a group resource to modify it's output
         #[clap(long, hide = true)]
         as_group: bool,
@@ -97,7 +109,38 @@ pub enum SubCommand {
         subcommand: FunctionSubCommand,
     },
     #[clap(name = "server", alias = "mcp", about = t!("args.serverAbout").to_string())]
-    Server,
+    Server {
+        #[clap(long, value_enum, default_value_t = ServerTransport::Stdio, help = t!("args.serverTransport").to_string())]
+        transport: ServerTransport,
+        #[clap(long, default_value = "127.0.0.1:8080", help = t!("args.serverListen").to_string())]
+        listen: String,
+        #[clap(long, help = t!("args.serverAllowRemote").to_string())]
+        allow_remote: bool,
+        #[clap(long, value_enum, help = t!("args.serverMode").to_string())]
+        mode: Option<ServerMode>,
+        #[clap(long = "allow-resource", help = t!("args.serverAllowResource").to_string())]
+        allow_resources: Vec<TypeNameFilter>,
+    },
+    #[clap(name = "agent", about = t!("args.agentAbout").to_string())]
+    Agent {
+        #[clap(long, help = t!("args.agentSocket").to_string())]
+        socket: Option<String>,
+    },
+    #[clap(name = "history", about = t!("args.historyAbout").to_string())]
+    History {
+        #[clap(long, help = t!("args.historyPath").to_string())]
+        path: Option<String>,
+        #[clap(short = 'r', long = "resource", help = t!("args.historyResource").to_string())]
+        resource_type: Option<TypeNameFilter>,
+        #[clap(long, value_enum, help = t!("args.historyOperation").to_string())]
+        operation: Option<AuditOperation>,
+        #[clap(long, help = t!("args.historySince").to_string())]
+        since: Option<String>,
+        #[clap(short = 'n', long, help = t!("args.historyLast").to_string())]
+        last: Option<usize>,
+        #[clap(short = 'o', long, help = t!("args.outputFormat").to_string())]
+        output_format: Option<OutputFormat>,
+    },
     #[clap(name = "resource", about = t!("args.resourceAbout").to_string())]
     Resource {
         #[clap(subcommand)]
@@ -110,6 +153,15 @@ pub enum SubCommand {
         #[clap(short = 'o', long, help = t!("args.outputFormat").to_string(), value_enum)]
         output_format: Option<OutputFormat>,
     },
+    #[clap(name = "secret", about = t!("args.secretAbout").to_string())]
+    Secret {
+        #[clap(long, global = true, help = t!("args.secretStorePath").to_string())]
+        path: Option<String>,
+        #[clap(long, global = true, help = t!("args.secretKeyFile").to_string())]
+        key_file: Option<String>,
+        #[clap(subcommand)]
+        subcommand: SecretSubCommand,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Subcommand)]
@@ -167,6 +219,34 @@ pub enum ConfigSubCommand {
         #[clap(short = 'o', long, help = t!("args.outputFormat").to_string())]
         output_format: Option<OutputFormat>,
     },
+    #[clap(name = "lock", about = t!("args.lockAbout").to_string())]
+    Lock {
+        #[clap(short = 'i', long, help = t!("args.input").to_string(), conflicts_with = "file")]
+        input: Option<String>,
+        #[clap(short = 'f', long, help = t!("args.file").to_string(), conflicts_with = "input")]
+        file: Option<String>,
+        #[clap(short = 'o', long, help = t!("args.outputFormat").to_string())]
+        output_format: Option<OutputFormat>,
+    },
+    #[clap(name = "watch", about = t!("args.watchAbout").to_string())]
+    Watch {
+        #[clap(short = 'i', long, help = t!("args.input").to_string(), conflicts_with = "file")]
+        input: Option<String>,
+        #[clap(short = 'f', long, help = t!("args.file").to_string(), conflicts_with = "input")]
+        file: Option<String>,
+        #[clap(short = 'o', long, help = t!("args.outputFormat").to_string())]
+        output_format: Option<OutputFormat>,
+        #[clap(short = 'm', long, value_enum, default_value_t = WatchMode::Monitor, help = t!("args.watchMode").to_string())]
+        mode: WatchMode,
+        #[clap(long, default_value_t = 1800, value_parser = clap::value_parser!(u64).range(1..), help = t!("args.watchInterval").to_string())]
+        interval: u64,
+        #[clap(long, help = t!("args.watchCount").to_string())]
+        count: Option<u64>,
+        #[clap(long, help = t!("args.watchHistory").to_string())]
+        history: Option<String>,
+        #[clap(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..), help = t!("args.watchHistorySize").to_string())]
+        history_size: u64,
+    },
     #[clap(name = "resolve", about = t!("args.resolveAbout").to_string(), hide = true)]
     Resolve {
         #[clap(short = 'i', long, help = t!("args.input").to_string(), conflicts_with = "file")]
@@ -178,6 +258,17 @@ pub enum ConfigSubCommand {
     }
 }
 
+/// How `config watch` handles a configuration that isn't in the desired state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchMode {
+    /// Drift is only recorded.
+    Monitor,
+    /// Drift is recorded and corrected by setting the configuration.
+    #[value(alias = "applyAndAutoCorrect")]
+    ApplyAndAutoCorrect,
+}
+
 #[derive(Debug, PartialEq, Eq, Subcommand)]
 pub enum ExtensionSubCommand {
     #[clap(name = "list", about = t!("args.listExtensionAbout").to_string())]
@@ -190,6 +281,41 @@ pub enum ExtensionSubCommand {
     },
 }
 
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum SecretSubCommand {
+    #[clap(name = "set", about = t!("args.secretSetAbout").to_string(), long_about = t!("args.secretSetLongAbout").to_string())]
+    Set {
+        #[clap(short, long, help = t!("args.secretName").to_string())]
+        name: String,
+        #[clap(short, long, help = t!("args.secretVault").to_string())]
+        vault: Option<String>,
+    },
+    #[clap(name = "get", about = t!("args.secretGetAbout").to_string())]
+    Get {
+        #[clap(short, long, help = t!("args.secretName").to_string())]
+        name: String,
+        #[clap(short, long, help = t!("args.secretVault").to_string())]
+        vault: Option<String>,
+        // used by the extension manifest so a missing store or secret isn't an error
+        #[clap(long, hide = true)]
+        as_extension: bool,
+    },
+    #[clap(name = "list", about = t!("args.secretListAbout").to_string())]
+    List {
+        #[clap(short, long, help = t!("args.secretListVault").to_string())]
+        vault: Option<String>,
+        #[clap(short = 'o', long, help = t!("args.outputFormat").to_string())]
+        output_format: Option<OutputFormat>,
+    },
+    #[clap(name = "remove", about = t!("args.secretRemoveAbout").to_string())]
+    Remove {
+        #[clap(short, long, help = t!("args.secretName").to_string())]
+        name: String,
+        #[clap(short, long, help = t!("args.secretVault").to_string())]
+        vault: Option<String>,
+    },
+}
+
 #[derive(Debug, PartialEq, Eq, Subcommand)]
 pub enum FunctionSubCommand {
     #[clap(name = "list", about = t!("args.listFunctionAbout").to_string())]
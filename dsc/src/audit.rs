@@ -0,0 +1,484 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::args::OutputFormat;
+use crate::util::{EXIT_DSC_ERROR, EXIT_INVALID_ARGS, EXIT_JSON_ERROR, get_local_data_path, write_object};
+use clap::ValueEnum;
+use dsc_lib::{
+    configure::{
+        Configurator,
+        config_doc::{Configuration, ExecutionKind, SecurityContextKind},
+        config_result::ResourceSetResult,
+        context::Context,
+    },
+    dscerror::DscError,
+    dscresources::{
+        dscresource::redact,
+        invoke_result::{ResourceSetResponse, SetResult},
+    },
+    scrub::scrub_value,
+    types::{FullyQualifiedTypeName, TypeNameFilter},
+    util::get_setting,
+};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use sha2::{Digest, Sha256};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users, get_current_pid};
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::LazyLock,
+};
+use tracing::{debug, error, warn};
+
+/// The name of the setting configuring the audit log.
+pub const AUDIT_LOG_SETTING: &str = "auditLog";
+/// The environment variable that enables the audit log at the given path.
+pub const DSC_AUDIT_LOG: &str = "DSC_AUDIT_LOG";
+
+const AUDIT_LOG_FILE_NAME: &str = "audit.jsonl";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AuditLogSetting {
+    /// Whether operations are recorded, the audit log is opt-in.
+    #[serde(default)]
+    enabled: bool,
+    /// The path of the log, defaults to `audit.jsonl` in the DSC local data directory.
+    path: Option<String>,
+    /// The size in kilobytes at which the log is rotated.
+    #[serde(default = "default_max_size_kb")]
+    max_size_kb: u64,
+    /// The number of rotated logs to keep.
+    #[serde(default = "default_max_files")]
+    max_files: u32,
+}
+
+impl Default for AuditLogSetting {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_size_kb: default_max_size_kb(),
+            max_files: default_max_files(),
+        }
+    }
+}
+
+fn default_max_size_kb() -> u64 {
+    10240
+}
+
+fn default_max_files() -> u32 {
+    5
+}
+
+/// The operations recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOperation {
+    Set,
+    Delete,
+}
+
+/// A change to a resource instance recorded in the audit log.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub security_context: SecurityContextKind,
+    pub operation: AuditOperation,
+    pub execution_type: ExecutionKind,
+    #[serde(rename = "type")]
+    pub resource_type: FullyQualifiedTypeName,
+    /// The name of the resource instance in the configuration, not set for resource commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The before and after value of each changed property with secure values redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_properties: Option<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    /// The hash of the configuration document, not set for resource commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+}
+
+impl AuditRecord {
+    fn new(operation: AuditOperation, execution_type: ExecutionKind, security_context: SecurityContextKind, resource_type: FullyQualifiedTypeName) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            user: CURRENT_USER.clone(),
+            security_context,
+            operation,
+            execution_type,
+            resource_type,
+            name: None,
+            changed_properties: None,
+            duration: None,
+            config_hash: None,
+        }
+    }
+}
+
+/// An append-only JSON lines file recording changes made by DSC, rotated by size.
+pub struct AuditLog {
+    path: PathBuf,
+    max_size: u64,
+    max_files: u32,
+}
+
+impl AuditLog {
+    /// Open the audit log if it's enabled by the `auditLog` setting or the `DSC_AUDIT_LOG` environment variable.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the setting is invalid or the path of the log can't be determined.
+    pub fn open() -> Result<Option<Self>, DscError> {
+        let mut setting = get_audit_log_setting()?;
+        if let Ok(path) = env::var(DSC_AUDIT_LOG) && !path.is_empty() {
+            setting.enabled = true;
+            setting.path = Some(path);
+        }
+        if !setting.enabled {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(setting.path.as_deref(), setting.max_size_kb, setting.max_files)?))
+    }
+
+    /// Get the audit log at the configured path whether or not it's enabled, used to read it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the log, overrides the configured path.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the setting is invalid or the path of the log can't be determined.
+    pub fn configured(path: Option<&str>) -> Result<Self, DscError> {
+        let setting = get_audit_log_setting()?;
+        let env_path = env::var(DSC_AUDIT_LOG).ok().filter(|path| !path.is_empty());
+        let path = path.or(env_path.as_deref()).or(setting.path.as_deref());
+        Self::new(path, setting.max_size_kb, setting.max_files)
+    }
+
+    fn new(path: Option<&str>, max_size_kb: u64, max_files: u32) -> Result<Self, DscError> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => get_local_data_path()
+                .ok_or_else(|| DscError::Operation(t!("audit.noLogPath").to_string()))?
+                .join(AUDIT_LOG_FILE_NAME),
+        };
+        Ok(Self { path, max_size: max_size_kb.saturating_mul(1024), max_files })
+    }
+
+    /// Get the path of the current log file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append records to the log, rotating it first if it has reached the maximum size.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the log can't be written.
+    pub fn write(&self, records: &[AuditRecord]) -> Result<(), DscError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() && !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::metadata(&self.path).is_ok_and(|metadata| metadata.len() >= self.max_size) {
+            self.rotate()?;
+        }
+
+        let mut lines = String::new();
+        for record in records {
+            // the log is persisted so secure values are always scrubbed
+            lines.push_str(&serde_json::to_string(&scrub_value(&serde_json::to_value(record)?))?);
+            lines.push('\n');
+        }
+        // a single write so records from concurrent processes aren't interleaved
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        debug!("{}", t!("audit.recordsWritten", count = records.len(), path = self.path.display()));
+        Ok(())
+    }
+
+    /// Get the path of a rotated log, `audit.1.jsonl` being the most recent.
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let file_name = match self.path.extension() {
+            Some(extension) => format!("{stem}.{index}.{}", extension.to_string_lossy()),
+            None => format!("{stem}.{index}"),
+        };
+        self.path.with_file_name(file_name)
+    }
+
+    fn rotate(&self) -> Result<(), DscError> {
+        debug!("{}", t!("audit.rotating", path = self.path.display()));
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+            return Ok(());
+        }
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let rotated = self.rotated_path(index);
+            if rotated.exists() {
+                fs::rename(&rotated, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+        Ok(())
+    }
+
+    /// Read the records in the log including the rotated logs, oldest first.
+    ///
+    /// Lines that aren't valid records are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a log can't be read.
+    pub fn read(&self) -> Result<Vec<AuditRecord>, DscError> {
+        let mut paths: Vec<PathBuf> = (1..=self.max_files).rev().map(|index| self.rotated_path(index)).collect();
+        paths.push(self.path.clone());
+
+        let mut records = Vec::new();
+        for path in paths.iter().filter(|path| path.exists()) {
+            for line in fs::read_to_string(path)?.lines().filter(|line| !line.trim().is_empty()) {
+                match serde_json::from_str::<AuditRecord>(line) {
+                    Ok(record) => records.push(record),
+                    Err(err) => warn!("{}", t!("audit.invalidRecord", path = path.display(), error = err.to_string())),
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+fn get_audit_log_setting() -> Result<AuditLogSetting, DscError> {
+    let Ok(v) = get_setting(AUDIT_LOG_SETTING) else {
+        return Ok(AuditLogSetting::default());
+    };
+
+    // if there is a policy value defined - use it; otherwise use setting value
+    let value = if v.policy != Value::Null {
+        v.policy
+    } else if v.setting != Value::Null {
+        v.setting
+    } else {
+        return Ok(AuditLogSetting::default());
+    };
+
+    serde_json::from_value::<AuditLogSetting>(value)
+        .map_err(|err| DscError::Setting(t!("audit.invalidSetting", error = err.to_string()).to_string()))
+}
+
+static CURRENT_USER: LazyLock<Option<String>> = LazyLock::new(current_user);
+
+/// Get the name of the user running the process from the OS rather than the environment, which the caller controls.
+fn current_user() -> Option<String> {
+    let pid = get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, ProcessRefreshKind::nothing().with_user(UpdateKind::Always));
+    let uid = system.process(pid)?.user_id()?;
+    let users = Users::new_with_refreshed_list();
+    // fall back to the id when the user can't be looked up, like a domain account
+    Some(users.get_user_by_id(uid).map_or_else(|| uid.to_string(), |user| user.name().to_string()))
+}
+
+/// Get the hash of a configuration document.
+fn config_hash(configuration: &Configuration) -> Option<String> {
+    let json = serde_json::to_string(configuration).ok()?;
+    Some(format!("sha256:{:x}", Sha256::digest(json.as_bytes())))
+}
+
+/// Get the before and after value of the properties changed by set.
+fn changed_properties(response: &ResourceSetResponse) -> Option<Map<String, Value>> {
+    let changed = response.changed_properties.as_ref()?;
+    let before = redact(&response.before_state);
+    let after = redact(&response.after_state);
+    let changes = changed.iter()
+        .map(|property| (property.clone(), json!({
+            "before": before.get(property).cloned().unwrap_or(Value::Null),
+            "after": after.get(property).cloned().unwrap_or(Value::Null),
+        })))
+        .collect();
+    Some(changes)
+}
+
+fn write_records(records: &[AuditRecord]) {
+    match AuditLog::open() {
+        Ok(Some(log)) => {
+            // failing to audit doesn't fail the operation that has already run
+            if let Err(err) = log.write(records) {
+                warn!("{}", t!("audit.failedToWrite", path = log.path().display(), error = err.to_string()));
+            }
+        },
+        Ok(None) => {},
+        Err(err) => warn!("{err}"),
+    }
+}
+
+fn add_set_records(records: &mut Vec<AuditRecord>, results: &[ResourceSetResult], configurator: &Configurator, hash: Option<&String>) {
+    for result in results {
+        match &result.result {
+            SetResult::Resource(response) => {
+                let mut record = AuditRecord::new(
+                    AuditOperation::Set,
+                    configurator.context.execution_type.clone(),
+                    configurator.context.security_context.clone(),
+                    result.resource_type.clone(),
+                );
+                record.name = Some(result.name.clone());
+                record.changed_properties = changed_properties(response);
+                record.duration = result.execution_information.as_ref().and_then(|info| info.duration.clone());
+                record.config_hash = hash.cloned();
+                records.push(record);
+            },
+            SetResult::Group(group_results) => add_set_records(records, group_results, configurator, hash),
+        }
+    }
+}
+
+/// Record the resource instances set by a configuration in the audit log if it's enabled.
+///
+/// # Arguments
+///
+/// * `configurator` - The configurator that invoked set.
+/// * `results` - The results of the resource instances.
+pub fn audit_config_set(configurator: &Configurator, results: &[ResourceSetResult]) {
+    let hash = config_hash(configurator.get_config());
+    let mut records = Vec::new();
+    add_set_records(&mut records, results, configurator, hash.as_ref());
+    write_records(&records);
+}
+
+/// Record a resource instance set by `resource set` in the audit log if it's enabled.
+///
+/// # Arguments
+///
+/// * `resource_type` - The type of the resource.
+/// * `result` - The result of the set operation.
+/// * `execution_type` - Whether set was invoked as what-if.
+/// * `duration` - The duration of the operation.
+pub fn audit_resource_set(resource_type: &FullyQualifiedTypeName, result: &SetResult, execution_type: &ExecutionKind, duration: chrono::Duration) {
+    let security_context = Context::new().security_context;
+    let mut records = Vec::new();
+    match result {
+        SetResult::Resource(response) => {
+            let mut record = AuditRecord::new(AuditOperation::Set, execution_type.clone(), security_context, resource_type.clone());
+            record.changed_properties = changed_properties(response);
+            record.duration = Some(duration.to_string());
+            records.push(record);
+        },
+        SetResult::Group(group_results) => add_group_set_records(&mut records, group_results, execution_type, &security_context),
+    }
+    write_records(&records);
+}
+
+fn add_group_set_records(records: &mut Vec<AuditRecord>, results: &[ResourceSetResult], execution_type: &ExecutionKind, security_context: &SecurityContextKind) {
+    for result in results {
+        match &result.result {
+            SetResult::Resource(response) => {
+                let mut record = AuditRecord::new(AuditOperation::Set, execution_type.clone(), security_context.clone(), result.resource_type.clone());
+                record.name = Some(result.name.clone());
+                record.changed_properties = changed_properties(response);
+                record.duration = result.execution_information.as_ref().and_then(|info| info.duration.clone());
+                records.push(record);
+            },
+            SetResult::Group(group_results) => add_group_set_records(records, group_results, execution_type, security_context),
+        }
+    }
+}
+
+/// Record a resource instance deleted by `resource delete` in the audit log if it's enabled.
+///
+/// # Arguments
+///
+/// * `resource_type` - The type of the resource.
+/// * `input` - The properties identifying the deleted instance.
+/// * `execution_type` - Whether delete was invoked as what-if.
+/// * `duration` - The duration of the operation.
+pub fn audit_resource_delete(resource_type: &FullyQualifiedTypeName, input: &str, execution_type: &ExecutionKind, duration: chrono::Duration) {
+    let mut record = AuditRecord::new(AuditOperation::Delete, execution_type.clone(), Context::new().security_context, resource_type.clone());
+    if let Ok(Value::Object(properties)) = serde_json::from_str::<Value>(input) {
+        let properties = redact(&Value::Object(properties));
+        record.changed_properties = properties.as_object().map(|properties| {
+            properties.iter()
+                .map(|(name, value)| (name.clone(), json!({ "before": value, "after": Value::Null })))
+                .collect()
+        });
+    }
+    record.duration = Some(duration.to_string());
+    write_records(&[record]);
+}
+
+/// Write the records in the audit log matching the filters, oldest first.
+///
+/// # Arguments
+///
+/// * `path` - The path of the log, overrides the configured path.
+/// * `resource_type` - Only records for matching resource types are written.
+/// * `operation` - Only records for the operation are written.
+/// * `since` - Only records at or after the RFC 3339 date and time are written.
+/// * `last` - Only the most recent number of matching records are written.
+/// * `format` - The output format.
+pub fn history(path: Option<&String>, resource_type: Option<&TypeNameFilter>, operation: Option<AuditOperation>, since: Option<&String>, last: Option<usize>, format: Option<&OutputFormat>) {
+    let since = match since.map(|since| chrono::DateTime::parse_from_rfc3339(since)).transpose() {
+        Ok(since) => since,
+        Err(err) => {
+            error!("{}", t!("audit.invalidSince", error = err.to_string()));
+            exit(EXIT_INVALID_ARGS);
+        }
+    };
+    let log = match AuditLog::configured(path.map(String::as_str)) {
+        Ok(log) => log,
+        Err(err) => {
+            error!("{err}");
+            exit(EXIT_DSC_ERROR);
+        }
+    };
+    let records = match log.read() {
+        Ok(records) => records,
+        Err(err) => {
+            error!("{}", t!("audit.failedToRead", path = log.path().display(), error = err.to_string()));
+            exit(EXIT_DSC_ERROR);
+        }
+    };
+
+    let mut records: Vec<AuditRecord> = records.into_iter()
+        .filter(|record| resource_type.is_none_or(|filter| filter.is_match(&record.resource_type)))
+        .filter(|record| operation.is_none_or(|operation| record.operation == operation))
+        .filter(|record| since.is_none_or(|since| {
+            chrono::DateTime::parse_from_rfc3339(&record.timestamp).is_ok_and(|timestamp| timestamp >= since)
+        }))
+        .collect();
+    if let Some(last) = last && records.len() > last {
+        records.drain(..records.len() - last);
+    }
+
+    let mut include_separator = false;
+    for record in &records {
+        let json = match serde_json::to_string(record) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("JSON Error: {err}");
+                exit(EXIT_JSON_ERROR);
+            }
+        };
+        write_object(&json, format, include_separator);
+        include_separator = true;
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::resolve::normalize_path;
+use crate::util::get_local_data_path;
+use rust_i18n::t;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info};
+
+/// The environment variable that overrides the directory remote include sources are cached in.
+pub const DSC_INCLUDE_CACHE_PATH: &str = "DSC_INCLUDE_CACHE_PATH";
+
+const INCLUDE_CACHE_DIR: &str = "includeCache";
+
+/// Only these transports can fetch a repository so a source can't run commands through transports like `ext`.
+const GIT_PROTOCOL_CONFIG: [&str; 6] = ["-c", "protocol.allow=never", "-c", "protocol.https.allow=always", "-c", "protocol.ssh.allow=always"];
+
+/// Allows fetching from a repository that is a local path.
+const GIT_FILE_PROTOCOL_CONFIG: [&str; 2] = ["-c", "protocol.file.allow=always"];
+
+/// A configuration file in a git repository at a pinned commit.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct GitSource {
+    /// The URL or local path of the git repository.
+    pub repository: String,
+    /// The full hash of the commit to read the configuration file from.
+    pub commit: String,
+    /// The path of the configuration file within the repository.
+    pub path: String,
+    /// The SHA-256 hash of the configuration file content, optionally prefixed with `sha256:`.
+    pub sha256: String,
+}
+
+/// A configuration file in a `.tar.gz` archive on the local file system or an HTTP server.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ArchiveSource {
+    /// The HTTP(S) URL or local path of the archive.
+    pub uri: String,
+    /// The path of the configuration file within the archive.
+    pub path: String,
+    /// The SHA-256 hash of the archive, optionally prefixed with `sha256:`.
+    pub sha256: String,
+}
+
+fn get_cache_path(kind: &str) -> Result<PathBuf, String> {
+    let path = match env::var(DSC_INCLUDE_CACHE_PATH) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => get_local_data_path()
+            .ok_or_else(|| t!("include_cache.noCachePath").to_string())?
+            .join(INCLUDE_CACHE_DIR),
+    }.join(kind);
+    fs::create_dir_all(&path).map_err(|err| t!("include_cache.failedCreateCache", path = path.to_string_lossy(), error = err.to_string()).to_string())?;
+    Ok(path)
+}
+
+fn get_expected_hash(sha256: &str) -> Result<String, String> {
+    let hash = sha256.strip_prefix("sha256:").unwrap_or(sha256).to_ascii_lowercase();
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(t!("include_cache.invalidHash", hash = sha256).to_string());
+    }
+    Ok(hash)
+}
+
+fn get_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn validate_relative_path(path: &str) -> Result<&Path, String> {
+    let relative = Path::new(path);
+    if relative.as_os_str().is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err(t!("include_cache.invalidSourcePath", path = path).to_string());
+    }
+    Ok(relative)
+}
+
+fn run_command<I, S>(program: &str, args: I) -> Result<Vec<u8>, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<S> = args.into_iter().collect();
+    debug!("{}", t!("include_cache.runningCommand", command = format!("{program} {}", args.iter().map(|arg| arg.as_ref().to_string_lossy()).collect::<Vec<_>>().join(" "))));
+    let output = Command::new(program).args(&args).output()
+        .map_err(|err| t!("include_cache.failedRunCommand", command = program, error = err.to_string()).to_string())?;
+    if !output.status.success() {
+        return Err(t!("include_cache.commandFailed", command = program, error = String::from_utf8_lossy(&output.stderr).trim()).to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Read a configuration file from a git repository at a pinned commit.
+///
+/// The repository is kept in the cache so a commit that was already fetched is read without
+/// contacting the repository again.
+///
+/// # Arguments
+///
+/// * `source` - The git source to read.
+///
+/// # Returns
+///
+/// The content of the configuration file.
+///
+/// # Errors
+///
+/// This function will return an error if the commit isn't a full hash, the repository can't be
+/// fetched, the file doesn't exist at the commit, or its content doesn't match the hash.
+pub fn get_git_content(source: &GitSource) -> Result<String, String> {
+    let expected_hash = get_expected_hash(&source.sha256)?;
+    if !matches!(source.commit.len(), 40 | 64) || !source.commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(t!("include_cache.commitNotPinned", commit = source.commit).to_string());
+    }
+    validate_relative_path(&source.path)?;
+    if source.repository.starts_with('-') {
+        return Err(t!("include_cache.invalidRepository", repository = source.repository).to_string());
+    }
+
+    let repository_path = get_cache_path("git")?.join(get_hash(source.repository.as_bytes()));
+    let repository = repository_path.as_os_str();
+    if !repository_path.exists() {
+        run_command("git", [OsStr::new("init"), OsStr::new("--bare"), OsStr::new("--quiet"), repository])?;
+    }
+
+    let commit = format!("{}^{{commit}}", source.commit);
+    if run_command("git", [OsStr::new("-C"), repository, OsStr::new("cat-file"), OsStr::new("-e"), OsStr::new(&commit)]).is_ok() {
+        info!("{}", t!("include_cache.usingCachedCommit", commit = source.commit, repository = source.repository));
+    } else {
+        info!("{}", t!("include_cache.fetchingCommit", commit = source.commit, repository = source.repository));
+        let mut fetch: Vec<&OsStr> = GIT_PROTOCOL_CONFIG.iter().map(OsStr::new).collect();
+        if Path::new(&source.repository).is_dir() {
+            fetch.extend(GIT_FILE_PROTOCOL_CONFIG.iter().map(OsStr::new));
+        }
+        fetch.extend([OsStr::new("-C"), repository, OsStr::new("fetch"), OsStr::new("--quiet")]);
+        // not every server allows fetching a commit directly, so fall back to fetching all refs
+        let fetch_commit = [OsStr::new("--depth"), OsStr::new("1"), OsStr::new("--"), OsStr::new(&source.repository), OsStr::new(&source.commit)];
+        if run_command("git", fetch.iter().copied().chain(fetch_commit)).is_err() {
+            let fetch_refs = [OsStr::new("--"), OsStr::new(&source.repository), OsStr::new("+refs/heads/*:refs/remotes/origin/*"), OsStr::new("+refs/tags/*:refs/tags/*")];
+            run_command("git", fetch.iter().copied().chain(fetch_refs))?;
+        }
+    }
+
+    let object = format!("{}:{}", source.commit, source.path.replace('\\', "/").trim_start_matches("./"));
+    let content = run_command("git", [OsStr::new("-C"), repository, OsStr::new("show"), OsStr::new(&object)])?;
+    let actual_hash = get_hash(&content);
+    if actual_hash != expected_hash {
+        return Err(t!("include_cache.hashMismatch", source = object, expected = expected_hash, actual = actual_hash).to_string());
+    }
+
+    String::from_utf8(content).map_err(|err| t!("include_cache.invalidContent", source = object, error = err.to_string()).to_string())
+}
+
+/// Read a configuration file from an archive.
+///
+/// The archive is stored in the cache by its hash so it's only downloaded or copied once.
+///
+/// # Arguments
+///
+/// * `source` - The archive source to read, a relative path is resolved like `configurationFile`.
+///
+/// # Returns
+///
+/// The content of the configuration file.
+///
+/// # Errors
+///
+/// This function will return an error if the archive can't be retrieved, doesn't match the hash,
+/// can't be extracted, or doesn't contain the file.
+pub fn get_archive_content(source: &ArchiveSource) -> Result<String, String> {
+    let expected_hash = get_expected_hash(&source.sha256)?;
+    let relative_path = validate_relative_path(&source.path)?;
+    let cache_path = get_cache_path("archives")?;
+    let archive_path = cache_path.join(format!("{expected_hash}.tar.gz"));
+    let extract_path = cache_path.join(&expected_hash);
+
+    let cached = fs::read(&archive_path).is_ok_and(|archive| get_hash(&archive) == expected_hash);
+    if cached {
+        info!("{}", t!("include_cache.usingCachedArchive", uri = source.uri));
+    } else {
+        let download_path = cache_path.join(format!("{expected_hash}.download"));
+        if source.uri.starts_with("https://") || source.uri.starts_with("http://") {
+            info!("{}", t!("include_cache.downloadingArchive", uri = source.uri));
+            run_command("curl", [OsStr::new("--fail"), OsStr::new("--silent"), OsStr::new("--show-error"), OsStr::new("--location"), OsStr::new("--output"), download_path.as_os_str(), OsStr::new(&source.uri)])?;
+        } else {
+            let local = source.uri.strip_prefix("file://").unwrap_or(&source.uri);
+            let local_path = normalize_path(Path::new(local))?;
+            info!("{}", t!("include_cache.copyingArchive", path = local_path.to_string_lossy()));
+            fs::copy(&local_path, &download_path).map_err(|err| t!("include_cache.failedReadArchive", uri = source.uri, error = err.to_string()).to_string())?;
+        }
+
+        let archive = fs::read(&download_path).map_err(|err| t!("include_cache.failedReadArchive", uri = source.uri, error = err.to_string()).to_string())?;
+        let actual_hash = get_hash(&archive);
+        if actual_hash != expected_hash {
+            let _ = fs::remove_file(&download_path);
+            return Err(t!("include_cache.hashMismatch", source = source.uri, expected = expected_hash, actual = actual_hash).to_string());
+        }
+        fs::rename(&download_path, &archive_path).map_err(|err| t!("include_cache.failedCreateCache", path = archive_path.to_string_lossy(), error = err.to_string()).to_string())?;
+        let _ = fs::remove_dir_all(&extract_path);
+    }
+
+    if !extract_path.exists() {
+        let partial_path = cache_path.join(format!("{expected_hash}.partial"));
+        let _ = fs::remove_dir_all(&partial_path);
+        fs::create_dir_all(&partial_path).map_err(|err| t!("include_cache.failedCreateCache", path = partial_path.to_string_lossy(), error = err.to_string()).to_string())?;
+        validate_archive_members(&archive_path)?;
+        run_command("tar", [OsStr::new("-xzf"), archive_path.as_os_str(), OsStr::new("--no-same-owner"), OsStr::new("--no-same-permissions"), OsStr::new("-C"), partial_path.as_os_str()])?;
+        if let Err(err) = reject_symlinks(&partial_path) {
+            let _ = fs::remove_dir_all(&partial_path);
+            return Err(err);
+        }
+        fs::rename(&partial_path, &extract_path).map_err(|err| t!("include_cache.failedCreateCache", path = extract_path.to_string_lossy(), error = err.to_string()).to_string())?;
+    }
+
+    let not_in_archive = |err: std::io::Error| t!("include_cache.fileNotInArchive", path = source.path, uri = source.uri, error = err.to_string()).to_string();
+    let extract_path = fs::canonicalize(&extract_path).map_err(not_in_archive)?;
+    let file_path = fs::canonicalize(extract_path.join(relative_path)).map_err(not_in_archive)?;
+    if !file_path.starts_with(&extract_path) {
+        return Err(t!("include_cache.pathOutsideArchive", path = source.path, uri = source.uri).to_string());
+    }
+    fs::read_to_string(&file_path).map_err(not_in_archive)
+}
+
+/// Check that every member of an archive is a relative path within the extraction directory.
+fn validate_archive_members(archive_path: &Path) -> Result<(), String> {
+    let members = run_command("tar", [OsStr::new("-tzf"), archive_path.as_os_str()])?;
+    for member in String::from_utf8_lossy(&members).lines() {
+        let member_path = Path::new(member);
+        if member.starts_with('/') || member_path.has_root() || member_path.components().any(|c| matches!(c, Component::ParentDir | Component::Prefix(_))) {
+            return Err(t!("include_cache.invalidArchiveMember", member = member).to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Check that an extracted archive doesn't contain symbolic links that could point outside of it.
+fn reject_symlinks(path: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(path).map_err(|err| t!("include_cache.failedCreateCache", path = path.to_string_lossy(), error = err.to_string()).to_string())?;
+    for entry in entries {
+        let entry = entry.map_err(|err| t!("include_cache.failedCreateCache", path = path.to_string_lossy(), error = err.to_string()).to_string())?;
+        let file_type = entry.file_type().map_err(|err| t!("include_cache.failedCreateCache", path = entry.path().to_string_lossy(), error = err.to_string()).to_string())?;
+        if file_type.is_symlink() {
+            return Err(t!("include_cache.symlinkInArchive", path = entry.path().to_string_lossy()).to_string());
+        }
+        if file_type.is_dir() {
+            reject_symlinks(&entry.path())?;
+        }
+    }
+    Ok(())
+}
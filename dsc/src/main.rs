@@ -5,26 +5,31 @@ use args::{Args, SubCommand};
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
 use dsc_lib::{progress::ProgressFormat, util::DSC_IGNORE_SETTINGS_FILE};
-use server::start_server;
+use server::{policy::ServerPolicy, start_server};
 use rust_i18n::{i18n, t};
 use std::{env::set_var, io, process::exit};
 use sysinfo::{Process, RefreshKind, System, get_current_pid, ProcessRefreshKind};
 use tracing::{error, info, warn, debug};
 
-use crate::util::{EXIT_INVALID_INPUT, get_input};
+use crate::util::EXIT_INVALID_INPUT;
 
 #[cfg(debug_assertions)]
 use crossterm::event;
 #[cfg(debug_assertions)]
 use std::env;
 
+pub mod agent;
 pub mod args;
+pub mod audit;
+pub mod include_cache;
 pub mod resolve;
 pub mod resource_command;
+pub mod secret_store;
 pub mod server;
 pub mod subcommand;
 pub mod tablewriter;
 pub mod util;
+pub mod watch;
 
 i18n!("locales", fallback = "en-us");
 
@@ -86,31 +91,16 @@ fn dsc_main() {
             let mut cmd = Args::command();
             generate(shell, &mut cmd, "dsc", &mut io::stdout());
         },
-        SubCommand::Config { subcommand, parameters, parameters_file, system_root, as_group, as_assert, as_include } => {
-            let params = get_input(None, parameters_file.as_ref());
-            let file_params = if params.is_empty() {
-                None
-            } else {
-                Some(params)
-            };
-
-            let merged_parameters = match (file_params, parameters) {
-                (Some(file_content), Some(inline_content)) => {
-                    info!("{}", t!("main.mergingParameters"));
-                    match util::merge_parameters(&file_content, &inline_content) {
-                        Ok(merged) => Some(merged),
-                        Err(err) => {
-                            error!("{}: {err}", t!("main.failedMergingParameters"));
-                            exit(EXIT_INVALID_INPUT);
-                        }
-                    }
-                },
-                (Some(file_content), None) => Some(file_content),
-                (None, Some(inline_content)) => Some(inline_content),
-                (None, None) => None,
+        SubCommand::Config { subcommand, parameters, parameters_file, print_effective_parameters, system_root, lock_file, as_group, as_assert, as_include } => {
+            let (merged_parameters, parameter_sources) = match util::merge_parameter_sources(&parameters_file, parameters.as_ref()) {
+                Ok(merged) => merged,
+                Err(err) => {
+                    error!("{}: {err}", t!("main.failedMergingParameters"));
+                    exit(EXIT_INVALID_INPUT);
+                }
             };
 
-            subcommand::config(&subcommand, &merged_parameters, system_root.as_ref(), &as_group, &as_assert, &as_include, progress_format);
+            subcommand::config(&subcommand, &merged_parameters, parameter_sources, print_effective_parameters, system_root.as_ref(), lock_file.as_ref(), &as_group, &as_assert, &as_include, progress_format);
         },
         SubCommand::Extension { subcommand } => {
             subcommand::extension(&subcommand, progress_format);
@@ -118,13 +108,30 @@ fn dsc_main() {
         SubCommand::Function { subcommand } => {
             subcommand::function(&subcommand);
         },
-        SubCommand::Server => {
-            if let Err(err) = start_server() {
+        SubCommand::Server { transport, listen, allow_remote, mode, allow_resources } => {
+            let policy = match ServerPolicy::new(mode, &allow_resources) {
+                Ok(policy) => policy,
+                Err(err) => {
+                    error!("{}", t!("main.failedToStartServer", error = err));
+                    exit(util::EXIT_INVALID_ARGS);
+                }
+            };
+            if let Err(err) = start_server(transport, &listen, allow_remote, policy) {
                 error!("{}", t!("main.failedToStartServer", error = err));
                 exit(util::EXIT_SERVER_FAILED);
             }
             exit(util::EXIT_SUCCESS);
         }
+        SubCommand::Agent { socket } => {
+            if let Err(err) = agent::start_agent(socket.as_deref()) {
+                error!("{}", t!("main.failedToStartAgent", error = err));
+                exit(util::EXIT_SERVER_FAILED);
+            }
+            exit(util::EXIT_SUCCESS);
+        }
+        SubCommand::History { path, resource_type, operation, since, last, output_format } => {
+            audit::history(path.as_ref(), resource_type.as_ref(), operation, since.as_ref(), last, output_format.as_ref());
+        },
         SubCommand::Resource { subcommand } => {
             subcommand::resource(&subcommand, progress_format);
         },
@@ -139,6 +146,9 @@ fn dsc_main() {
             };
             util::write_object(&json, output_format.as_ref(), false);
         },
+        SubCommand::Secret { path, key_file, subcommand } => {
+            secret_store::secret(&subcommand, path.as_ref(), key_file.as_ref());
+        },
     }
 
     exit(util::EXIT_SUCCESS);
@@ -10,8 +10,12 @@ use std::fs::File;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+use crate::include_cache::{ArchiveSource, GitSource, get_archive_content, get_git_content};
 use crate::util::DSC_CONFIG_ROOT;
 
+/// The type name of the resource that includes another configuration.
+pub const INCLUDE_RESOURCE_TYPE: &str = "Microsoft.DSC/Include";
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub enum IncludeKind {
     /// The path to the file to include.  Path is relative to the file containing the include
@@ -21,6 +25,12 @@ pub enum IncludeKind {
     ConfigurationFile(String),
     #[serde(rename = "configurationContent")]
     ConfigurationContent(String),
+    /// A configuration file in a git repository at a pinned commit.
+    #[serde(rename = "configurationGit")]
+    ConfigurationGit(GitSource),
+    /// A configuration file in a `.tar.gz` archive on the local file system or an HTTP server.
+    #[serde(rename = "configurationArchive")]
+    ConfigurationArchive(ArchiveSource),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
@@ -100,6 +110,24 @@ pub fn get_contents(input: &str) -> Result<(Option<String>, String), String> {
                 }
             }
         },
+        IncludeKind::ConfigurationGit(source) => {
+            let include_content = get_git_content(&source)?;
+            match parse_input_to_json(&include_content) {
+                Ok(json) => json,
+                Err(err) => {
+                    return Err(t!("resolve.invalidFile", path = source.path, error = err.to_string()).to_string());
+                }
+            }
+        },
+        IncludeKind::ConfigurationArchive(source) => {
+            let include_content = get_archive_content(&source)?;
+            match parse_input_to_json(&include_content) {
+                Ok(json) => json,
+                Err(err) => {
+                    return Err(t!("resolve.invalidFile", path = source.path, error = err.to_string()).to_string());
+                }
+            }
+        },
         IncludeKind::ConfigurationContent(text) => {
             match parse_input_to_json(&text) {
                 Ok(json) => json,
@@ -148,7 +176,7 @@ pub fn get_contents(input: &str) -> Result<(Option<String>, String), String> {
     Ok((parameters, config_json))
 }
 
-fn normalize_path(path: &Path) -> Result<PathBuf, String> {
+pub(crate) fn normalize_path(path: &Path) -> Result<PathBuf, String> {
     if path.is_absolute() {
         Ok(path.to_path_buf())
     } else {
@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 use crate::args::{GetOutputFormat, OutputFormat};
+use crate::audit::{audit_resource_delete, audit_resource_set};
 use crate::util::{EXIT_DSC_ERROR, EXIT_INVALID_ARGS, EXIT_JSON_ERROR, EXIT_DSC_RESOURCE_NOT_FOUND, write_object};
 use dsc_lib::configure::config_doc::{Configuration, ExecutionKind};
 use dsc_lib::configure::add_resource_export_results_to_configuration;
@@ -170,6 +171,7 @@ pub fn set(dsc: &mut DscManager, resource_type: &FullyQualifiedTypeName, version
             }
         };
 
+        let start_datetime = chrono::Local::now();
         if let Err(err) = resource.delete(input, &ExecutionKind::Actual) {
             error!("{err}");
             exit(EXIT_DSC_ERROR);
@@ -191,6 +193,7 @@ pub fn set(dsc: &mut DscManager, resource_type: &FullyQualifiedTypeName, version
             after_state,
             changed_properties: Some(diff),
         });
+        audit_resource_set(resource_type, &result, &ExecutionKind::Actual, chrono::Local::now() - start_datetime);
 
         let json = match serde_json::to_string(&result) {
             Ok(json) => json,
@@ -203,8 +206,10 @@ pub fn set(dsc: &mut DscManager, resource_type: &FullyQualifiedTypeName, version
         return;
     }
 
+    let start_datetime = chrono::Local::now();
     match resource.set(input, true, &execution_kind) {
         Ok(result) => {
+            audit_resource_set(resource_type, &result, &execution_kind, chrono::Local::now() - start_datetime);
             // convert to json
             let json = match serde_json::to_string(&result) {
                 Ok(json) => json,
@@ -272,8 +277,10 @@ pub fn delete(dsc: &mut DscManager, resource_type: &FullyQualifiedTypeName, vers
 
     let execution_kind = if what_if { ExecutionKind::WhatIf } else { ExecutionKind::Actual };
 
+    let start_datetime = chrono::Local::now();
     match resource.delete(input, &execution_kind) {
         Ok(result) => {
+            audit_resource_delete(resource_type, input, &execution_kind, chrono::Local::now() - start_datetime);
             match result {
                 DeleteResultKind::ResourceActual => {
                 },
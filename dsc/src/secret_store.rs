@@ -0,0 +1,460 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::args::{OutputFormat, SecretSubCommand};
+use crate::util::{EXIT_DSC_ERROR, EXIT_INVALID_INPUT, EXIT_JSON_ERROR, get_local_data_path, write_object};
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, KeyInit, OsRng, rand_core::RngCore},
+};
+use argon2::Argon2;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use crossterm::{event::{self, Event, KeyCode, KeyEventKind, KeyModifiers}, terminal};
+use dsc_lib::{dscerror::DscError, util::get_setting};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    env,
+    fs,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
+use tracing::{debug, error, info};
+
+/// The name of the setting configuring the secret store.
+pub const SECRET_STORE_SETTING: &str = "secretStore";
+/// The environment variable with the path of the secret store.
+pub const DSC_SECRET_STORE_PATH: &str = "DSC_SECRET_STORE_PATH";
+/// The environment variable with the path of the file containing the key of the secret store.
+pub const DSC_SECRET_STORE_KEY_FILE: &str = "DSC_SECRET_STORE_KEY_FILE";
+/// The environment variable with the passphrase of the secret store, used if there is no key file.
+pub const DSC_SECRET_STORE_PASSPHRASE: &str = "DSC_SECRET_STORE_PASSPHRASE";
+/// The vault a secret is stored in if no vault is specified.
+pub const DEFAULT_VAULT: &str = "default";
+
+const SECRET_STORE_FILE_NAME: &str = "secrets.json";
+const STORE_VERSION: u32 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SecretStoreSetting {
+    /// The path of the store, defaults to `secrets.json` in the DSC local data directory.
+    path: Option<String>,
+    /// The path of the file containing the key of the store.
+    key_file: Option<String>,
+}
+
+/// The store as written to disk, the secrets are encrypted as a whole with AES-256-GCM using a
+/// key derived from the key file or passphrase with Argon2id.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct EncryptedStore {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The decrypted secrets by vault and then by name.
+type Vaults = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A secret in the store, the value is never listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub vault: String,
+}
+
+/// A local file of secrets encrypted with a key file or passphrase.
+pub struct SecretStore {
+    path: PathBuf,
+    key: Option<Vec<u8>>,
+}
+
+impl SecretStore {
+    /// Open the secret store configured by the arguments, environment variables, or `secretStore` setting in that order.
+    ///
+    /// The key isn't read until the store is read or written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the store, overrides the configured path.
+    /// * `key_file` - The path of the file containing the key, overrides the configured key file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the setting is invalid, the path of the store can't be determined, or the key file can't be read.
+    pub fn open(path: Option<&str>, key_file: Option<&str>) -> Result<Self, DscError> {
+        let setting = get_secret_store_setting()?;
+        let env_path = env::var(DSC_SECRET_STORE_PATH).ok().filter(|path| !path.is_empty());
+        let path = match path.or(env_path.as_deref()).or(setting.path.as_deref()) {
+            Some(path) => PathBuf::from(path),
+            None => get_local_data_path()
+                .ok_or_else(|| DscError::Operation(t!("secret_store.noStorePath").to_string()))?
+                .join(SECRET_STORE_FILE_NAME),
+        };
+
+        let env_key_file = env::var(DSC_SECRET_STORE_KEY_FILE).ok().filter(|path| !path.is_empty());
+        let key = match key_file.or(env_key_file.as_deref()).or(setting.key_file.as_deref()) {
+            Some(key_file) => {
+                let key = fs::read(key_file).map_err(|err| {
+                    DscError::Operation(t!("secret_store.failedToReadKeyFile", path = key_file, error = err.to_string()).to_string())
+                })?;
+                // a key file written by an editor usually ends with a newline
+                Some(key.trim_ascii_end().to_vec())
+            },
+            None => env::var(DSC_SECRET_STORE_PASSPHRASE).ok().map(String::into_bytes),
+        };
+        if key.as_ref().is_some_and(Vec::is_empty) {
+            return Err(DscError::Operation(t!("secret_store.emptyKey").to_string()));
+        }
+        Ok(Self { path, key })
+    }
+
+    /// Get the path of the store.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the store has been created.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Result<Key<Aes256Gcm>, DscError> {
+        let Some(secret) = &self.key else {
+            return Err(DscError::Operation(t!("secret_store.noKey").to_string()));
+        };
+        let mut key = Key::<Aes256Gcm>::default();
+        Argon2::default().hash_password_into(secret, salt, &mut key)
+            .map_err(|err| DscError::Operation(t!("secret_store.deriveKeyFailed", error = err.to_string()).to_string()))?;
+        Ok(key)
+    }
+
+    fn load(&self) -> Result<Vaults, DscError> {
+        if !self.exists() {
+            return Ok(Vaults::new());
+        }
+        let invalid = |error: String| DscError::Operation(t!("secret_store.invalidStore", path = self.path.display(), error = error).to_string());
+        let store: EncryptedStore = serde_json::from_str(&fs::read_to_string(&self.path)?)
+            .map_err(|err| invalid(err.to_string()))?;
+        if store.version != STORE_VERSION {
+            return Err(DscError::Operation(t!("secret_store.unsupportedVersion", path = self.path.display(), version = store.version).to_string()));
+        }
+        let salt = STANDARD.decode(&store.salt).map_err(|err| invalid(err.to_string()))?;
+        let nonce = STANDARD.decode(&store.nonce).map_err(|err| invalid(err.to_string()))?;
+        let ciphertext = STANDARD.decode(&store.ciphertext).map_err(|err| invalid(err.to_string()))?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(invalid(format!("nonce length {}", nonce.len())));
+        }
+
+        let cipher = Aes256Gcm::new(&self.derive_key(&salt)?);
+        let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_err| DscError::Operation(t!("secret_store.decryptFailed", path = self.path.display()).to_string()))?;
+        serde_json::from_slice(&plaintext).map_err(|err| invalid(err.to_string()))
+    }
+
+    fn save(&self, vaults: &Vaults) -> Result<(), DscError> {
+        // a new salt and nonce are used every time the store is written
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+
+        let cipher = Aes256Gcm::new(&self.derive_key(&salt)?);
+        let plaintext = serde_json::to_vec(vaults)?;
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_err| DscError::Operation(t!("secret_store.encryptFailed").to_string()))?;
+        let store = EncryptedStore {
+            version: STORE_VERSION,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+
+        if let Some(parent) = self.path.parent() && !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+        // write to a temporary file first so a failure doesn't lose the existing secrets
+        let temp_path = self.path.with_extension("json.tmp");
+        let mut file = create_private_file(&temp_path)?;
+        file.write_all(serde_json::to_string_pretty(&store)?.as_bytes())?;
+        file.flush()?;
+        drop(file);
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Get the value of a secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret.
+    /// * `vault` - The vault of the secret, every vault is searched if not specified.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the store can't be decrypted or the secret exists in
+    /// multiple vaults with different values and no vault is specified.
+    pub fn get(&self, name: &str, vault: Option<&str>) -> Result<Option<String>, DscError> {
+        let vaults = self.load()?;
+        if let Some(vault) = vault {
+            return Ok(vaults.get(vault).and_then(|secrets| secrets.get(name)).cloned());
+        }
+
+        let mut result: Option<&String> = None;
+        for secrets in vaults.values() {
+            if let Some(value) = secrets.get(name) {
+                if result.is_some_and(|result| result != value) {
+                    return Err(DscError::Operation(t!("secret_store.ambiguousSecret", name = name).to_string()));
+                }
+                result = Some(value);
+            }
+        }
+        Ok(result.cloned())
+    }
+
+    /// Add or update a secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret.
+    /// * `vault` - The vault of the secret, defaults to `default`.
+    /// * `value` - The value of the secret.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the store can't be decrypted or written.
+    pub fn set(&self, name: &str, vault: Option<&str>, value: &str) -> Result<(), DscError> {
+        let mut vaults = self.load()?;
+        vaults.entry(vault.unwrap_or(DEFAULT_VAULT).to_string())
+            .or_default()
+            .insert(name.to_string(), value.to_string());
+        self.save(&vaults)
+    }
+
+    /// Remove a secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the secret.
+    /// * `vault` - The vault of the secret, the secret is removed from every vault if not specified.
+    ///
+    /// # Returns
+    ///
+    /// Whether the secret was found.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the store can't be decrypted or written.
+    pub fn remove(&self, name: &str, vault: Option<&str>) -> Result<bool, DscError> {
+        let mut vaults = self.load()?;
+        let mut removed = false;
+        for (vault_name, secrets) in &mut vaults {
+            if vault.is_none_or(|vault| vault == vault_name) {
+                removed |= secrets.remove(name).is_some();
+            }
+        }
+        if removed {
+            vaults.retain(|_, secrets| !secrets.is_empty());
+            self.save(&vaults)?;
+        }
+        Ok(removed)
+    }
+
+    /// List the secrets without their values.
+    ///
+    /// # Arguments
+    ///
+    /// * `vault` - Only list the secrets in the vault.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the store can't be decrypted.
+    pub fn list(&self, vault: Option<&str>) -> Result<Vec<SecretInfo>, DscError> {
+        let vaults = self.load()?;
+        Ok(vaults.iter()
+            .filter(|(vault_name, _)| vault.is_none_or(|vault| vault == vault_name.as_str()))
+            .flat_map(|(vault_name, secrets)| secrets.keys().map(|name| SecretInfo {
+                name: name.clone(),
+                vault: vault_name.clone(),
+            }))
+            .collect())
+    }
+}
+
+#[cfg(unix)]
+fn create_private_file(path: &Path) -> Result<fs::File, DscError> {
+    use std::os::unix::fs::OpenOptionsExt;
+    Ok(fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &Path) -> Result<fs::File, DscError> {
+    Ok(fs::File::create(path)?)
+}
+
+fn get_secret_store_setting() -> Result<SecretStoreSetting, DscError> {
+    let Ok(v) = get_setting(SECRET_STORE_SETTING) else {
+        return Ok(SecretStoreSetting::default());
+    };
+
+    // if there is a policy value defined - use it; otherwise use setting value
+    let value = if v.policy != Value::Null {
+        v.policy
+    } else if v.setting != Value::Null {
+        v.setting
+    } else {
+        return Ok(SecretStoreSetting::default());
+    };
+
+    serde_json::from_value::<SecretStoreSetting>(value)
+        .map_err(|err| DscError::Setting(t!("secret_store.invalidSetting", error = err.to_string()).to_string()))
+}
+
+/// Read the value of a secret from STDIN, only the first line is used.
+fn read_value_from_stdin() -> String {
+    let mut input = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut input) {
+        error!("{}", t!("secret_store.failedToReadValue", error = err.to_string()));
+        exit(EXIT_INVALID_INPUT);
+    }
+    input.lines().next().unwrap_or_default().to_string()
+}
+
+/// Prompt for the value of a secret on the terminal without echoing it.
+fn read_value_from_terminal(name: &str) -> String {
+    eprint!("{}", t!("secret_store.enterValue", name = name));
+    let _ = io::stderr().flush();
+    if let Err(err) = terminal::enable_raw_mode() {
+        error!("{}", t!("secret_store.failedToReadValue", error = err.to_string()));
+        exit(EXIT_INVALID_INPUT);
+    }
+
+    let mut value = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Backspace => { value.pop(); },
+                KeyCode::Esc => break Err(t!("secret_store.valueCancelled").to_string()),
+                KeyCode::Char('c' | 'd') if key.modifiers.contains(KeyModifiers::CONTROL) => break Err(t!("secret_store.valueCancelled").to_string()),
+                KeyCode::Char(c) => value.push(c),
+                _ => {},
+            },
+            Ok(_) => {},
+            Err(err) => break Err(t!("secret_store.failedToReadValue", error = err.to_string()).to_string()),
+        }
+    };
+    let _ = terminal::disable_raw_mode();
+    eprintln!();
+
+    if let Err(err) = result {
+        error!("{err}");
+        exit(EXIT_INVALID_INPUT);
+    }
+    value
+}
+
+/// Run a `secret` subcommand against the local secret store.
+///
+/// # Arguments
+///
+/// * `subcommand` - The subcommand to run.
+/// * `path` - The path of the store, overrides the configured path.
+/// * `key_file` - The path of the file containing the key, overrides the configured key file.
+pub fn secret(subcommand: &SecretSubCommand, path: Option<&String>, key_file: Option<&String>) {
+    let store = match SecretStore::open(path.map(String::as_str), key_file.map(String::as_str)) {
+        Ok(store) => store,
+        Err(err) => {
+            error!("{err}");
+            exit(EXIT_DSC_ERROR);
+        }
+    };
+
+    match subcommand {
+        SecretSubCommand::Set { name, vault } => {
+            // the value is never taken from the command line where other users and the shell history can see it
+            let value = if io::stdin().is_terminal() {
+                read_value_from_terminal(name)
+            } else {
+                read_value_from_stdin()
+            };
+            if value.is_empty() {
+                error!("{}", t!("secret_store.emptyValue"));
+                exit(EXIT_INVALID_INPUT);
+            }
+            if let Err(err) = store.set(name, vault.as_deref(), &value) {
+                error!("{err}");
+                exit(EXIT_DSC_ERROR);
+            }
+            info!("{}", t!("secret_store.secretSet", name = name, vault = vault.as_deref().unwrap_or(DEFAULT_VAULT)));
+        },
+        SecretSubCommand::Get { name, vault, as_extension } => {
+            // as an extension, a missing store or secret returns nothing so other extensions can provide the secret
+            if *as_extension && !store.exists() {
+                debug!("{}", t!("secret_store.storeNotFound", path = store.path().display()));
+                return;
+            }
+            match store.get(name, vault.as_deref()) {
+                Ok(Some(value)) => println!("{value}"),
+                Ok(None) if *as_extension => {
+                    debug!("{}", t!("secret_store.secretNotFound", name = name));
+                },
+                Ok(None) => {
+                    error!("{}", t!("secret_store.secretNotFound", name = name));
+                    exit(EXIT_DSC_ERROR);
+                },
+                Err(err) => {
+                    error!("{err}");
+                    exit(EXIT_DSC_ERROR);
+                }
+            }
+        },
+        SecretSubCommand::List { vault, output_format } => {
+            let secrets = match store.list(vault.as_deref()) {
+                Ok(secrets) => secrets,
+                Err(err) => {
+                    error!("{err}");
+                    exit(EXIT_DSC_ERROR);
+                }
+            };
+            write_secrets(&secrets, output_format.as_ref());
+        },
+        SecretSubCommand::Remove { name, vault } => {
+            match store.remove(name, vault.as_deref()) {
+                Ok(true) => info!("{}", t!("secret_store.secretRemoved", name = name)),
+                Ok(false) => {
+                    error!("{}", t!("secret_store.secretNotFound", name = name));
+                    exit(EXIT_DSC_ERROR);
+                },
+                Err(err) => {
+                    error!("{err}");
+                    exit(EXIT_DSC_ERROR);
+                }
+            }
+        },
+    }
+}
+
+fn write_secrets(secrets: &[SecretInfo], format: Option<&OutputFormat>) {
+    let mut include_separator = false;
+    for secret in secrets {
+        let json = match serde_json::to_string(secret) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("JSON Error: {err}");
+                exit(EXIT_JSON_ERROR);
+            }
+        };
+        write_object(&json, format, include_separator);
+        include_separator = true;
+    }
+}
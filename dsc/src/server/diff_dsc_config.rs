@@ -0,0 +1,120 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::server::{
+    invoke_dsc_config::{new_configurator, parse_configuration},
+    mcp_progress::ConfigurationProgress,
+    mcp_server::McpServer,
+    policy::PolicyOperation,
+};
+use dsc_lib::{
+    configure::config_result::ResourceTestResult,
+    dscresources::invoke_result::TestResult,
+    types::FullyQualifiedTypeName,
+};
+use rmcp::{ErrorData as McpError, Json, RoleServer, tool, tool_router, handler::server::wrapper::Parameters, service::RequestContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task;
+
+#[derive(Serialize, JsonSchema)]
+pub struct PropertyDiff {
+    /// The name of the property that differs.
+    pub property: String,
+    /// The value of the property in the desired state.
+    pub desired: Option<Value>,
+    /// The value of the property in the actual state.
+    pub actual: Option<Value>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDiff {
+    /// The name of the resource instance, nested instances are prefixed with the name of their group.
+    pub name: String,
+    /// The type of the resource instance.
+    pub r#type: FullyQualifiedTypeName,
+    /// Whether the resource instance is in the desired state.
+    pub in_desired_state: bool,
+    /// The properties that differ from the desired state.
+    pub differences: Vec<PropertyDiff>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffDscConfigResponse {
+    pub in_desired_state: bool,
+    pub resources: Vec<ResourceDiff>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DiffDscConfigRequest {
+    #[schemars(description = "The DSC configuration document as a YAML string")]
+    pub configuration: String,
+    #[schemars(description = "Optional parameters to pass to the configuration as a YAML string")]
+    pub parameters: Option<String>,
+}
+
+#[tool_router(router = diff_dsc_config_router, vis = "pub")]
+impl McpServer {
+    #[tool(
+        description = "Test a DSC configuration and return the properties of each resource instance that differ from the desired state",
+        annotations(
+            title = "Test a DSC configuration and return the properties of each resource instance that differ from the desired state",
+            read_only_hint = true,
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = true,
+        )
+    )]
+    pub async fn diff_dsc_config(&self, context: RequestContext<RoleServer>, Parameters(DiffDscConfigRequest { configuration, parameters }): Parameters<DiffDscConfigRequest>) -> Result<Json<DiffDscConfigResponse>, McpError> {
+        let policy = self.policy.clone();
+        let (progress, reporter) = ConfigurationProgress::new(&context);
+        let result = task::spawn_blocking(move || {
+            let config = parse_configuration(&configuration)?;
+            policy.check_configuration(PolicyOperation::Test, &config)?;
+            let mut configurator = new_configurator(&config, parameters.as_deref())?;
+            reporter.attach(&mut configurator);
+            let test_result = configurator.invoke_test().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+            let mut resources = Vec::<ResourceDiff>::new();
+            add_resource_diffs(&mut resources, None, &test_result.results);
+            Ok(DiffDscConfigResponse {
+                in_desired_state: resources.iter().all(|resource| resource.in_desired_state),
+                resources,
+            })
+        }).await.map_err(|e| McpError::internal_error(e.to_string(), None));
+        progress.finish().await;
+        let result = result??;
+
+        Ok(Json(result))
+    }
+}
+
+fn add_resource_diffs(diffs: &mut Vec<ResourceDiff>, parent: Option<&str>, results: &[ResourceTestResult]) {
+    for result in results {
+        let name = match parent {
+            Some(parent) => format!("{parent}/{}", result.name),
+            None => result.name.clone(),
+        };
+        match &result.result {
+            TestResult::Resource(response) => {
+                let differences = response.diff_properties.iter().map(|property| PropertyDiff {
+                    property: property.clone(),
+                    desired: response.desired_state.get(property).cloned(),
+                    actual: response.actual_state.get(property).cloned(),
+                }).collect();
+                diffs.push(ResourceDiff {
+                    name,
+                    r#type: result.resource_type.clone(),
+                    in_desired_state: response.in_desired_state,
+                    differences,
+                });
+            },
+            TestResult::Group(group_results) => {
+                add_resource_diffs(diffs, Some(&name), group_results);
+            },
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::server::{mcp_server::McpServer, policy::ServerPolicy};
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{StatusCode, header::AUTHORIZATION},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+};
+use rmcp::{
+    ErrorData as McpError,
+    transport::streamable_http_server::{
+        StreamableHttpServerConfig,
+        StreamableHttpService,
+        session::local::LocalSessionManager,
+    },
+};
+use rust_i18n::t;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// The path the MCP endpoint is served at.
+pub const MCP_ENDPOINT: &str = "/mcp";
+
+/// The environment variable containing the bearer token clients must present.
+pub const BEARER_TOKEN_ENV_VAR: &str = "DSC_MCP_BEARER_TOKEN";
+
+/// Serve the MCP server over the streamable HTTP transport.
+///
+/// Only loopback addresses are accepted unless `allow_remote` is set, and a non-loopback
+/// address also requires the `DSC_MCP_BEARER_TOKEN` environment variable.  If the variable
+/// is set, every request must include it as a bearer token in the `Authorization` header.
+///
+/// # Arguments
+///
+/// * `listen` - The socket address to listen on, such as `127.0.0.1:8080`.
+/// * `allow_remote` - Whether to allow listening on a non-loopback address.
+/// * `policy` - The policy restricting the tools of the MCP server.
+///
+/// # Errors
+///
+/// This function will return an error if the address is invalid or not allowed, or the server fails.
+pub async fn start_http_server_async(listen: &str, allow_remote: bool, policy: Arc<ServerPolicy>) -> Result<(), McpError> {
+    let address: SocketAddr = listen.parse()
+        .map_err(|err: std::net::AddrParseError| McpError::invalid_params(t!("server.http_transport.invalidListenAddress", address = listen, error = err.to_string()), None))?;
+    let is_loopback = address.ip().is_loopback();
+    if !is_loopback && !allow_remote {
+        return Err(McpError::invalid_params(t!("server.http_transport.remoteNotAllowed", address = address), None));
+    }
+
+    let bearer_token = std::env::var(BEARER_TOKEN_ENV_VAR).ok().filter(|token| !token.is_empty());
+    if bearer_token.is_none() && !is_loopback {
+        return Err(McpError::invalid_params(t!("server.http_transport.noBearerToken", address = address), None));
+    }
+
+    let mut config = StreamableHttpServerConfig::default();
+    if !is_loopback {
+        // the default only accepts loopback `Host` headers which remote clients won't send
+        config = config.disable_allowed_hosts();
+    }
+    let service = StreamableHttpService::new(
+        move || Ok(McpServer::with_policy(policy.clone())),
+        Arc::new(LocalSessionManager::default()),
+        config,
+    );
+
+    let mut router = Router::new().nest_service(MCP_ENDPOINT, service);
+    if let Some(token) = bearer_token {
+        router = router.layer(middleware::from_fn_with_state(Arc::new(token), authorize));
+    }
+
+    let listener = TcpListener::bind(address).await
+        .map_err(|err| McpError::internal_error(t!("server.http_transport.failedToBind", address = address, error = err.to_string()), None))?;
+    info!("{}", t!("server.http_transport.listening", address = address, endpoint = MCP_ENDPOINT));
+    axum::serve(listener, router).await
+        .map_err(|err| McpError::internal_error(t!("server.mod.serverWaitFailed", error = err.to_string()), None))?;
+
+    info!("{}", t!("server.mod.serverStopped"));
+    Ok(())
+}
+
+async fn authorize(State(token): State<Arc<String>>, request: Request, next: Next) -> Response {
+    let authorized = request.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes()));
+    if !authorized {
+        warn!("{}", t!("server.http_transport.unauthorized"));
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+// compare without returning early so the time taken doesn't reveal how much of the token matched
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter().zip(right).fold(0u8, |acc, (l, r)| acc | (l ^ r)) == 0
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::server::mcp_server::McpServer;
+use crate::server::{mcp_progress::ConfigurationProgress, mcp_server::McpServer, policy::PolicyOperation};
 use dsc_lib::{
     configure::{
         config_doc::Configuration,
@@ -13,7 +13,7 @@ use dsc_lib::{
     },
     progress::ProgressFormat,
 };
-use rmcp::{handler::server::wrapper::Parameters, tool, tool_router, ErrorData as McpError, Json};
+use rmcp::{handler::server::wrapper::Parameters, service::RequestContext, tool, tool_router, ErrorData as McpError, Json, RoleServer};
 use rust_i18n::t;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -28,6 +28,17 @@ pub enum ConfigOperation {
     Export,
 }
 
+impl From<&ConfigOperation> for PolicyOperation {
+    fn from(operation: &ConfigOperation) -> Self {
+        match operation {
+            ConfigOperation::Get => PolicyOperation::Get,
+            ConfigOperation::Set => PolicyOperation::Set,
+            ConfigOperation::Test => PolicyOperation::Test,
+            ConfigOperation::Export => PolicyOperation::Export,
+        }
+    }
+}
+
 #[derive(Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ConfigOperationResult {
@@ -68,104 +79,21 @@ impl McpServer {
     )]
     pub async fn invoke_dsc_config(
         &self,
+        context: RequestContext<RoleServer>,
         Parameters(InvokeDscConfigRequest {
             operation,
             configuration,
             parameters,
         }): Parameters<InvokeDscConfigRequest>,
     ) -> Result<Json<InvokeDscConfigResponse>, McpError> {
+        let policy = self.policy.clone();
+        let (progress, reporter) = ConfigurationProgress::new(&context);
         let result = task::spawn_blocking(move || {
-            let config: Configuration = match serde_yaml::from_str::<serde_yaml::Value>(&configuration) {
-                Ok(yaml_value) => match serde_json::to_value(yaml_value) {
-                    Ok(json_value) => match serde_json::from_value(json_value) {
-                        Ok(config) => config,
-                        Err(e) => {
-                            return Err(McpError::invalid_request(
-                                format!(
-                                    "{}: {e}",
-                                    t!("server.invoke_dsc_config.invalidConfiguration")
-                                ),
-                                None,
-                            ))
-                        }
-                    },
-                    Err(e) => {
-                        return Err(McpError::invalid_request(
-                            format!(
-                                "{}: {e}",
-                                t!("server.invoke_dsc_config.failedConvertJson")
-                            ),
-                            None,
-                        ))
-                    }
-                },
-                Err(e) => {
-                    return Err(McpError::invalid_request(
-                        format!(
-                            "{}: {e}",
-                            t!("server.invoke_dsc_config.invalidConfiguration")
-                        ),
-                        None,
-                    ))
-                }
-            };
-
-            let config_json = match serde_json::to_string(&config) {
-                Ok(json) => json,
-                Err(e) => {
-                    return Err(McpError::internal_error(
-                        format!("{}: {e}", t!("server.invoke_dsc_config.failedSerialize")),
-                        None,
-                    ))
-                }
-            };
-
-            let mut configurator = match Configurator::new(&config_json, ProgressFormat::None) {
-                Ok(configurator) => configurator,
-                Err(e) => return Err(McpError::internal_error(e.to_string(), None)),
-            };
-
-            configurator.context.dsc_version = Some(env!("CARGO_PKG_VERSION").to_string());
-
-            let parameters_value: Option<serde_json::Value> = if let Some(params_str) = parameters {
-                let params_json = match serde_yaml::from_str::<serde_yaml::Value>(&params_str) {
-                    Ok(yaml) => match serde_json::to_value(yaml) {
-                        Ok(json) => json,
-                        Err(e) => {
-                            return Err(McpError::invalid_request(
-                                format!(
-                                    "{}: {e}",
-                                    t!("server.invoke_dsc_config.failedConvertJson")
-                                ),
-                                None,
-                            ))
-                        }
-                    },
-                    Err(e) => {
-                        return Err(McpError::invalid_request(
-                            format!(
-                                "{}: {e}",
-                                t!("server.invoke_dsc_config.invalidParameters")
-                            ),
-                            None,
-                        ))
-                    }
-                };
-
-                // Wrap parameters in a "parameters" field for configurator.set_context()
-                Some(serde_json::json!({
-                    "parameters": params_json
-                }))
-            } else {
-                None
-            };
-
-            if let Err(e) = configurator.set_context(parameters_value.as_ref()) {
-                return Err(McpError::invalid_request(
-                    format!("{}: {e}", t!("server.invoke_dsc_config.failedSetParameters")),
-                    None,
-                ));
-            }
+            let config = parse_configuration(&configuration)?;
+            policy.check_configuration(PolicyOperation::from(&operation), &config)?;
+            let mut configurator = new_configurator(&config, parameters.as_deref())?;
+            configurator.context.execution_type = policy.execution_kind();
+            reporter.attach(&mut configurator);
 
             match operation {
                 ConfigOperation::Get => {
@@ -199,8 +127,119 @@ impl McpServer {
             }
         })
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))??;
+        .map_err(|e| McpError::internal_error(e.to_string(), None));
+        progress.finish().await;
+        let result = result??;
 
         Ok(Json(InvokeDscConfigResponse { result }))
     }
 }
+
+/// Parse a configuration document supplied to a tool as YAML or JSON.
+///
+/// # Errors
+///
+/// This function will return an error if the document isn't a valid configuration.
+pub fn parse_configuration(configuration: &str) -> Result<Configuration, McpError> {
+    let config: Configuration = match serde_yaml::from_str::<serde_yaml::Value>(configuration) {
+        Ok(yaml_value) => match serde_json::to_value(yaml_value) {
+            Ok(json_value) => match serde_json::from_value(json_value) {
+                Ok(config) => config,
+                Err(e) => {
+                    return Err(McpError::invalid_request(
+                        format!(
+                            "{}: {e}",
+                            t!("server.invoke_dsc_config.invalidConfiguration")
+                        ),
+                        None,
+                    ))
+                }
+            },
+            Err(e) => {
+                return Err(McpError::invalid_request(
+                    format!(
+                        "{}: {e}",
+                        t!("server.invoke_dsc_config.failedConvertJson")
+                    ),
+                    None,
+                ))
+            }
+        },
+        Err(e) => {
+            return Err(McpError::invalid_request(
+                format!(
+                    "{}: {e}",
+                    t!("server.invoke_dsc_config.invalidConfiguration")
+                ),
+                None,
+            ))
+        }
+    };
+    Ok(config)
+}
+
+/// Create a configurator for a configuration with the parameters supplied to a tool as YAML or JSON.
+///
+/// # Errors
+///
+/// This function will return an error if the configurator can't be created or the parameters are invalid.
+pub fn new_configurator(config: &Configuration, parameters: Option<&str>) -> Result<Configurator, McpError> {
+    let config_json = match serde_json::to_string(config) {
+        Ok(json) => json,
+        Err(e) => {
+            return Err(McpError::internal_error(
+                format!("{}: {e}", t!("server.invoke_dsc_config.failedSerialize")),
+                None,
+            ))
+        }
+    };
+
+    let mut configurator = match Configurator::new(&config_json, ProgressFormat::None) {
+        Ok(configurator) => configurator,
+        Err(e) => return Err(McpError::internal_error(e.to_string(), None)),
+    };
+
+    configurator.context.dsc_version = Some(env!("CARGO_PKG_VERSION").to_string());
+
+    let parameters_value: Option<serde_json::Value> = if let Some(params_str) = parameters {
+        let params_json = match serde_yaml::from_str::<serde_yaml::Value>(params_str) {
+            Ok(yaml) => match serde_json::to_value(yaml) {
+                Ok(json) => json,
+                Err(e) => {
+                    return Err(McpError::invalid_request(
+                        format!(
+                            "{}: {e}",
+                            t!("server.invoke_dsc_config.failedConvertJson")
+                        ),
+                        None,
+                    ))
+                }
+            },
+            Err(e) => {
+                return Err(McpError::invalid_request(
+                    format!(
+                        "{}: {e}",
+                        t!("server.invoke_dsc_config.invalidParameters")
+                    ),
+                    None,
+                ))
+            }
+        };
+
+        // Wrap parameters in a "parameters" field for configurator.set_context()
+        Some(serde_json::json!({
+            "parameters": params_json
+        }))
+    } else {
+        None
+    };
+
+    if let Err(e) = configurator.set_context(parameters_value.as_ref()) {
+        return Err(McpError::invalid_request(
+            format!("{}: {e}", t!("server.invoke_dsc_config.failedSetParameters")),
+            None,
+        ));
+    }
+
+    Ok(configurator)
+}
@@ -1,7 +1,11 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::server::mcp_server::McpServer;
+use crate::server::{
+    invoke_dsc_config::{new_configurator, parse_configuration},
+    mcp_server::McpServer,
+    policy::PolicyOperation,
+};
 use dsc_lib::{configure::context::Context, parser::Statement};
 use rmcp::{ErrorData as McpError, Json, tool, tool_router, handler::server::wrapper::Parameters};
 use rust_i18n::t;
@@ -26,24 +30,37 @@ pub struct ExpressionResponse {
 pub struct ExpressionRequest {
     #[schemars(description = "The DSC expression to invoke")]
     pub expression: String,
+    #[schemars(description = "Optional DSC configuration document as a YAML string whose parameters and variables the expression can reference")]
+    pub configuration: Option<String>,
+    #[schemars(description = "Optional parameters to pass to the configuration as a YAML string")]
+    pub parameters: Option<String>,
 }
 
 #[tool_router(router = invoke_dsc_expression_router, vis = "pub")]
 impl McpServer {
     #[tool(
-        description = "Invoke a DSC expression.",
+        description = "Invoke a DSC expression, optionally against the parameters and variables of a configuration.",
         annotations(
             title = "Invoke a DSC expression",
-            read_only_hint = false,
+            read_only_hint = true,
             destructive_hint = false,
             idempotent_hint = true,
             open_world_hint = true,
         )
     )]
-    pub async fn invoke_dsc_expression(&self, Parameters(ExpressionRequest { expression }): Parameters<ExpressionRequest>) -> Result<Json<ExpressionResponse>, McpError> {
+    pub async fn invoke_dsc_expression(&self, Parameters(ExpressionRequest { expression, configuration, parameters }): Parameters<ExpressionRequest>) -> Result<Json<ExpressionResponse>, McpError> {
+        let policy = self.policy.clone();
         let result = task::spawn_blocking(move || {
+            let context = match configuration {
+                Some(configuration) => {
+                    let config = parse_configuration(&configuration)?;
+                    policy.check_configuration(PolicyOperation::Get, &config)?;
+                    new_configurator(&config, parameters.as_deref())?.context
+                },
+                None => Context::new(),
+            };
             let mut statement = Statement::new().map_err(|e| McpError::internal_error(t!("server.invoke_dsc_expression.parserInitializationFailed", error = e), None))?;
-            let result = statement.parse_and_execute(&expression, &Context::new())
+            let result = statement.parse_and_execute(&expression, &context)
                 .map_err(|e| McpError::invalid_request(t!("server.invoke_dsc_expression.expressionEvaluationFailed", expression = expression, error = e), None))?;
             Ok(ExpressionResponse { result: ExpressionResult::Value(result) })
         }).await.map_err(|e| McpError::internal_error(e.to_string(), None))??;
@@ -1,13 +1,15 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::server::mcp_server::McpServer;
+use crate::server::{mcp_server::McpServer, policy::PolicyOperation};
 use dsc_lib::{
-    DscManager, configure::config_doc::ExecutionKind,
+    DscManager,
     discovery::discovery_trait::DiscoveryFilter,
     dscresources::{
         dscresource::Invoke,
         invoke_result::{
+            DeleteResult,
+            DeleteResultKind,
             ExportResult,
             GetResult,
             SetResult,
@@ -31,6 +33,18 @@ pub enum DscOperation {
     Delete,
 }
 
+impl From<&DscOperation> for PolicyOperation {
+    fn from(operation: &DscOperation) -> Self {
+        match operation {
+            DscOperation::Get => PolicyOperation::Get,
+            DscOperation::Set => PolicyOperation::Set,
+            DscOperation::Test => PolicyOperation::Test,
+            DscOperation::Export => PolicyOperation::Export,
+            DscOperation::Delete => PolicyOperation::Delete,
+        }
+    }
+}
+
 #[derive(Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ResourceOperationResult {
@@ -39,6 +53,7 @@ pub enum ResourceOperationResult {
     TestResult(TestResult),
     ExportResult(ExportResult),
     DeleteResult { success: bool },
+    DeleteWhatIfResult(DeleteResult),
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -69,6 +84,8 @@ impl McpServer {
         )
     )]
     pub async fn invoke_dsc_resource(&self, Parameters(InvokeDscResourceRequest { operation, resource_type, properties_json }): Parameters<InvokeDscResourceRequest>) -> Result<Json<InvokeDscResourceResponse>, McpError> {
+        self.policy.check(PolicyOperation::from(&operation), &resource_type)?;
+        let execution_kind = self.policy.execution_kind();
         let result = task::spawn_blocking(move || {
             let mut dsc = DscManager::new();
             let Some(resource) = dsc.find_resource(&DiscoveryFilter::new(&resource_type, None, None)).unwrap_or(None) else {
@@ -83,7 +100,7 @@ impl McpServer {
                     Ok(ResourceOperationResult::GetResult(result))
                 },
                 DscOperation::Set => {
-                    let result = match resource.set(&properties_json, false, &ExecutionKind::Actual) {
+                    let result = match resource.set(&properties_json, false, &execution_kind) {
                         Ok(res) => res,
                         Err(e) => return Err(McpError::internal_error(e.to_string(), None)),
                     };
@@ -97,8 +114,11 @@ impl McpServer {
                     Ok(ResourceOperationResult::TestResult(result))
                 },
                 DscOperation::Delete => {
-                    match resource.delete(&properties_json, &ExecutionKind::Actual) {
-                        Ok(_) => Ok(ResourceOperationResult::DeleteResult { success: true }),
+                    // in what-if mode the simulated result is returned instead of reporting a delete
+                    match resource.delete(&properties_json, &execution_kind) {
+                        Ok(DeleteResultKind::ResourceActual) => Ok(ResourceOperationResult::DeleteResult { success: true }),
+                        Ok(DeleteResultKind::ResourceWhatIf(result)) => Ok(ResourceOperationResult::DeleteWhatIfResult(result)),
+                        Ok(DeleteResultKind::SyntheticWhatIf(result)) => Ok(ResourceOperationResult::TestResult(result)),
                         Err(e) => Err(McpError::internal_error(e.to_string(), None)),
                     }
                 },
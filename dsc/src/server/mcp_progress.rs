@@ -0,0 +1,139 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use dsc_lib::{
+    configure::{
+        Configurator,
+        config_progress::{
+            ConfigurationProgressListener,
+            ConfigurationResourceCompletedEvent,
+            ConfigurationResourceCompletionStatus,
+            ConfigurationResourceStartedEvent,
+        },
+    },
+    progress::Progress,
+};
+use rmcp::{
+    RoleServer,
+    model::{ProgressNotificationParam, ProgressToken},
+    service::RequestContext,
+};
+use rust_i18n::t;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::debug;
+
+/// Sends MCP progress notifications for the resources of a configuration.
+///
+/// The configurator runs on a blocking thread so notifications are queued to a task
+/// that sends them to the client in the order the resources are processed.
+struct McpProgressListener {
+    token: ProgressToken,
+    sender: mpsc::UnboundedSender<ProgressNotificationParam>,
+}
+
+impl McpProgressListener {
+    fn notify(&self, progress: &Progress, message: String) {
+        let notification = ProgressNotificationParam::new(self.token.clone(), progress.completed_items as f64)
+            .with_total(progress.total_items as f64)
+            .with_message(message);
+        // the receiver is only dropped once the operation has finished
+        let _ = self.sender.send(notification);
+    }
+}
+
+impl ConfigurationProgressListener for McpProgressListener {
+    fn resource_started(&self, event: &ConfigurationResourceStartedEvent, progress: &Progress) {
+        self.notify(progress, t!("server.mcp_progress.resourceStarted", resource = event.resource).to_string());
+    }
+
+    fn resource_completed(&self, event: &ConfigurationResourceCompletedEvent, progress: &Progress) {
+        let message = match event.status {
+            ConfigurationResourceCompletionStatus::Success => t!("server.mcp_progress.resourceCompleted", resource = event.resource),
+            ConfigurationResourceCompletionStatus::Failure => t!("server.mcp_progress.resourceFailed", resource = event.resource),
+        };
+        self.notify(progress, message.to_string());
+    }
+}
+
+/// Attaches the progress reporting and cancellation of a tool request to a configurator.
+pub struct ProgressReporter {
+    listener: Option<Arc<McpProgressListener>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ProgressReporter {
+    /// Attach the progress reporting and cancellation to a configurator.
+    ///
+    /// # Arguments
+    ///
+    /// * `configurator` - The configurator running the operation.
+    pub fn attach(&self, configurator: &mut Configurator) {
+        if let Some(listener) = &self.listener {
+            configurator.set_progress_listener(listener.clone());
+        }
+        configurator.set_cancellation_flag(self.cancelled.clone());
+    }
+}
+
+/// Reports the progress of a configuration operation to the MCP client and stops the
+/// operation after the current resource instance when the client cancels the request.
+pub struct ConfigurationProgress {
+    notifier: Option<JoinHandle<()>>,
+    cancellation_watcher: JoinHandle<()>,
+}
+
+impl ConfigurationProgress {
+    /// Create the progress reporting for a tool request along with the reporter to attach to the configurator.
+    ///
+    /// Progress notifications are only sent if the client supplied a progress token with the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The context of the tool request.
+    #[must_use]
+    pub fn new(context: &RequestContext<RoleServer>) -> (Self, ProgressReporter) {
+        let (listener, notifier) = match context.meta.get_progress_token() {
+            Some(token) => {
+                let (sender, mut receiver) = mpsc::unbounded_channel::<ProgressNotificationParam>();
+                let peer = context.peer.clone();
+                let notifier = tokio::spawn(async move {
+                    while let Some(notification) = receiver.recv().await {
+                        if let Err(err) = peer.notify_progress(notification).await {
+                            debug!("{}: {err}", t!("server.mcp_progress.failedNotify"));
+                        }
+                    }
+                });
+                (Some(Arc::new(McpProgressListener { token, sender })), Some(notifier))
+            },
+            None => (None, None),
+        };
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancellation_watcher = tokio::spawn({
+            let ct = context.ct.clone();
+            let cancelled = cancelled.clone();
+            async move {
+                ct.cancelled().await;
+                debug!("{}", t!("server.mcp_progress.cancelled"));
+                cancelled.store(true, Ordering::SeqCst);
+            }
+        });
+
+        (Self { notifier, cancellation_watcher }, ProgressReporter { listener, cancelled })
+    }
+
+    /// Wait for the queued progress notifications to be sent so they arrive before the tool result.
+    pub async fn finish(mut self) {
+        // the channel closes once the reporter and the configurator it was attached to are dropped
+        if let Some(notifier) = self.notifier.take() {
+            let _ = notifier.await;
+        }
+    }
+}
+
+impl Drop for ConfigurationProgress {
+    fn drop(&mut self) {
+        self.cancellation_watcher.abort();
+    }
+}
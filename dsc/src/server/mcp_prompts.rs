@@ -0,0 +1,76 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::server::mcp_server::McpServer;
+use dsc_lib::{
+    DscManager,
+    discovery::discovery_trait::DiscoveryFilter,
+    dscresources::dscresource::Invoke,
+    types::FullyQualifiedTypeName,
+};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{PromptMessage, Role},
+    prompt,
+    prompt_router,
+};
+use rust_i18n::t;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tokio::task;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AuthorConfigurationRequest {
+    #[schemars(description = "The type name of the DSC resource to author a configuration for")]
+    pub resource_type: FullyQualifiedTypeName,
+    #[schemars(description = "Optional description of the desired state the configuration should enforce")]
+    pub goal: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ExplainConfigurationRequest {
+    #[schemars(description = "The DSC configuration document as a YAML or JSON string")]
+    pub configuration: String,
+}
+
+#[prompt_router(router = "dsc_prompt_router", vis = "pub")]
+impl McpServer {
+    #[prompt(
+        name = "author_configuration",
+        description = "Author a DSC configuration document that uses the specified resource"
+    )]
+    pub async fn author_configuration(&self, Parameters(AuthorConfigurationRequest { resource_type, goal }): Parameters<AuthorConfigurationRequest>) -> Result<Vec<PromptMessage>, McpError> {
+        let schema = task::spawn_blocking({
+            let resource_type = resource_type.clone();
+            move || {
+                let mut dsc = DscManager::new();
+                let Some(resource) = dsc.find_resource(&DiscoveryFilter::new(&resource_type, None, None)).unwrap_or(None) else {
+                    return Err(McpError::invalid_params(t!("server.mcp_prompts.resourceNotFound", resource = resource_type), None));
+                };
+                Ok(resource.schema().unwrap_or_default())
+            }
+        }).await.map_err(|e| McpError::internal_error(e.to_string(), None))??;
+
+        let goal = goal.unwrap_or_else(|| t!("server.mcp_prompts.defaultGoal").to_string());
+        Ok(vec![
+            PromptMessage::new_text(
+                Role::User,
+                t!("server.mcp_prompts.authorConfiguration", resource = resource_type, goal = goal, schema = schema).to_string(),
+            ),
+        ])
+    }
+
+    #[prompt(
+        name = "explain_configuration",
+        description = "Explain what a DSC configuration document does and the changes it would make"
+    )]
+    pub async fn explain_configuration(&self, Parameters(ExplainConfigurationRequest { configuration }): Parameters<ExplainConfigurationRequest>) -> Vec<PromptMessage> {
+        vec![
+            PromptMessage::new_text(
+                Role::User,
+                t!("server.mcp_prompts.explainConfiguration", configuration = configuration).to_string(),
+            ),
+        ]
+    }
+}
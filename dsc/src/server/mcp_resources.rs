@@ -0,0 +1,143 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::{args::SchemaType, util};
+use clap::ValueEnum;
+use dsc_lib::{
+    DscManager,
+    discovery::discovery_trait::DiscoveryFilter,
+    functions::FunctionDispatcher,
+    types::FullyQualifiedTypeName,
+};
+use rmcp::{
+    ErrorData as McpError,
+    model::{ReadResourceResult, Resource, ResourceContents, ResourceTemplate},
+};
+use rust_i18n::t;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const JSON_MIME_TYPE: &str = "application/json";
+const YAML_MIME_TYPE: &str = "application/yaml";
+
+const FUNCTIONS_URI: &str = "dsc://functions";
+const SCHEMAS_URI_PREFIX: &str = "dsc://schemas/";
+const EXAMPLES_URI_PREFIX: &str = "dsc://examples/";
+const RESOURCES_URI_PREFIX: &str = "dsc://resources/";
+const MANIFEST_URI_SUFFIX: &str = "/manifest";
+
+/// Example configurations embedded from the `examples` folder so they're available with any installation.
+/// The list is generated by the build script from the files in the folder.
+const EXAMPLES: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+/// The text of resource manifests already read by the server, keyed by the lowercase type name,
+/// so discovery only runs the first time a manifest is read.
+pub type ManifestCache = Arc<Mutex<HashMap<String, String>>>;
+
+/// List the static resources exposed by the MCP server.
+#[must_use]
+pub fn list_resources() -> Vec<Resource> {
+    let mut resources = vec![
+        Resource::new(FUNCTIONS_URI, "functions")
+            .with_description(t!("server.mcp_resources.functionsDescription"))
+            .with_mime_type(JSON_MIME_TYPE),
+    ];
+
+    for schema_type in SchemaType::value_variants() {
+        let Some(name) = schema_type.to_possible_value().map(|value| value.get_name().to_string()) else {
+            continue;
+        };
+        resources.push(
+            Resource::new(format!("{SCHEMAS_URI_PREFIX}{name}"), format!("schema-{name}"))
+                .with_description(t!("server.mcp_resources.schemaDescription", name = name))
+                .with_mime_type(JSON_MIME_TYPE)
+        );
+    }
+
+    for (name, content) in EXAMPLES {
+        let mut resource = Resource::new(format!("{EXAMPLES_URI_PREFIX}{name}"), format!("example-{name}"))
+            .with_mime_type(YAML_MIME_TYPE);
+        if let Some(description) = example_description(content) {
+            resource = resource.with_description(description);
+        }
+        resources.push(resource);
+    }
+
+    resources
+}
+
+/// List the resource templates exposed by the MCP server.
+#[must_use]
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate::new(format!("{RESOURCES_URI_PREFIX}{{type}}{MANIFEST_URI_SUFFIX}"), "resource-manifest")
+            .with_description(t!("server.mcp_resources.manifestDescription"))
+            .with_mime_type(JSON_MIME_TYPE),
+    ]
+}
+
+/// Read the contents of a resource exposed by the MCP server.
+///
+/// # Arguments
+///
+/// * `uri` - The URI of the resource to read.
+/// * `manifest_cache` - The resource manifests already read by the server.
+///
+/// # Errors
+///
+/// This function will return an error if the URI doesn't identify a resource.
+pub fn read_resource(uri: &str, manifest_cache: &ManifestCache) -> Result<ReadResourceResult, McpError> {
+    let contents = if uri == FUNCTIONS_URI {
+        ResourceContents::text(to_json(&FunctionDispatcher::new().list())?, uri).with_mime_type(JSON_MIME_TYPE)
+    } else if let Some(name) = uri.strip_prefix(SCHEMAS_URI_PREFIX) {
+        let Ok(schema_type) = SchemaType::from_str(name, true) else {
+            return Err(not_found(uri));
+        };
+        ResourceContents::text(to_json(util::get_schema(schema_type).as_value())?, uri).with_mime_type(JSON_MIME_TYPE)
+    } else if let Some(name) = uri.strip_prefix(EXAMPLES_URI_PREFIX) {
+        let Some((_, content)) = EXAMPLES.iter().find(|(example, _)| *example == name) else {
+            return Err(not_found(uri));
+        };
+        ResourceContents::text(*content, uri).with_mime_type(YAML_MIME_TYPE)
+    } else if let Some(type_name) = uri.strip_prefix(RESOURCES_URI_PREFIX).and_then(|rest| rest.strip_suffix(MANIFEST_URI_SUFFIX)) {
+        let Ok(type_name) = type_name.parse::<FullyQualifiedTypeName>() else {
+            return Err(not_found(uri));
+        };
+        let mut cache = manifest_cache.lock().map_err(|err| McpError::internal_error(err.to_string(), None))?;
+        let text = match cache.get(&type_name.to_lowercase()) {
+            Some(text) => text.clone(),
+            None => {
+                let mut dsc = DscManager::new();
+                let Some(resource) = dsc.find_resource(&DiscoveryFilter::new(&type_name, None, None)).unwrap_or(None) else {
+                    return Err(not_found(uri));
+                };
+                // adapted resources don't have a manifest so the resource definition from the adapter is returned
+                let text = match &resource.manifest {
+                    Some(manifest) => to_json(manifest)?,
+                    None => to_json(resource)?,
+                };
+                cache.insert(type_name.to_lowercase(), text.clone());
+                text
+            }
+        };
+        ResourceContents::text(text, uri).with_mime_type(JSON_MIME_TYPE)
+    } else {
+        return Err(not_found(uri));
+    };
+
+    Ok(ReadResourceResult::new(vec![contents]))
+}
+
+fn example_description(content: &str) -> Option<String> {
+    let first_line = content.lines().next()?;
+    first_line.strip_prefix('#').map(|comment| comment.trim().to_string())
+}
+
+fn not_found(uri: &str) -> McpError {
+    McpError::resource_not_found(t!("server.mcp_resources.notFound", uri = uri), None)
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, McpError> {
+    serde_json::to_string_pretty(value).map_err(|err| McpError::internal_error(err.to_string(), None))
+}
@@ -1,27 +1,46 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use crate::server::{mcp_resources, policy::ServerPolicy};
+use dsc_lib::scrub::{SecureValueScope, scrub, scrub_result, show_secure_values};
 use rmcp::{
     ErrorData as McpError,
-    handler::server::tool::ToolRouter,
-    model::{InitializeResult, InitializeRequestParams, ServerCapabilities, ServerInfo},
+    handler::server::{router::prompt::PromptRouter, tool::{ToolCallContext, ToolRouter}},
+    model::{
+        CallToolRequestParams, CallToolResult, InitializeResult, InitializeRequestParams, ListResourceTemplatesResult,
+        ListResourcesResult, PaginatedRequestParams, ReadResourceRequestParams, ReadResourceResult, ServerCapabilities,
+        ServerInfo,
+    },
     service::{RequestContext, RoleServer},
     ServerHandler,
+    prompt_handler,
     tool_handler,
 };
 use rust_i18n::t;
+use std::sync::Arc;
+use tokio::task;
 
 #[derive(Debug, Clone)]
 pub struct McpServer {
-    tool_router: ToolRouter<Self>
+    tool_router: ToolRouter<Self>,
+    prompt_router: PromptRouter<Self>,
+    pub policy: Arc<ServerPolicy>,
+    manifest_cache: mcp_resources::ManifestCache,
 }
 
 impl McpServer {
     #[must_use]
     pub fn new() -> Self {
+        Self::with_policy(Arc::new(ServerPolicy::default()))
+    }
+
+    /// Create a server that restricts its tools using the specified policy.
+    #[must_use]
+    pub fn with_policy(policy: Arc<ServerPolicy>) -> Self {
         Self {
             tool_router:
-                Self::invoke_dsc_config_router()
+                Self::diff_dsc_config_router()
+                + Self::invoke_dsc_config_router()
                 + Self::invoke_dsc_expression_router()
                 + Self::invoke_dsc_function_router()
                 + Self::invoke_dsc_resource_router()
@@ -29,6 +48,11 @@ impl McpServer {
                 + Self::list_dsc_resources_router()
                 + Self::show_dsc_resource_router()
                 + Self::show_dsc_schema_router()
+                + Self::validate_dsc_config_router()
+                + Self::what_if_dsc_config_router(),
+            prompt_router: Self::dsc_prompt_router(),
+            policy,
+            manifest_cache: mcp_resources::ManifestCache::default(),
         }
     }
 }
@@ -40,18 +64,54 @@ impl Default for McpServer {
 }
 
 #[tool_handler(router = self.tool_router)]
+#[prompt_handler(router = self.prompt_router)]
 impl ServerHandler for McpServer {
     fn get_info(&self) -> ServerInfo {
         let mut info = ServerInfo::new(
             ServerCapabilities::builder()
                 .enable_tools()
+                .enable_resources()
+                .enable_prompts()
                 .build()
         );
         info.instructions = Some(t!("server.mod.instructions").to_string());
         info
     }
 
+    async fn call_tool(&self, request: CallToolRequestParams, context: RequestContext<RoleServer>) -> Result<CallToolResult, McpError> {
+        // the secure values used by the tool are scrubbed from its result and forgotten once it completes
+        let _scope = SecureValueScope::new();
+        let result = self.tool_router.call(ToolCallContext::new(self, request, context)).await;
+        match result {
+            Ok(result) => {
+                let value = serde_json::to_value(result).map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                serde_json::from_value(scrub_result(value)).map_err(|e| McpError::internal_error(e.to_string(), None))
+            },
+            Err(mut err) => {
+                if !show_secure_values() {
+                    err.message = scrub(&err.message).into_owned().into();
+                }
+                Err(err)
+            },
+        }
+    }
+
     async fn initialize(&self, _request: InitializeRequestParams, _context: RequestContext<RoleServer>) -> Result<InitializeResult, McpError> {
         Ok(self.get_info())
     }
+
+    async fn list_resources(&self, _request: Option<PaginatedRequestParams>, _context: RequestContext<RoleServer>) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult::with_all_items(mcp_resources::list_resources()))
+    }
+
+    async fn list_resource_templates(&self, _request: Option<PaginatedRequestParams>, _context: RequestContext<RoleServer>) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(mcp_resources::list_resource_templates()))
+    }
+
+    async fn read_resource(&self, request: ReadResourceRequestParams, _context: RequestContext<RoleServer>) -> Result<ReadResourceResult, McpError> {
+        let manifest_cache = self.manifest_cache.clone();
+        task::spawn_blocking(move || mcp_resources::read_resource(&request.uri, &manifest_cache))
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+    }
 }
@@ -1,32 +1,55 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use crate::args::ServerTransport;
+use crate::server::http_transport::start_http_server_async;
 use crate::server::mcp_server::McpServer;
+use crate::server::policy::ServerPolicy;
 use rmcp::{
     ErrorData as McpError,
     ServiceExt,
     transport::stdio,
 };
 use rust_i18n::t;
+use std::sync::Arc;
 
+pub mod diff_dsc_config;
+pub mod http_transport;
 pub mod invoke_dsc_config;
 pub mod invoke_dsc_expression;
 pub mod invoke_dsc_function;
 pub mod invoke_dsc_resource;
 pub mod list_dsc_functions;
 pub mod list_dsc_resources;
+pub mod mcp_progress;
+pub mod mcp_prompts;
+pub mod mcp_resources;
 pub mod mcp_server;
+pub mod policy;
 pub mod show_dsc_resource;
 pub mod show_dsc_schema;
+pub mod validate_dsc_config;
+pub mod what_if_dsc_config;
 
 /// This function initializes and starts the MCP server, handling any errors that may occur.
 ///
+/// # Arguments
+///
+/// * `transport` - The transport to serve the MCP server over.
+/// * `listen` - The socket address to listen on when using the HTTP transport.
+/// * `allow_remote` - Whether the HTTP transport may listen on a non-loopback address.
+/// * `policy` - The policy restricting the tools of the MCP server.
+///
 /// # Errors
 ///
 /// This function will return an error if the MCP server fails to start.
-pub async fn start_server_async() -> Result<(), McpError> {
+pub async fn start_server_async(transport: ServerTransport, listen: &str, allow_remote: bool, policy: Arc<ServerPolicy>) -> Result<(), McpError> {
+    if transport == ServerTransport::Http {
+        return start_http_server_async(listen, allow_remote, policy).await;
+    }
+
     // Initialize the MCP server
-    let server = McpServer::new();
+    let server = McpServer::with_policy(policy);
 
     // Try to create the service with proper error handling
     let service = server.serve(stdio()).await
@@ -45,11 +68,11 @@ pub async fn start_server_async() -> Result<(), McpError> {
 /// # Errors
 ///
 /// This function will return an error if the MCP server fails to start or if the tokio runtime cannot be created.
-pub fn start_server() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub fn start_server(transport: ServerTransport, listen: &str, allow_remote: bool, policy: ServerPolicy) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let rt = tokio::runtime::Runtime::new()
         .map_err(|e| McpError::internal_error(t!("server.mod.failedToCreateRuntime", error = e.to_string()), None))?;
 
-    rt.block_on(start_server_async())
+    rt.block_on(start_server_async(transport, listen, allow_remote, Arc::new(policy)))
         .map_err(|e| McpError::internal_error(t!("server.mod.failedToStart", error = e.to_string()), None))?;
     Ok(())
 }
@@ -0,0 +1,212 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::resolve::INCLUDE_RESOURCE_TYPE;
+use clap::ValueEnum;
+use dsc_lib::{
+    configure::{
+        config_doc::{Configuration, ExecutionKind, Resource, ResourceKind},
+        module::expand_modules,
+    },
+    types::{FullyQualifiedTypeName, TypeNameFilter},
+    util::get_setting,
+};
+use rmcp::ErrorData as McpError;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+/// The name of the setting containing the MCP server policy.
+pub const SERVER_POLICY_SETTING: &str = "mcpServer";
+
+/// The modes the MCP server can run in, ordered from least to most restrictive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerMode {
+    /// All operations are allowed.
+    #[default]
+    Full,
+    /// Mutating operations are run as what-if.
+    WhatIf,
+    /// Mutating operations are denied.
+    ReadOnly,
+}
+
+/// The operations checked against the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PolicyOperation {
+    Get,
+    Set,
+    Test,
+    Export,
+    Delete,
+    WhatIf,
+}
+
+impl PolicyOperation {
+    fn is_mutating(self) -> bool {
+        matches!(self, PolicyOperation::Set | PolicyOperation::Delete)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+struct ServerPolicySetting {
+    #[serde(default)]
+    mode: ServerMode,
+    allowed_resources: Option<Vec<TypeNameFilter>>,
+}
+
+/// The restrictions applied to the tools of the MCP server.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerPolicy {
+    mode: ServerMode,
+    /// A resource type must match a filter in every list to be allowed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allowed_resources: Vec<Vec<TypeNameFilter>>,
+}
+
+impl ServerPolicy {
+    /// Create the policy from the startup options and the `mcpServer` setting.
+    ///
+    /// Restrictions are combined so the most restrictive mode wins and a resource
+    /// type must be allowed by both the setting and the startup options.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The mode requested at startup.
+    /// * `allowed_resources` - The resource types allowed at startup, empty to allow all.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the setting is invalid.
+    pub fn new(mode: Option<ServerMode>, allowed_resources: &[TypeNameFilter]) -> Result<Self, McpError> {
+        let setting = get_server_policy_setting()?;
+        let mut policy = Self {
+            mode: setting.mode.max(mode.unwrap_or_default()),
+            allowed_resources: Vec::new(),
+        };
+        if let Some(setting_allowed) = setting.allowed_resources {
+            policy.allowed_resources.push(setting_allowed);
+        }
+        if !allowed_resources.is_empty() {
+            policy.allowed_resources.push(allowed_resources.to_vec());
+        }
+        debug!("{}", t!("server.policy.usingPolicy", mode = policy.mode : {:?}));
+        Ok(policy)
+    }
+
+    /// Get the execution kind to use for mutating operations.
+    #[must_use]
+    pub fn execution_kind(&self) -> ExecutionKind {
+        if self.mode == ServerMode::WhatIf {
+            ExecutionKind::WhatIf
+        } else {
+            ExecutionKind::Actual
+        }
+    }
+
+    /// Check whether an operation on a resource type is allowed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the policy as data if the operation is denied.
+    pub fn check(&self, operation: PolicyOperation, resource_type: &FullyQualifiedTypeName) -> Result<(), McpError> {
+        if self.mode == ServerMode::ReadOnly && operation.is_mutating() {
+            return Err(self.denied(
+                t!("server.policy.readOnly", operation = operation : {:?}).to_string(),
+                operation,
+                resource_type,
+            ));
+        }
+        if !self.is_resource_allowed(resource_type) {
+            return Err(self.denied(
+                t!("server.policy.resourceNotAllowed", resource = resource_type).to_string(),
+                operation,
+                resource_type,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check whether an operation is allowed for every resource in a configuration.
+    ///
+    /// Modules are expanded first so the resources of their templates are checked.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error with the policy as data if the operation is denied for any resource,
+    /// or an invalid params error if the modules can't be expanded.
+    pub fn check_configuration(&self, operation: PolicyOperation, configuration: &Configuration) -> Result<(), McpError> {
+        let mut configuration = configuration.clone();
+        expand_modules(&mut configuration).map_err(|err| McpError::invalid_params(err.to_string(), None))?;
+        self.check_resources(operation, &configuration.resources)
+    }
+
+    fn check_resources(&self, operation: PolicyOperation, resources: &[Resource]) -> Result<(), McpError> {
+        for resource in resources {
+            // an existing resource is only read, whatever the operation on the configuration
+            let operation = if resource.existing == Some(true) { PolicyOperation::Get } else { operation };
+            // modules nested in a group are only expanded when the group runs, so they can't be checked against an allow-list
+            if !self.allowed_resources.is_empty() && resource.kind == Some(ResourceKind::Module) {
+                return Err(self.denied(
+                    t!("server.policy.moduleNotAllowed", name = resource.name).to_string(),
+                    operation,
+                    &resource.resource_type,
+                ));
+            }
+            self.check(operation, &resource.resource_type)?;
+            // the configuration of an include isn't known until it's resolved, so it can't be checked against an allow-list
+            if !self.allowed_resources.is_empty() && resource.resource_type == INCLUDE_RESOURCE_TYPE {
+                return Err(self.denied(
+                    t!("server.policy.includeNotAllowed").to_string(),
+                    operation,
+                    &resource.resource_type,
+                ));
+            }
+            if let Some(nested) = &resource.resources {
+                self.check_resources(operation, nested)?;
+            }
+            // group and adapter resources run the resources in their properties
+            if let Some(nested) = resource.properties.as_ref()
+                .and_then(|properties| properties.get("resources"))
+                .and_then(|nested| serde_json::from_value::<Vec<Resource>>(nested.clone()).ok()) {
+                self.check_resources(operation, &nested)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_resource_allowed(&self, resource_type: &FullyQualifiedTypeName) -> bool {
+        self.allowed_resources.iter().all(|filters| filters.iter().any(|filter| filter.is_match(resource_type)))
+    }
+
+    fn denied(&self, message: String, operation: PolicyOperation, resource_type: &FullyQualifiedTypeName) -> McpError {
+        McpError::invalid_request(message, Some(json!({
+            "operation": operation,
+            "resourceType": resource_type,
+            "policy": self,
+        })))
+    }
+}
+
+fn get_server_policy_setting() -> Result<ServerPolicySetting, McpError> {
+    let Ok(v) = get_setting(SERVER_POLICY_SETTING) else {
+        return Ok(ServerPolicySetting::default());
+    };
+
+    // if there is a policy value defined - use it; otherwise use setting value
+    let value = if v.policy != serde_json::Value::Null {
+        v.policy
+    } else if v.setting != serde_json::Value::Null {
+        v.setting
+    } else {
+        return Ok(ServerPolicySetting::default());
+    };
+
+    serde_json::from_value::<ServerPolicySetting>(value)
+        .map_err(|err| McpError::invalid_params(t!("server.policy.invalidSetting", error = err.to_string()), None))
+}
@@ -0,0 +1,87 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::{
+    args::SchemaType,
+    server::{invoke_dsc_config::parse_configuration, mcp_server::McpServer, policy::PolicyOperation},
+    subcommand::validate_config_resources,
+    util::get_schema,
+};
+use dsc_lib::{dscresources::dscresource::validate_json, progress::ProgressFormat, types::FullyQualifiedTypeName};
+use rmcp::{ErrorData as McpError, Json, tool, tool_router, handler::server::wrapper::Parameters};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::task;
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiagnostic {
+    /// The name of the resource instance the diagnostic applies to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_name: Option<String>,
+    /// The type of the resource instance the diagnostic applies to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<FullyQualifiedTypeName>,
+    /// The reason the configuration is invalid.
+    pub message: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ValidateDscConfigResponse {
+    pub valid: bool,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ValidateDscConfigRequest {
+    #[schemars(description = "The DSC configuration document as a YAML string")]
+    pub configuration: String,
+}
+
+impl ConfigDiagnostic {
+    fn configuration(message: String) -> Self {
+        Self { resource_name: None, resource_type: None, message }
+    }
+}
+
+#[tool_router(router = validate_dsc_config_router, vis = "pub")]
+impl McpServer {
+    #[tool(
+        description = "Validate a DSC configuration document and the properties of each resource instance against their schemas",
+        annotations(
+            title = "Validate a DSC configuration document and the properties of each resource instance against their schemas",
+            read_only_hint = true,
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = true,
+        )
+    )]
+    pub async fn validate_dsc_config(&self, Parameters(ValidateDscConfigRequest { configuration }): Parameters<ValidateDscConfigRequest>) -> Result<Json<ValidateDscConfigResponse>, McpError> {
+        let policy = self.policy.clone();
+        let diagnostics = task::spawn_blocking(move || {
+            let config = match parse_configuration(&configuration) {
+                Ok(config) => config,
+                Err(err) => return Ok(vec![ConfigDiagnostic::configuration(err.message.to_string())]),
+            };
+            // validating runs the schema executable of each resource
+            policy.check_configuration(PolicyOperation::Get, &config)?;
+            let schema = serde_json::to_value(get_schema(SchemaType::Configuration)).map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            let config_value = serde_json::to_value(&config).map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            if let Err(err) = validate_json("Configuration", &schema, &config_value) {
+                return Ok(vec![ConfigDiagnostic::configuration(err.to_string())]);
+            }
+
+            let failures = match validate_config_resources(&config_value, ProgressFormat::None) {
+                Ok(failures) => failures,
+                Err(err) => return Ok(vec![ConfigDiagnostic::configuration(err.to_string())]),
+            };
+            Ok(failures.into_iter().map(|failure| ConfigDiagnostic {
+                resource_name: Some(failure.name),
+                resource_type: Some(failure.resource_type),
+                message: failure.error.to_string(),
+            }).collect())
+        }).await.map_err(|e| McpError::internal_error(e.to_string(), None))??;
+
+        Ok(Json(ValidateDscConfigResponse { valid: diagnostics.is_empty(), diagnostics }))
+    }
+}
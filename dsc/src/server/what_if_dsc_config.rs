@@ -0,0 +1,57 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::server::{
+    invoke_dsc_config::{new_configurator, parse_configuration},
+    mcp_progress::ConfigurationProgress,
+    mcp_server::McpServer,
+    policy::PolicyOperation,
+};
+use dsc_lib::configure::{config_doc::ExecutionKind, config_result::ConfigurationSetResult};
+use rmcp::{ErrorData as McpError, Json, RoleServer, tool, tool_router, handler::server::wrapper::Parameters, service::RequestContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::task;
+
+#[derive(Serialize, JsonSchema)]
+pub struct WhatIfDscConfigResponse {
+    pub result: Box<ConfigurationSetResult>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct WhatIfDscConfigRequest {
+    #[schemars(description = "The DSC configuration document as a YAML string")]
+    pub configuration: String,
+    #[schemars(description = "Optional parameters to pass to the configuration as a YAML string")]
+    pub parameters: Option<String>,
+}
+
+#[tool_router(router = what_if_dsc_config_router, vis = "pub")]
+impl McpServer {
+    #[tool(
+        description = "Show the changes a DSC configuration Set operation would make without changing the system",
+        annotations(
+            title = "Show the changes a DSC configuration Set operation would make without changing the system",
+            read_only_hint = true,
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = true,
+        )
+    )]
+    pub async fn what_if_dsc_config(&self, context: RequestContext<RoleServer>, Parameters(WhatIfDscConfigRequest { configuration, parameters }): Parameters<WhatIfDscConfigRequest>) -> Result<Json<WhatIfDscConfigResponse>, McpError> {
+        let policy = self.policy.clone();
+        let (progress, reporter) = ConfigurationProgress::new(&context);
+        let result = task::spawn_blocking(move || {
+            let config = parse_configuration(&configuration)?;
+            policy.check_configuration(PolicyOperation::WhatIf, &config)?;
+            let mut configurator = new_configurator(&config, parameters.as_deref())?;
+            reporter.attach(&mut configurator);
+            configurator.context.execution_type = ExecutionKind::WhatIf;
+            configurator.invoke_set(false).map_err(|e| McpError::internal_error(e.to_string(), None))
+        }).await.map_err(|e| McpError::internal_error(e.to_string(), None));
+        progress.finish().await;
+        let result = result??;
+
+        Ok(Json(WhatIfDscConfigResponse { result: Box::new(result) }))
+    }
+}
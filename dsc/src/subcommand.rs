@@ -1,22 +1,30 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use crate::audit::audit_config_set;
 use crate::args::{ConfigSubCommand, SchemaType, ExtensionSubCommand, FunctionSubCommand, GetOutputFormat, ListOutputFormat, OutputFormat, ResourceSubCommand};
-use crate::resolve::{get_contents, Include};
+use crate::resolve::{get_contents, Include, INCLUDE_RESOURCE_TYPE};
 use crate::resource_command::{get_resource, self};
 use crate::tablewriter::Table;
-use crate::util::{get_input, get_schema, in_desired_state, set_dscconfigroot, write_object, DSC_CONFIG_ROOT, EXIT_DSC_ASSERTION_FAILED, EXIT_DSC_ERROR, EXIT_INVALID_ARGS, EXIT_INVALID_INPUT, EXIT_JSON_ERROR};
+use crate::watch::{config_watch, WatchHistory};
+use crate::util::{add_environment_parameters, get_input, get_schema, in_desired_state, set_dscconfigroot, write_object, DSC_CONFIG_ROOT, DSC_LOCK_FILE, PARAMETER_SOURCE_DEFAULT, EXIT_DSC_ASSERTION_FAILED, EXIT_DSC_ERROR, EXIT_INVALID_ARGS, EXIT_INVALID_INPUT, EXIT_JSON_ERROR, EXIT_VALIDATION_FAILED};
 use dsc_lib::types::{FullyQualifiedTypeName, ResourceVersionReq, TypeNameFilter};
 use dsc_lib::{
     configure::{
         config_doc::{
             Configuration,
+            DataType,
             ExecutionKind,
+            Metadata,
+            MicrosoftDscMetadata,
             Resource,
         },
         config_result::ResourceGetResult,
+        lock::ConfigurationLock,
+        parameters::SECURE_VALUE_REDACTED,
         Configurator,
     },
+    discovery::Discovery,
     discovery::discovery_trait::{DiscoveryFilter, DiscoveryKind},
     discovery::command_discovery::ImportedManifest,
     dscerror::DscError,
@@ -27,6 +35,7 @@ use dsc_lib::{
         ValidateResult,
     },
     dscresources::dscresource::{Capability, ImplementedAs, validate_json, validate_properties},
+    dscresources::native_resource::is_native_resource,
     extensions::dscextension::Capability as ExtensionCapability,
     functions::{FunctionCategory, FunctionDispatcher},
     progress::ProgressFormat,
@@ -37,17 +46,29 @@ use rust_i18n::t;
 use core::convert::AsRef;
 use std::{
     collections::HashMap,
+    fs,
     io::{self, IsTerminal},
-    path::Path,
+    path::{Path, PathBuf},
     process::exit
 };
-use tracing::{debug, error, trace};
+use tracing::{debug, error, info, trace};
+
+/// Attach the typed outputs of the configuration to the metadata of the last result so a configuration
+/// including this one can consume them.
+fn add_typed_outputs(configurator: &Configurator, metadata: Option<&mut Option<Metadata>>) {
+    if let Some(outputs) = configurator.get_typed_outputs()
+        && let Some(metadata) = metadata {
+            let metadata = metadata.get_or_insert_with(|| Metadata { microsoft: None, other: serde_json::Map::new() });
+            metadata.microsoft.get_or_insert_with(MicrosoftDscMetadata::default).outputs = Some(outputs);
+        }
+}
 
 pub fn config_get(configurator: &mut Configurator, format: Option<&OutputFormat>, as_group: &bool)
 {
     match configurator.invoke_get() {
-        Ok(result) => {
+        Ok(mut result) => {
             if *as_group {
+                add_typed_outputs(configurator, result.results.last_mut().map(|result| &mut result.metadata));
                 let json = match serde_json::to_string(&(result.results)) {
                     Ok(json) => json,
                     Err(err) => {
@@ -78,11 +99,16 @@ pub fn config_get(configurator: &mut Configurator, format: Option<&OutputFormat>
     }
 }
 
-pub fn config_set(configurator: &mut Configurator, format: Option<&OutputFormat>, as_group: &bool)
+pub fn config_set(configurator: &mut Configurator, format: Option<&OutputFormat>, as_group: &bool, as_include: &bool)
 {
     match configurator.invoke_set(false) {
-        Ok(result) => {
+        Ok(mut result) => {
+            // nested configurations are audited by the parent process from the returned results
+            if !*as_group && !*as_include {
+                audit_config_set(configurator, &result.results);
+            }
             if *as_group {
+                add_typed_outputs(configurator, result.results.last_mut().map(|result| &mut result.metadata));
                 let json = match serde_json::to_string(&(result.results)) {
                     Ok(json) => json,
                     Err(err) => {
@@ -116,7 +142,7 @@ pub fn config_set(configurator: &mut Configurator, format: Option<&OutputFormat>
 pub fn config_test(configurator: &mut Configurator, format: Option<&OutputFormat>, as_group: &bool, as_get: &bool, as_config: &bool, as_assert: &bool)
 {
     match configurator.invoke_test() {
-        Ok(result) => {
+        Ok(mut result) => {
             if *as_group {
                 let json = if *as_config {
                     let mut result_configuration = Configuration::new();
@@ -175,6 +201,7 @@ pub fn config_test(configurator: &mut Configurator, format: Option<&OutputFormat
                     }
                 }
                 else {
+                    add_typed_outputs(configurator, result.results.last_mut().map(|result| &mut result.metadata));
                     if *as_assert {
                         for test_result in &result.results {
                             if !in_desired_state(test_result) {
@@ -273,15 +300,165 @@ fn initialize_config_root(path: Option<&String>) -> Option<String> {
     None
 }
 
+/// Get the path of the lock file to use for a configuration.
+///
+/// # Arguments
+///
+/// * `lock_file` - The lock file path specified by the user.
+/// * `config_file` - The path to the configuration file, if any.
+///
+/// # Returns
+///
+/// The specified lock file path or `<config>.lock.json` next to the configuration file.
+fn get_lock_file_path(lock_file: Option<&String>, config_file: Option<&String>) -> Option<PathBuf> {
+    if let Some(lock_file) = lock_file {
+        return Some(PathBuf::from(lock_file));
+    }
+
+    match config_file {
+        Some(config_file) if config_file != "-" => {
+            let config_path = Path::new(config_file);
+            let stem = config_path.file_stem()?.to_string_lossy();
+            Some(config_path.with_file_name(format!("{stem}.lock.json")))
+        },
+        _ => None,
+    }
+}
+
+fn config_lock(configurator: &mut Configurator, lock_path: Option<&PathBuf>, format: Option<&OutputFormat>, progress_format: ProgressFormat) {
+    let mut lock = match configurator.create_lock() {
+        Ok(lock) => lock,
+        Err(err) => {
+            error!("{}: {err}", t!("subcommand.failedCreateLock"));
+            exit(EXIT_DSC_ERROR);
+        }
+    };
+    if let Err(err) = add_include_locks(&configurator.get_config().resources, &mut lock, progress_format) {
+        error!("{}: {err}", t!("subcommand.failedCreateLock"));
+        exit(EXIT_DSC_ERROR);
+    }
+
+    let Some(lock_path) = lock_path else {
+        let Ok(json) = serde_json::to_string(&lock) else {
+            error!("{}", t!("subcommand.failedSerializeLock"));
+            exit(EXIT_JSON_ERROR);
+        };
+        write_object(&json, format, false);
+        return;
+    };
+
+    let Ok(json) = serde_json::to_string_pretty(&lock) else {
+        error!("{}", t!("subcommand.failedSerializeLock"));
+        exit(EXIT_JSON_ERROR);
+    };
+    if let Err(err) = fs::write(lock_path, json + "\n") {
+        error!("{} '{}': {err}", t!("subcommand.failedWriteLockFile"), lock_path.display());
+        exit(EXIT_DSC_ERROR);
+    }
+    info!("{} '{}'", t!("subcommand.wroteLockFile"), lock_path.display());
+}
+
+/// Add the resources of the configurations included by include resources to the lock.
+///
+/// # Arguments
+///
+/// * `resources` - The resources to search for include resources, including nested resources.
+/// * `lock` - The lock to add the resources to.
+/// * `progress_format` - The format of the progress output.
+///
+/// # Errors
+///
+/// This function will return an error if an included configuration can't be resolved or locked.
+fn add_include_locks(resources: &[Resource], lock: &mut ConfigurationLock, progress_format: ProgressFormat) -> Result<(), String> {
+    for resource in resources {
+        if let Some(nested) = &resource.resources {
+            add_include_locks(nested, lock, progress_format)?;
+        }
+        let Some(properties) = &resource.properties else {
+            continue;
+        };
+        if resource.resource_type != INCLUDE_RESOURCE_TYPE {
+            // resources of group resources run in a child configuration that can also include configurations
+            if let Some(nested) = properties.get("resources").and_then(|nested| serde_json::from_value::<Vec<Resource>>(nested.clone()).ok()) {
+                add_include_locks(&nested, lock, progress_format)?;
+            }
+            continue;
+        }
+
+        let input = serde_json::to_string(properties).map_err(|err| err.to_string())?;
+        let (_, config_json) = get_contents(&input)?;
+        let mut configurator = Configurator::new(&config_json, progress_format).map_err(|err| err.to_string())?;
+        let include_lock = configurator.create_lock().map_err(|err| err.to_string())?;
+        for locked in include_lock.resources {
+            if !lock.resources.contains(&locked) {
+                lock.resources.push(locked);
+            }
+        }
+        add_include_locks(&configurator.get_config().resources, lock, progress_format)?;
+    }
+    Ok(())
+}
+
+fn apply_lock_file(configurator: &mut Configurator, lock_path: &Path, required: bool) {
+    if !lock_path.exists() {
+        if required {
+            error!("{}: '{}'", t!("subcommand.lockFileNotFound"), lock_path.display());
+            exit(EXIT_INVALID_ARGS);
+        }
+        return;
+    }
+
+    info!("{} '{}'", t!("subcommand.applyingLockFile"), lock_path.display());
+    let contents = match fs::read_to_string(lock_path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            error!("{} '{}': {err}", t!("subcommand.failedReadLockFile"), lock_path.display());
+            exit(EXIT_INVALID_INPUT);
+        }
+    };
+    let lock: ConfigurationLock = match serde_json::from_str(&contents) {
+        Ok(lock) => lock,
+        Err(err) => {
+            error!("{} '{}': {err}", t!("subcommand.invalidLockFile"), lock_path.display());
+            exit(EXIT_INVALID_INPUT);
+        }
+    };
+    if let Err(err) = configurator.apply_lock(&lock) {
+        error!("{}: {err}", t!("subcommand.lockMismatch"));
+        exit(EXIT_VALIDATION_FAILED);
+    }
+
+    // set env var so child configurations of group and include resources honor the same lock
+    let lock_path = std::path::absolute(lock_path).unwrap_or_else(|_| lock_path.to_path_buf());
+    unsafe {
+        std::env::set_var(DSC_LOCK_FILE, lock_path);
+    }
+}
+
+fn get_config_output_format(subcommand: &ConfigSubCommand) -> Option<&OutputFormat> {
+    match subcommand {
+        ConfigSubCommand::Get { output_format, .. } |
+        ConfigSubCommand::Set { output_format, .. } |
+        ConfigSubCommand::Test { output_format, .. } |
+        ConfigSubCommand::Validate { output_format, .. } |
+        ConfigSubCommand::Export { output_format, .. } |
+        ConfigSubCommand::Lock { output_format, .. } |
+        ConfigSubCommand::Watch { output_format, .. } |
+        ConfigSubCommand::Resolve { output_format, .. } => output_format.as_ref(),
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 #[allow(clippy::too_many_arguments)]
-pub fn config(subcommand: &ConfigSubCommand, parameters: &Option<String>, mounted_path: Option<&String>, as_group: &bool, as_assert: &bool, as_include: &bool, progress_format: ProgressFormat) {
+pub fn config(subcommand: &ConfigSubCommand, parameters: &Option<String>, mut parameter_sources: HashMap<String, String>, print_effective_parameters: bool, mounted_path: Option<&String>, lock_file: Option<&String>, as_group: &bool, as_assert: &bool, as_include: &bool, progress_format: ProgressFormat) {
     let (new_parameters, json_string) = match subcommand {
         ConfigSubCommand::Get { input, file, .. } |
         ConfigSubCommand::Set { input, file, .. } |
         ConfigSubCommand::Test { input, file, .. } |
         ConfigSubCommand::Validate { input, file, .. } |
-        ConfigSubCommand::Export { input, file, .. } => {
+        ConfigSubCommand::Export { input, file, .. } |
+        ConfigSubCommand::Lock { input, file, .. } |
+        ConfigSubCommand::Watch { input, file, .. } => {
             let new_path = initialize_config_root(file.as_ref());
             let document = get_input(input.as_ref(), new_path.as_ref());
             if *as_include {
@@ -321,11 +498,35 @@ pub fn config(subcommand: &ConfigSubCommand, parameters: &Option<String>, mounte
 
     configurator.context.dsc_version = Some(env!("CARGO_PKG_VERSION").to_string());
 
+    let lock_path = match subcommand {
+        ConfigSubCommand::Get { file, .. } |
+        ConfigSubCommand::Set { file, .. } |
+        ConfigSubCommand::Test { file, .. } |
+        ConfigSubCommand::Lock { file, .. } => get_lock_file_path(lock_file, file.as_ref()),
+        _ => None,
+    };
+
+    // child configurations of group and include resources inherit the lock applied by the parent
+    let inherited_lock_path = if (*as_group || *as_include) && lock_file.is_none() {
+        std::env::var_os(DSC_LOCK_FILE).map(PathBuf::from)
+    } else {
+        unsafe {
+            std::env::remove_var(DSC_LOCK_FILE);
+        }
+        None
+    };
+    let lock_required = lock_file.is_some() || inherited_lock_path.is_some();
+    let lock_path = inherited_lock_path.or(lock_path);
+
+    if let Some(path) = &lock_path && !matches!(subcommand, ConfigSubCommand::Lock { .. }) {
+        apply_lock_file(&mut configurator, path, lock_required);
+    }
+
     if let ConfigSubCommand::Set { what_if , .. } = subcommand && *what_if {
         configurator.context.execution_type = ExecutionKind::WhatIf;
     }
 
-    let parameters: Option<serde_json::Value> = match if new_parameters.is_some() {
+    let mut parameters: Option<serde_json::Value> = match if new_parameters.is_some() {
         &new_parameters
     } else {
         parameters
@@ -373,17 +574,42 @@ pub fn config(subcommand: &ConfigSubCommand, parameters: &Option<String>, mounte
         }
     }
 
+    // nested configurations inherit the environment of the parent, which applies the environment parameters to its own configuration
+    if new_parameters.is_none() && !*as_group && !*as_include && let Err(err) = add_environment_parameters(configurator.get_config(), &mut parameters, &mut parameter_sources) {
+        error!("{}: {err}", t!("subcommand.failedSetParameters"));
+        exit(EXIT_INVALID_INPUT);
+    }
+
     if let Err(err) = configurator.set_context(parameters.as_ref()) {
         error!("{}: {err}", t!("subcommand.failedSetParameters"));
         exit(EXIT_INVALID_INPUT);
     }
 
+    if print_effective_parameters {
+        let mut effective = serde_json::Map::new();
+        for (name, (value, parameter_type)) in &configurator.context.parameters {
+            let value = if *parameter_type == DataType::SecureString || *parameter_type == DataType::SecureObject {
+                serde_json::Value::String(SECURE_VALUE_REDACTED.to_string())
+            } else {
+                value.clone()
+            };
+            let source = parameter_sources.get(name).map_or(PARAMETER_SOURCE_DEFAULT, String::as_str);
+            effective.insert(name.clone(), serde_json::json!({ "value": value, "source": source }));
+        }
+        let Ok(json) = serde_json::to_string(&effective) else {
+            error!("{}", t!("subcommand.failedSerialize"));
+            exit(EXIT_JSON_ERROR);
+        };
+        write_object(&json, get_config_output_format(subcommand), false);
+        return;
+    }
+
     match subcommand {
         ConfigSubCommand::Get { output_format, .. } => {
             config_get(&mut configurator, output_format.as_ref(), as_group);
         },
         ConfigSubCommand::Set { output_format, .. } => {
-            config_set(&mut configurator, output_format.as_ref(), as_group);
+            config_set(&mut configurator, output_format.as_ref(), as_group, as_include);
         },
         ConfigSubCommand::Test { output_format, as_get, as_config, .. } => {
             config_test(&mut configurator, output_format.as_ref(), as_group, as_get, as_config, as_assert);
@@ -427,6 +653,19 @@ pub fn config(subcommand: &ConfigSubCommand, parameters: &Option<String>, mounte
         ConfigSubCommand::Export { output_format, .. } => {
             config_export(&mut configurator, output_format.as_ref());
         },
+        ConfigSubCommand::Lock { output_format, .. } => {
+            config_lock(&mut configurator, lock_path.as_ref(), output_format.as_ref(), progress_format);
+        },
+        ConfigSubCommand::Watch { output_format, mode, interval, count, history, history_size, .. } => {
+            let mut history = match WatchHistory::new(history.as_ref(), usize::try_from(*history_size).unwrap_or(usize::MAX)) {
+                Ok(history) => history,
+                Err(err) => {
+                    error!("{err}");
+                    exit(EXIT_INVALID_ARGS);
+                }
+            };
+            config_watch(&mut configurator, *mode, *interval, *count, &mut history, output_format.as_ref());
+        },
         ConfigSubCommand::Resolve { output_format, .. } => {
             let configuration = match serde_json::from_str(&json_string) {
                 Ok(json) => json,
@@ -480,9 +719,44 @@ pub fn validate_config(config: &Configuration, progress_format: ProgressFormat)
     let schema = serde_json::to_value(get_schema(SchemaType::Configuration))?;
     let config_value = serde_json::to_value(config)?;
     validate_json("Configuration", &schema, &config_value)?;
-    let mut dsc = DscManager::new();
 
     // then validate each resource
+    if let Some(failure) = validate_config_resources(&config_value, progress_format)?.into_iter().next() {
+        return Err(failure.error);
+    }
+
+    Ok(())
+}
+
+/// A resource instance in a configuration that failed validation.
+pub struct ResourceValidationFailure {
+    /// The name of the resource instance.
+    pub name: String,
+    /// The type of the resource instance.
+    pub resource_type: FullyQualifiedTypeName,
+    /// The reason the resource instance is invalid.
+    pub error: DscError,
+}
+
+/// Validate the properties of each resource instance in a configuration.
+///
+/// Unlike `validate_config`, validation continues after a resource instance fails
+/// so every invalid instance is reported.
+///
+/// # Arguments
+///
+/// * `config_value` - The configuration to validate as JSON.
+///
+/// # Returns
+///
+/// The resource instances that failed validation.
+///
+/// # Errors
+///
+/// * `DscError` - The resources couldn't be discovered or a resource type is invalid.
+pub fn validate_config_resources(config_value: &serde_json::Value, progress_format: ProgressFormat) -> Result<Vec<ResourceValidationFailure>, DscError> {
+    let mut dsc = DscManager::new();
+
     let Some(resources) = config_value["resources"].as_array() else {
         return Err(DscError::Validation(t!("subcommand.noResources").to_string()));
     };
@@ -502,30 +776,35 @@ pub fn validate_config(config: &Configuration, progress_format: ProgressFormat)
     }
     dsc.find_resources(&resource_types, progress_format)?;
 
+    let mut failures = Vec::<ResourceValidationFailure>::new();
     for resource_block in resources {
         let Some(type_name) = resource_block["type"].as_str() else {
             return Err(DscError::Validation(t!("subcommand.resourceTypeNotSpecified").to_string()));
         };
-        let type_name = &FullyQualifiedTypeName::parse(type_name)?;
+        let type_name = FullyQualifiedTypeName::parse(type_name)?;
         let require_version = resource_block["requireVersion"]
             .as_str()
             .map(ResourceVersionReq::parse)
             .transpose()?;
+        let name = resource_block["name"].as_str().unwrap_or_default().to_string();
 
-        trace!("{} '{}'", t!("subcommand.validatingResource"), resource_block["name"].as_str().unwrap_or_default());
+        trace!("{} '{name}'", t!("subcommand.validatingResource"));
 
         // get the actual resource
-        let Some(resource) = get_resource(&mut dsc, type_name, require_version.as_ref()) else {
-            return Err(DscError::Validation(format!("{}: '{type_name}'", t!("subcommand.resourceNotFound"))));
+        let Some(resource) = get_resource(&mut dsc, &type_name, require_version.as_ref()) else {
+            let error = DscError::Validation(format!("{}: '{type_name}'", t!("subcommand.resourceNotFound")));
+            failures.push(ResourceValidationFailure { name, resource_type: type_name, error });
+            continue;
         };
 
-        // see if the resource is command based
-        if resource.implemented_as == Some(ImplementedAs::Command) {
-            validate_properties(resource, &resource_block["properties"])?;
+        // see if the resource is command based or implemented in-process
+        if (resource.implemented_as == Some(ImplementedAs::Command) || is_native_resource(resource))
+            && let Err(error) = validate_properties(resource, &resource_block["properties"]) {
+            failures.push(ResourceValidationFailure { name, resource_type: type_name, error });
         }
     }
 
-    Ok(())
+    Ok(failures)
 }
 
 pub fn extension(subcommand: &ExtensionSubCommand, progress_format: ProgressFormat) {
@@ -723,6 +1002,8 @@ fn list_functions(functions: &FunctionDispatcher, function_name: Option<&String>
     });
 
     let mut functions_list = functions.list();
+    let mut discovery = Discovery::new();
+    functions_list.extend(FunctionDispatcher::list_extension_functions(&discovery.get_extensions(&ExtensionCapability::Function)));
     functions_list.sort();
     for function in functions_list {
         if !regex.is_match(&function.name) {
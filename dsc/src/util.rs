@@ -9,6 +9,7 @@ use dsc_lib::{
     configure::{
         config_doc::{
             Configuration,
+            DataType,
             Resource,
             RestartRequired,
         },
@@ -18,6 +19,7 @@ use dsc_lib::{
             ConfigurationTestResult,
             ResourceTestResult,
         },
+        parameters::ComplexInput,
     },
     discovery::{
         command_discovery::ManifestList,
@@ -41,6 +43,7 @@ use dsc_lib::{
         extension_manifest::ExtensionManifest,
     },
     functions::FunctionDefinition,
+    scrub::{scrub, scrub_value, show_secure_values},
     util::{
         get_setting,
         parse_input_to_json,
@@ -50,10 +53,11 @@ use path_absolutize::Absolutize;
 use rust_i18n::t;
 use schemars::{Schema, schema_for};
 use serde::Deserialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::env;
 use std::io::{IsTerminal, Read, stdout, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use syntect::{
     easy::HighlightLines,
@@ -62,7 +66,7 @@ use syntect::{
     util::{as_24_bit_terminal_escaped, LinesWithEndings}
 };
 use tracing::{Level, debug, error, info, warn, trace};
-use tracing_subscriber::{filter::EnvFilter, layer::SubscriberExt, Layer};
+use tracing_subscriber::{filter::EnvFilter, fmt::MakeWriter, layer::SubscriberExt, Layer};
 use tracing_indicatif::IndicatifLayer;
 
 pub const EXIT_SUCCESS: i32 = 0;
@@ -79,6 +83,10 @@ pub const EXIT_BICEP_FAILED: i32 = 10;
 
 pub const DSC_CONFIG_ROOT: &str = "DSC_CONFIG_ROOT";
 pub const DSC_TRACE_LEVEL: &str = "DSC_TRACE_LEVEL";
+pub const DSC_LOCK_FILE: &str = "DSC_LOCK_FILE";
+pub const DSC_PARAM_PREFIX: &str = "DSC_PARAM_";
+pub const PARAMETER_SOURCE_DEFAULT: &str = "default";
+pub const PARAMETER_SOURCE_INLINE: &str = "inline";
 
 #[derive(Deserialize)]
 pub struct TracingSetting {
@@ -240,6 +248,21 @@ pub fn get_schema(schema: SchemaType) -> Schema {
 /// * `format` - The format to use
 /// * `include_separator` - Whether to include a separator for YAML before the object
 pub fn write_object(json: &str, format: Option<&OutputFormat>, include_separator: bool) {
+    let json: &str = &if show_secure_values() {
+        Cow::Borrowed(json)
+    } else {
+        // scrub the strings of the parsed value so a secure value can't alter the structure of the JSON
+        match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(value) => match serde_json::to_string(&scrub_value(&value)) {
+                Ok(scrubbed) => Cow::Owned(scrubbed),
+                Err(err) => {
+                    error!("JSON: {err}");
+                    exit(EXIT_JSON_ERROR);
+                }
+            },
+            Err(_) => scrub(json),
+        }
+    };
     let mut is_json = true;
     let mut output_format = format;
     let mut syntax_color = false;
@@ -325,6 +348,49 @@ pub fn write_object(json: &str, format: Option<&OutputFormat>, include_separator
     }
 }
 
+/// Writer that scrubs secure values from a trace event before writing it.
+pub struct ScrubWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Write for ScrubWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            let buffer = std::mem::take(&mut self.buffer);
+            match std::str::from_utf8(&buffer) {
+                Ok(text) => self.inner.write_all(scrub(text).as_bytes())?,
+                Err(_) => self.inner.write_all(&buffer)?,
+            }
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for ScrubWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Creates a `ScrubWriter` for each trace event so secure values never reach the trace output.
+pub struct ScrubMakeWriter<M> {
+    inner: M,
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for ScrubMakeWriter<M> where M::Writer: Write {
+    type Writer = ScrubWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ScrubWriter { inner: self.inner.make_writer(), buffer: Vec::new() }
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 pub fn enable_tracing(trace_level_arg: Option<&TraceLevel>, trace_format_arg: Option<&TraceFormat>) {
 
@@ -336,7 +402,7 @@ pub fn enable_tracing(trace_level_arg: Option<&TraceLevel>, trace_format_arg: Op
         .unwrap_or_default()
         .add_directive(Level::WARN.into());
     let default_indicatif_layer = IndicatifLayer::new();
-    let default_layer = tracing_subscriber::fmt::Layer::default().with_writer(default_indicatif_layer.get_stderr_writer());
+    let default_layer = tracing_subscriber::fmt::Layer::default().with_writer(ScrubMakeWriter { inner: default_indicatif_layer.get_stderr_writer() });
     let default_fmt = default_layer
                 .with_ansi(true)
                 .with_level(true)
@@ -406,7 +472,7 @@ pub fn enable_tracing(trace_level_arg: Option<&TraceLevel>, trace_format_arg: Op
         .unwrap_or_default()
         .add_directive(tracing_level.into());
     let indicatif_layer = IndicatifLayer::new();
-    let layer = tracing_subscriber::fmt::Layer::default().with_writer(indicatif_layer.get_stderr_writer());
+    let layer = tracing_subscriber::fmt::Layer::default().with_writer(ScrubMakeWriter { inner: indicatif_layer.get_stderr_writer() });
     let with_source = tracing_level == Level::DEBUG || tracing_level == Level::TRACE;
     let fmt = match tracing_setting.format {
         TraceFormat::Default => {
@@ -600,6 +666,22 @@ pub fn in_desired_state(test_result: &ResourceTestResult) -> bool {
     }
 }
 
+/// Get the directory DSC stores local state in.
+///
+/// This is `%LocalAppData%\dsc` on Windows and `$HOME/.dsc` on other platforms.
+///
+/// # Returns
+///
+/// * `Option<PathBuf>` - The directory, or `None` if the environment variable isn't set.
+#[must_use]
+pub fn get_local_data_path() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let path = env::var("LocalAppData").ok().map(|path| Path::new(&path).join("dsc"));
+    #[cfg(not(windows))]
+    let path = env::var("HOME").ok().map(|path| Path::new(&path).join(".dsc"));
+    path
+}
+
 /// Parse input string as JSON or YAML and return a serde_json::Value.
 ///
 /// # Arguments
@@ -705,3 +787,108 @@ pub fn merge_parameters(file_params: &str, inline_params: &str) -> Result<String
     let merged = Value::Object(file_map);
     Ok(serde_json::to_string(&merged)?)
 }
+
+/// Merge the parameters files in order followed by the inline parameters, later sources take precedence.
+///
+/// # Arguments
+///
+/// * `parameters_files` - Paths to the parameters files, `-` reads from STDIN
+/// * `inline_params` - Inline parameters (JSON or YAML format)
+///
+/// # Returns
+///
+/// * `Result<(Option<String>, HashMap<String, String>), DscError>` - Merged parameters as JSON string and the source of each parameter
+///
+/// # Errors
+///
+/// This function will return an error if any parameter set cannot be parsed as valid JSON or YAML
+pub fn merge_parameter_sources(parameters_files: &[String], inline_params: Option<&String>) -> Result<(Option<String>, HashMap<String, String>), DscError> {
+    let mut merged: Option<String> = None;
+    let mut sources = HashMap::new();
+    let inputs = parameters_files.iter()
+        .map(|path| (get_input(None, Some(path)), format!("file:{path}")))
+        .chain(inline_params.map(|params| (params.clone(), PARAMETER_SOURCE_INLINE.to_string())));
+
+    for (content, source) in inputs {
+        if content.is_empty() {
+            continue;
+        }
+
+        let map = params_to_map(&content, &source)?;
+        if let Some(parameters) = map.get("parameters").and_then(|parameters| parameters.as_object()) {
+            for name in parameters.keys() {
+                sources.insert(name.clone(), source.clone());
+            }
+        }
+
+        merged = match merged {
+            Some(merged) => {
+                info!("{}", t!("util.mergingParameters", source = source));
+                Some(merge_parameters(&merged, &content)?)
+            },
+            None => Some(content),
+        };
+    }
+
+    Ok((merged, sources))
+}
+
+/// Add parameters defined in the configuration from `DSC_PARAM_<NAME>` environment variables.
+/// Environment variables take precedence over parameters files, but not over inline parameters.
+/// If the parameters input uses the complex format, the values are added as `{"value": ...}`.
+///
+/// # Arguments
+///
+/// * `config` - The configuration declaring the parameters
+/// * `parameters` - The parameters input to add to
+/// * `sources` - The source of each parameter, updated for parameters read from the environment
+///
+/// # Errors
+///
+/// This function will return an error if the environment variable for a non-string parameter is not valid JSON
+pub fn add_environment_parameters(config: &Configuration, parameters: &mut Option<serde_json::Value>, sources: &mut HashMap<String, String>) -> Result<(), DscError> {
+    let Some(declared) = &config.parameters else {
+        return Ok(());
+    };
+    let is_complex = parameters.as_ref().is_some_and(|input| {
+        input.get("parameters").and_then(serde_json::Value::as_object).is_some_and(|values| !values.is_empty())
+            && serde_json::from_value::<ComplexInput>(input.clone()).is_ok()
+    });
+
+    for (name, parameter) in declared {
+        if sources.get(name).is_some_and(|source| source == PARAMETER_SOURCE_INLINE) {
+            continue;
+        }
+
+        let mut env_name = format!("{DSC_PARAM_PREFIX}{name}");
+        let env_value = match env::var(&env_name) {
+            Ok(value) => value,
+            Err(_) => {
+                env_name = format!("{DSC_PARAM_PREFIX}{}", name.to_uppercase());
+                let Ok(value) = env::var(&env_name) else {
+                    continue;
+                };
+                value
+            }
+        };
+
+        debug!("{}", t!("util.parameterFromEnvironment", name = name, variable = env_name));
+        let value = match parameter.parameter_type {
+            DataType::String | DataType::SecureString => serde_json::Value::String(env_value),
+            _ => serde_json::from_str(&env_value).map_err(|err| DscError::Parser(t!("util.invalidEnvironmentParameter", variable = env_name, error = err.to_string()).to_string()))?,
+        };
+        let value = if is_complex { serde_json::json!({ "value": value }) } else { value };
+
+        let input = parameters.get_or_insert_with(|| serde_json::json!({ "parameters": {} }));
+        let Some(input) = input.as_object_mut() else {
+            return Err(DscError::Parser(t!("util.parametersNotObject").to_string()));
+        };
+        let Some(values) = input.entry("parameters").or_insert_with(|| serde_json::json!({})).as_object_mut() else {
+            return Err(DscError::Parser(t!("util.parametersNotObject").to_string()));
+        };
+        values.insert(name.clone(), value);
+        sources.insert(name.clone(), format!("env:{env_name}"));
+    }
+
+    Ok(())
+}
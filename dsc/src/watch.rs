@@ -0,0 +1,250 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::args::{OutputFormat, WatchMode};
+use crate::util::{EXIT_DSC_ERROR, EXIT_JSON_ERROR, get_local_data_path, in_desired_state, write_object};
+use dsc_lib::{
+    configure::{
+        Configurator,
+        config_result::{ConfigurationSetResult, ConfigurationTestResult},
+    },
+    dscerror::DscError,
+    scrub::scrub_value,
+};
+use rust_i18n::t;
+use serde::Serialize;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process::exit,
+    thread,
+    time::Duration,
+};
+use tracing::{debug, error, info, warn};
+
+/// The file name of the watch history in the local data directory.
+const HISTORY_FILE_NAME: &str = "watch_history.jsonl";
+
+/// The outcome of checking a configuration once.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchRecord {
+    pub timestamp: String,
+    pub mode: WatchMode,
+    pub in_desired_state: bool,
+    /// The names of the resource instances that weren't in the desired state.
+    pub drifted_resources: Vec<String>,
+    /// Whether set was invoked and succeeded to correct the drift.
+    pub corrected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_result: Option<ConfigurationTestResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_result: Option<ConfigurationSetResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WatchRecord {
+    fn new(mode: WatchMode) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            mode,
+            in_desired_state: false,
+            drifted_resources: Vec::new(),
+            corrected: false,
+            test_result: None,
+            set_result: None,
+            error: None,
+        }
+    }
+}
+
+/// A JSON lines file keeping the most recent watch records.
+///
+/// Records are appended and the oldest are only removed once the file holds twice the maximum,
+/// so each check doesn't rewrite the whole history.
+pub struct WatchHistory {
+    path: PathBuf,
+    max_entries: usize,
+    /// The number of records in the file, counted on the first append.
+    entries: Option<usize>,
+}
+
+impl WatchHistory {
+    /// Create the history stored at a path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the history file, defaults to `watch_history.jsonl` in the local data directory.
+    /// * `max_entries` - The number of records to keep, older records are removed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no path is given and the local data directory can't be determined.
+    pub fn new(path: Option<&String>, max_entries: usize) -> Result<Self, DscError> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => get_local_data_path()
+                .ok_or_else(|| DscError::Operation(t!("watch.noHistoryPath").to_string()))?
+                .join(HISTORY_FILE_NAME),
+        };
+        Ok(Self { path, max_entries, entries: None })
+    }
+
+    /// Get the path of the history file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_lines(&self) -> Result<Vec<String>, DscError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&self.path)?;
+        Ok(contents.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect())
+    }
+
+    /// Add a record to the history, removing the oldest records once the history holds twice the maximum.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be written.
+    pub fn append(&mut self, record: &WatchRecord) -> Result<(), DscError> {
+        let entries = match self.entries {
+            Some(entries) => entries,
+            None => self.read_lines()?.len(),
+        };
+        // the history is persisted so secure values are always scrubbed
+        let mut line = serde_json::to_string(&scrub_value(&serde_json::to_value(record)?))?;
+        line.push('\n');
+
+        if let Some(parent) = self.path.parent() && !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        self.entries = Some(entries + 1);
+
+        if entries + 1 >= self.max_entries.saturating_mul(2) {
+            self.trim()?;
+        }
+        Ok(())
+    }
+
+    /// Remove the oldest records beyond the maximum.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be read or written.
+    pub fn trim(&mut self) -> Result<(), DscError> {
+        let mut records = self.read_lines()?;
+        if records.len() <= self.max_entries {
+            self.entries = Some(records.len());
+            return Ok(());
+        }
+        records.drain(..records.len() - self.max_entries);
+
+        // write to a temporary file first so a failure doesn't lose the existing history
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        let mut file = fs::File::create(&temp_path)?;
+        for record in &records {
+            writeln!(file, "{record}")?;
+        }
+        file.flush()?;
+        drop(file);
+        fs::rename(&temp_path, &self.path)?;
+        self.entries = Some(records.len());
+        Ok(())
+    }
+}
+
+/// Test the configuration and, depending on the mode, set it if it has drifted.
+fn check_configuration(configurator: &mut Configurator, mode: WatchMode) -> WatchRecord {
+    let mut record = WatchRecord::new(mode);
+    match configurator.invoke_test() {
+        Ok(result) => {
+            record.drifted_resources = result.results.iter()
+                .filter(|test_result| !in_desired_state(test_result))
+                .map(|test_result| test_result.name.clone())
+                .collect();
+            record.in_desired_state = record.drifted_resources.is_empty() && !result.had_errors;
+            record.test_result = Some(result);
+        },
+        Err(err) => {
+            error!("{}", t!("watch.testFailed", error = err.to_string()));
+            record.error = Some(err.to_string());
+            return record;
+        }
+    }
+
+    if record.drifted_resources.is_empty() {
+        debug!("{}", t!("watch.inDesiredState"));
+        return record;
+    }
+
+    let drifted = record.drifted_resources.join(", ");
+    match mode {
+        WatchMode::Monitor => {
+            warn!("{}", t!("watch.driftDetected", resources = drifted));
+        },
+        WatchMode::ApplyAndAutoCorrect => {
+            info!("{}", t!("watch.correctingDrift", resources = drifted));
+            match configurator.invoke_set(false) {
+                Ok(result) => {
+                    record.corrected = !result.had_errors;
+                    record.set_result = Some(result);
+                },
+                Err(err) => {
+                    error!("{}", t!("watch.setFailed", error = err.to_string()));
+                    record.error = Some(err.to_string());
+                }
+            }
+        },
+    }
+    record
+}
+
+/// Periodically test a configuration, recording drift or correcting it based on the mode.
+///
+/// Each check is written to the output and added to the history.
+///
+/// # Arguments
+///
+/// * `configurator` - The configurator for the configuration to watch.
+/// * `mode` - Whether drift is only recorded or corrected.
+/// * `interval` - The number of seconds to wait between checks.
+/// * `count` - The number of checks to run, runs until stopped if `None`.
+/// * `history` - The history to add the records to.
+/// * `format` - The output format.
+pub fn config_watch(configurator: &mut Configurator, mode: WatchMode, interval: u64, count: Option<u64>, history: &mut WatchHistory, format: Option<&OutputFormat>) {
+    info!("{}", t!("watch.watching", mode = mode : {:?}, interval = interval, history = history.path().display()));
+    let mut checks = 0u64;
+    loop {
+        let record = check_configuration(configurator, mode);
+        if let Err(err) = history.append(&record) {
+            error!("{}", t!("watch.failedWriteHistory", path = history.path().display(), error = err.to_string()));
+            exit(EXIT_DSC_ERROR);
+        }
+
+        let json = match serde_json::to_string(&record) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("JSON Error: {err}");
+                exit(EXIT_JSON_ERROR);
+            }
+        };
+        write_object(&json, format, checks > 0);
+
+        checks += 1;
+        if count.is_some_and(|count| checks >= count) {
+            if let Err(err) = history.trim() {
+                error!("{}", t!("watch.failedWriteHistory", path = history.path().display(), error = err.to_string()));
+                exit(EXIT_DSC_ERROR);
+            }
+            break;
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
@@ -0,0 +1,153 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Tests for DSC agent' -Skip:$IsWindows {
+    BeforeAll {
+        $socket = Join-Path $TestDrive 'agent.sock'
+        $agent = Start-Process -FilePath dsc -ArgumentList 'agent', '--socket', $socket -PassThru -RedirectStandardError $TestDrive/agent.log
+        $baseUri = 'http://localhost/v1/jobs'
+
+        # wait for the agent to start listening
+        $started = $false
+        for ($i = 0; $i -lt 50 -and -not $started; $i++) {
+            try {
+                $null = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri
+                $started = $true
+            }
+            catch {
+                Start-Sleep -Milliseconds 100
+            }
+        }
+
+        function Wait-Job([string]$id) {
+            for ($i = 0; $i -lt 100; $i++) {
+                $job = Invoke-RestMethod -UnixSocket $socket -Uri "$baseUri/$id"
+                if ($job.status -notin 'queued', 'running') {
+                    return $job
+                }
+                Start-Sleep -Milliseconds 100
+            }
+            return $job
+        }
+    }
+
+    AfterAll {
+        $agent | Stop-Process -Force -ErrorAction Ignore
+    }
+
+    It 'Socket is only accessible by the current user' {
+        (Get-Item $socket).UnixMode | Should -BeExactly 'srw-------'
+    }
+
+    It 'Agent does not replace a file that is not a socket' {
+        $path = Join-Path $TestDrive 'not-a-socket.txt'
+        Set-Content -Path $path -Value 'keep'
+        dsc agent --socket $path 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Not -Be 0
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike '*is not a socket*'
+        Get-Content $path | Should -BeExactly 'keep'
+    }
+
+    It 'Resource get job can be submitted and its result fetched' {
+        $request = @{
+            operation  = 'get'
+            kind       = 'resource'
+            type       = 'Microsoft.DSC.Debug/Echo'
+            properties = @{ output = 'hello' }
+        } | ConvertTo-Json -Compress
+        $job = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri -Method Post -Body $request -ContentType 'application/json'
+        $job.id | Should -Not -BeNullOrEmpty
+        $job.operation | Should -BeExactly 'get'
+
+        $job = Wait-Job $job.id
+        $job.status | Should -BeExactly 'succeeded' -Because (Get-Content $TestDrive/agent.log -Raw)
+        $job.progress.completedItems | Should -Be 1
+
+        $result = Invoke-RestMethod -UnixSocket $socket -Uri "$baseUri/$($job.id)/result"
+        $result.status | Should -BeExactly 'succeeded'
+        $result.result.actualState.output | Should -BeExactly 'hello'
+    }
+
+    It 'Configuration test job reports progress events' {
+        $configuration = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  text:
+    type: string
+resources:
+- name: first
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: "[parameters('text')]"
+- name: second
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: two
+'@
+        $request = @{
+            operation     = 'test'
+            kind          = 'configuration'
+            configuration = $configuration
+            parameters    = @{ text = 'one' }
+        } | ConvertTo-Json -Compress
+        $job = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri -Method Post -Body $request -ContentType 'application/json'
+        $job = Wait-Job $job.id
+        $job.status | Should -BeExactly 'succeeded' -Because (Get-Content $TestDrive/agent.log -Raw)
+
+        $events = Invoke-WebRequest -UnixSocket $socket -Uri "$baseUri/$($job.id)/events"
+        $data = $events.Content -split "`n" | Where-Object { $_ -like 'data:*' } | ForEach-Object { $_.Substring(5).Trim() | ConvertFrom-Json }
+        $data.Count | Should -Be 6
+        $data[0].type | Should -BeExactly 'statusChanged'
+        $data[0].status | Should -BeExactly 'running'
+        ($data | Where-Object { $_.type -eq 'resourceStarted' }).resource | Should -Be @('first', 'second')
+        ($data | Where-Object { $_.type -eq 'resourceCompleted' }).status | Should -Be @('success', 'success')
+        $data[-1].status | Should -BeExactly 'succeeded'
+
+        $result = Invoke-RestMethod -UnixSocket $socket -Uri "$baseUri/$($job.id)/result"
+        $result.result.results.Count | Should -Be 2
+        $result.result.results[0].result.actualState.output | Should -BeExactly 'one'
+    }
+
+    It 'Failed job returns the error' {
+        $request = @{
+            operation = 'get'
+            kind      = 'resource'
+            type      = 'Test/DoesNotExist'
+        } | ConvertTo-Json -Compress
+        $job = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri -Method Post -Body $request -ContentType 'application/json'
+        $job = Wait-Job $job.id
+        $job.status | Should -BeExactly 'failed'
+        $job.error | Should -Not -BeNullOrEmpty
+    }
+
+    It 'Unknown job returns not found' {
+        $response = Invoke-WebRequest -UnixSocket $socket -Uri "$baseUri/does-not-exist" -SkipHttpErrorCheck
+        $response.StatusCode | Should -Be 404
+    }
+
+    It 'Finished job can not be cancelled' {
+        $jobs = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri
+        $jobs.Count | Should -BeGreaterOrEqual 3
+        $response = Invoke-WebRequest -UnixSocket $socket -Uri "$baseUri/$($jobs[0].id)" -Method Delete -SkipHttpErrorCheck
+        $response.StatusCode | Should -Be 409
+    }
+
+    It 'Running resource job can not be cancelled' {
+        $request = @{
+            operation  = 'get'
+            kind       = 'resource'
+            type       = 'Test/Sleep'
+            properties = @{ seconds = 2 }
+        } | ConvertTo-Json -Compress
+        $job = Invoke-RestMethod -UnixSocket $socket -Uri $baseUri -Method Post -Body $request -ContentType 'application/json'
+        for ($i = 0; $i -lt 50 -and $job.status -ne 'running'; $i++) {
+            Start-Sleep -Milliseconds 100
+            $job = Invoke-RestMethod -UnixSocket $socket -Uri "$baseUri/$($job.id)"
+        }
+        $job.status | Should -BeExactly 'running'
+        $response = Invoke-WebRequest -UnixSocket $socket -Uri "$baseUri/$($job.id)" -Method Delete -SkipHttpErrorCheck
+        $response.StatusCode | Should -Be 409
+        $job = Wait-Job $job.id
+        $job.status | Should -BeExactly 'succeeded'
+    }
+}
@@ -0,0 +1,116 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Tests for configuration lock files' {
+    BeforeAll {
+        $configFile = Join-Path $TestDrive 'lock.dsc.config.yaml'
+        $lockFile = Join-Path $TestDrive 'lock.dsc.config.lock.json'
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Test Version
+              type: Test/Version
+              requireVersion: '^1.0'
+              properties:
+                version: '1.1.3'
+"@
+        Set-Content -Path $configFile -Value $config_yaml
+    }
+
+    BeforeEach {
+        Remove-Item -Path $lockFile -ErrorAction Ignore
+    }
+
+    It 'Lock writes a lock file next to the configuration' {
+        dsc config lock -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $lock = Get-Content $lockFile -Raw | ConvertFrom-Json
+        $lock.resources.Count | Should -Be 1
+        $lock.resources[0].type | Should -BeExactly 'Test/Version'
+        $lock.resources[0].requireVersion | Should -BeExactly '^1.0'
+        $lock.resources[0].version | Should -BeExactly '1.1.3'
+        $lock.resources[0].path | Should -Not -BeNullOrEmpty
+        $lock.resources[0].hash | Should -Match '^sha256:[0-9a-f]{64}$'
+    }
+
+    It 'Lock writes to stdout when the configuration is not a file' {
+        $out = dsc config lock -i (Get-Content $configFile -Raw) 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.resources[0].version | Should -BeExactly '1.1.3'
+        $lockFile | Should -Not -Exist
+    }
+
+    It 'Config <operation> honors a matching lock file' -TestCases @(
+        @{ operation = 'get' }
+        @{ operation = 'set' }
+        @{ operation = 'test' }
+    ) {
+        param($operation)
+        dsc config lock -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $null = dsc config $operation -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+    }
+
+    It 'Config <operation> fails when the locked version does not match' -TestCases @(
+        @{ operation = 'get' }
+        @{ operation = 'set' }
+        @{ operation = 'test' }
+    ) {
+        param($operation)
+        dsc config lock -f $configFile 2> $TestDrive/error.log
+        $lock = Get-Content $lockFile -Raw | ConvertFrom-Json
+        $lock.resources[0].version = '1.1.0'
+        $lock | ConvertTo-Json -Depth 5 | Set-Content -Path $lockFile
+        $null = dsc config $operation -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 5
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match "locked to version '1.1.0'"
+    }
+
+    It 'Config get fails when the manifest hash does not match' {
+        dsc config lock -f $configFile 2> $TestDrive/error.log
+        $lock = Get-Content $lockFile -Raw | ConvertFrom-Json
+        $lock.resources[0].hash = 'sha256:' + ('0' * 64)
+        $lock | ConvertTo-Json -Depth 5 | Set-Content -Path $lockFile
+        $null = dsc config get -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 5
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'does not match the hash in the lock file'
+    }
+
+    It 'Lock includes the resources of group resources' {
+        $groupConfigFile = Join-Path $TestDrive 'group.dsc.config.yaml'
+        @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Group
+              type: Microsoft.DSC/Group
+              properties:
+                `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: Test Version
+                  type: Test/Version
+                  requireVersion: '^1.0'
+                  properties:
+                    version: '1.1.3'
+"@ | Set-Content -Path $groupConfigFile
+        $groupLockFile = Join-Path $TestDrive 'group.dsc.config.lock.json'
+        dsc config lock -f $groupConfigFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $lock = Get-Content $groupLockFile -Raw | ConvertFrom-Json
+        $lock.resources.type | Should -Be @('Microsoft.DSC/Group', 'Test/Version')
+
+        $null = dsc config get -f $groupConfigFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        $lock.resources[1].version = '1.1.0'
+        $lock | ConvertTo-Json -Depth 5 | Set-Content -Path $groupLockFile
+        $null = dsc config get -f $groupConfigFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Not -Be 0
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match "locked to version '1.1.0'"
+    }
+
+    It 'Explicit lock file that does not exist fails' {
+        $null = dsc config --lock-file (Join-Path $TestDrive 'missing.lock.json') get -f $configFile 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 1
+    }
+}
@@ -0,0 +1,68 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'dsc config watch tests' {
+    BeforeAll {
+        $driftConfig = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Drifted
+  type: Test/InDesiredState
+  properties:
+    _inDesiredState: false
+    valueOne: 1
+    valueTwo: 2
+- name: InState
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: hello
+'@
+        $inStateConfig = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: InState
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: hello
+'@
+    }
+
+    It 'Monitor mode records drift without setting' {
+        $history = Join-Path $TestDrive 'monitor.jsonl'
+        $out = dsc config watch -i $driftConfig --count 1 --interval 1 --history $history -o json 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.mode | Should -BeExactly 'monitor'
+        $out.inDesiredState | Should -BeFalse
+        $out.driftedResources | Should -Be @('Drifted')
+        $out.corrected | Should -BeFalse
+        $out.testResult.results.Count | Should -Be 2
+        $out.setResult | Should -BeNullOrEmpty
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'Drifted'
+
+        $records = Get-Content $history | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].driftedResources | Should -Be @('Drifted')
+    }
+
+    It 'Configuration in desired state is not set in applyAndAutoCorrect mode' {
+        $history = Join-Path $TestDrive 'apply.jsonl'
+        $out = dsc config watch -i $inStateConfig --mode applyAndAutoCorrect --count 1 --interval 1 --history $history -o json 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.mode | Should -BeExactly 'applyAndAutoCorrect'
+        $out.inDesiredState | Should -BeTrue
+        $out.driftedResources | Should -BeNullOrEmpty
+        $out.setResult | Should -BeNullOrEmpty
+    }
+
+    It 'History keeps only the most recent results' {
+        $history = Join-Path $TestDrive 'rolling.jsonl'
+        $out = dsc config watch -i $inStateConfig --count 3 --interval 1 --history $history --history-size 2 -o json 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.Count | Should -Be 3
+        $results = $out | ConvertFrom-Json
+        $records = Get-Content $history | ConvertFrom-Json
+        $records.Count | Should -Be 2
+        $records[0].timestamp | Should -BeExactly $results[1].timestamp
+        $records[1].timestamp | Should -BeExactly $results[2].timestamp
+    }
+}
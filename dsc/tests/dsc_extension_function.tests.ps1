@@ -0,0 +1,99 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Tests for functions provided by extensions' {
+    BeforeAll {
+        $oldPath = $env:PATH
+        $toolPath = Resolve-Path -Path "$PSScriptRoot/../../extensions/test/function"
+        $env:PATH = "$toolPath" + [System.IO.Path]::PathSeparator + $oldPath
+    }
+
+    AfterAll {
+        $env:PATH = $oldPath
+    }
+
+    It 'Extension lists the function capability' {
+        $out = dsc extension list Test/Function 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+        $out.capabilities | Should -Contain 'function'
+    }
+
+    It 'Expression <expression> is evaluated by the extension' -TestCases @(
+        @{ expression = "[test.reverse('hello')]"; expected = 'olleh' }
+        @{ expression = "[string(test.sum(1, 2, 3))]"; expected = '6' }
+        @{ expression = "[toUpper(test.reverse('abc'))]"; expected = 'CBA' }
+    ) {
+        param($expression, $expected)
+        $configYaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "$expression"
+"@
+        $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+        $out.results[0].result.actualState.output | Should -BeExactly $expected
+    }
+
+    It 'Arguments are validated against the declared types' {
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[test.reverse(1)]"
+'@
+        $null = dsc config get -i $configYaml 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content -Raw -Path $TestDrive/error.log) | Should -Match "does not accept number arguments"
+    }
+
+    It 'Return value is validated against the declared types' {
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[test.mistyped()]"
+'@
+        $null = dsc config get -i $configYaml 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content -Raw -Path $TestDrive/error.log) | Should -Match "returned a value of type String, declared return types are: Number"
+    }
+
+    It 'Function list includes extension functions' {
+        $out = dsc function list 'test.*' 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+        $out.name | Should -Contain 'test.reverse'
+        $out.name | Should -Contain 'test.sum'
+        ($out | Where-Object name -EQ 'test.reverse').category | Should -Be @('Extension')
+    }
+
+    It 'User functions take precedence over extension functions' {
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            functions:
+            - namespace: test
+              members:
+                reverse:
+                  parameters:
+                  - name: text
+                    type: string
+                  output:
+                    type: string
+                    value: "[concat('user-', parameters('text'))]"
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[test.reverse('abc')]"
+'@
+        $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+        $out.results[0].result.actualState.output | Should -BeExactly 'user-abc'
+    }
+}
@@ -6,10 +6,13 @@ Describe 'Tests for the secret() function and extensions' {
         $oldPath = $env:PATH
         $toolPath = Resolve-Path -Path "$PSScriptRoot/../../extensions/test/secret"
         $env:PATH = "$toolPath" + [System.IO.Path]::PathSeparator + $oldPath
+        $oldShowSecureValues = $env:DSC_SHOW_SECURE_VALUES
+        $env:DSC_SHOW_SECURE_VALUES = 'true'
     }
 
     AfterAll {
         $env:PATH = $oldPath
+        $env:DSC_SHOW_SECURE_VALUES = $oldShowSecureValues
     }
 
     It 'Just a secret name' {
@@ -178,6 +181,73 @@ Describe 'Tests for the secret() function and extensions' {
       $out.results[0].result.actualState.Output | Should -BeExactly 'Hello'
     }
 
+    It 'Secret used more than once is only retrieved once' {
+      $configYaml = @'
+          $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+          resources:
+          - name: First
+            type: Microsoft.DSC.Debug/Echo
+            properties:
+              output: "[secret('MySecret')]"
+              showSecrets: true
+          - name: Second
+            type: Microsoft.DSC.Debug/Echo
+            properties:
+              output: "[secret('MySecret')]"
+              showSecrets: true
+'@
+      $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+      $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+      $out.results[0].result.actualState.Output | Should -BeExactly 'Hello'
+      $out.results[1].result.actualState.Output | Should -BeExactly 'Hello'
+      $out.executionInformation.secrets.requests | Should -Be 2
+      $out.executionInformation.secrets.cacheHits | Should -Be 1
+      $out.executionInformation.secrets.providerCalls | Should -BeGreaterThan 0
+    }
+
+    It 'Default vault directive is used when no vault is specified' {
+      $configYaml = @'
+          $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+          directives:
+            secrets:
+              defaultVault: VaultA
+          resources:
+          - name: Echo
+            type: Microsoft.DSC.Debug/Echo
+            properties:
+              output: "[secret('DuplicateSecret')]"
+              showSecrets: true
+'@
+      $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+      $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+      $out.results[0].result.actualState.Output | Should -BeExactly 'World2'
+    }
+
+    It 'Provider order directive uses the first provider returning the secret: <provider>' -TestCases @(
+      @{ provider = 'Test/Secret'; expected = 'World' }
+      @{ provider = 'Test/Secret2'; expected = 'World2' }
+    ) {
+      param($provider, $expected)
+
+      $configYaml = @"
+          `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+          directives:
+            secrets:
+              providerOrder:
+              - $provider
+          resources:
+          - name: Echo
+            type: Microsoft.DSC.Debug/Echo
+            properties:
+              output: "[secret('DuplicateSecret')]"
+              showSecrets: true
+"@
+      $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+      $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+      $out.results[0].result.actualState.Output | Should -BeExactly $expected
+      $out.executionInformation.secrets.providerCalls | Should -Be 1
+    }
+
     It 'Deprecated extension shows message' {
       try {
         $dscHome = Split-Path (Get-Command dsc).Source -Parent
@@ -200,4 +270,54 @@ Describe 'Tests for the secret() function and extensions' {
         $env:DSC_RESTRICTED_PATH = $null
       }
     }
+
+    It 'Parameter value can reference a secret with vault: <vault>' -TestCases @(
+        @{ vault = $null; secretName = 'MySecret'; expected = 'Hello' }
+        @{ vault = 'VaultA'; secretName = 'DifferentSecret'; expected = 'Hello2' }
+        @{ vault = @{ id = '/subscriptions/x/resourceGroups/y/providers/Microsoft.KeyVault/vaults/VaultA' }; secretName = 'DifferentSecret'; expected = 'Hello2' }
+    ) {
+        param($vault, $secretName, $expected)
+
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              password:
+                type: secureString
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                showSecrets: true
+                output: "[parameters('password')]"
+'@
+        $reference = @{ secretName = $secretName }
+        if ($null -ne $vault) {
+            $reference.keyVault = $vault
+        }
+        $parameters = @{ parameters = @{ password = @{ reference = $reference } } } | ConvertTo-Json -Depth 10 -Compress
+        $out = dsc config --parameters $parameters get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content -Raw -Path $TestDrive/error.log)
+        $out.results[0].result.actualState.Output | Should -BeExactly $expected
+        (Get-Content -Raw -Path $TestDrive/error.log) | Should -Not -Match $expected
+    }
+
+    It 'Parameter referencing a secret that does not exist' {
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              password:
+                type: secureString
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[parameters('password')]"
+'@
+        $parameters = '{"parameters":{"password":{"reference":{"secretName":"NonExistentSecret"}}}}'
+        dsc config --parameters $parameters get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 4
+        $errorMessage = Get-Content -Raw -Path $TestDrive/error.log
+        $errorMessage | Should -Match "parameter 'password'"
+        $errorMessage | Should -Match "Secret 'NonExistentSecret' not found"
+    }
 }
@@ -0,0 +1,163 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Audit log and dsc history tests' {
+    BeforeAll {
+        $oldAuditLog = $env:DSC_AUDIT_LOG
+    }
+
+    BeforeEach {
+        $env:DSC_AUDIT_LOG = Join-Path $TestDrive "audit-$(New-Guid).jsonl"
+    }
+
+    AfterAll {
+        $env:DSC_AUDIT_LOG = $oldAuditLog
+    }
+
+    It 'Config set is recorded with the changed properties' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: MyEcho
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: hello
+'@
+        $null = dsc config set -i $config 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        $records = dsc history -o json 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $records.Count | Should -Be 1
+        $records[0].operation | Should -BeExactly 'set'
+        $records[0].executionType | Should -BeExactly 'actual'
+        $records[0].type | Should -BeExactly 'Microsoft.DSC.Debug/Echo'
+        $records[0].name | Should -BeExactly 'MyEcho'
+        $records[0].securityContext | Should -BeIn @('elevated', 'restricted')
+        $records[0].configHash | Should -Match '^sha256:[0-9a-f]{64}$'
+        $records[0].duration | Should -Not -BeNullOrEmpty
+    }
+
+    It 'Group resources are recorded once' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: MyGroup
+  type: Microsoft.DSC/Group
+  properties:
+    $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+    resources:
+    - name: First
+      type: Microsoft.DSC.Debug/Echo
+      properties:
+        output: one
+    - name: Second
+      type: Microsoft.DSC.Debug/Echo
+      properties:
+        output: two
+'@
+        $null = dsc config set -i $config 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        $records = dsc history -o json 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $records.Count | Should -Be 2
+        $records.name | Should -Be @('First', 'Second')
+    }
+
+    It 'Secure values are redacted' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  secret:
+    type: secureString
+resources:
+- name: Secret
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: "[parameters('secret')]"
+'@
+        $null = dsc config --parameters '{"parameters":{"secret":"mySecret"}}' set -i $config 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        Get-Content $env:DSC_AUDIT_LOG -Raw | Should -Not -Match 'mySecret'
+    }
+
+    It 'Resource set and delete are recorded and can be filtered' {
+        $null = dsc resource set -r Microsoft.DSC.Debug/Echo -i '{"output":"one"}' 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $null = dsc resource delete -r Test/Delete -i '{"_exist":false}' 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        $records = dsc history -o json | ConvertFrom-Json
+        $records.Count | Should -Be 2
+        $records[0].name | Should -BeNullOrEmpty
+        $records[0].configHash | Should -BeNullOrEmpty
+
+        $records = dsc history --operation delete -o json | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].type | Should -BeExactly 'Test/Delete'
+
+        $records = dsc history --resource 'Microsoft.DSC.Debug/*' -o json | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].operation | Should -BeExactly 'set'
+
+        $records = dsc history --last 1 -o json | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].operation | Should -BeExactly 'delete'
+    }
+
+    It 'User is taken from the OS and not the environment' {
+        $oldUser = $env:USER
+        $oldUserName = $env:USERNAME
+        try {
+            $env:USER = 'spoofedUser'
+            $env:USERNAME = 'spoofedUser'
+            $null = dsc resource set -r Microsoft.DSC.Debug/Echo -i '{"output":"one"}' 2> $TestDrive/error.log
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        }
+        finally {
+            $env:USER = $oldUser
+            $env:USERNAME = $oldUserName
+        }
+
+        $records = dsc history -o json | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].user | Should -Not -BeNullOrEmpty
+        $records[0].user | Should -Not -Be 'spoofedUser'
+    }
+
+    It 'Group resource set records the duration of each resource' {
+        $group = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{ name = 'First'; type = 'Microsoft.DSC.Debug/Echo'; properties = @{ output = 'one' } }
+            )
+        } | ConvertTo-Json -Depth 10 -Compress
+        $null = dsc resource set -r Microsoft.DSC/Group -i $group 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        $records = dsc history -o json | ConvertFrom-Json
+        $records.Count | Should -Be 1
+        $records[0].name | Should -BeExactly 'First'
+        $records[0].duration | Should -Not -BeNullOrEmpty
+    }
+
+    It 'Operations are not recorded when the audit log is not enabled' {
+        $path = $env:DSC_AUDIT_LOG
+        $env:DSC_AUDIT_LOG = $null
+        try {
+            $null = dsc resource set -r Microsoft.DSC.Debug/Echo -i '{"output":"one"}' 2> $TestDrive/error.log
+            $LASTEXITCODE | Should -Be 0
+        }
+        finally {
+            $env:DSC_AUDIT_LOG = $path
+        }
+        Test-Path $path | Should -BeFalse
+    }
+
+    It 'Invalid since is an error' {
+        $null = dsc history --since 'yesterday' 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 1
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'since'
+    }
+}
@@ -349,4 +349,262 @@ resources:
         $out.results[0].result[0].result.inDesiredState | Should -Be $expectedState
         $out.results[0].result[0].result.differingProperties.Count | Should -Be $expectedDiff
     }
+
+    It 'Outputs of an included configuration can be consumed: <expression>' -TestCases @(
+        @{ expression = "[outputs('service').port]" }
+        @{ expression = "[reference('service').outputs.port]" }
+    ) {
+        param($expression)
+
+        $included = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: 8080
+            outputs:
+              port:
+                type: int
+                value: "[reference(resourceId('Microsoft.DSC.Debug/Echo', 'Echo')).output]"
+'@
+        $includedPath = Join-Path $TestDrive 'service.dsc.yaml'
+        $included | Set-Content -Path $includedPath
+
+        $config = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: service
+              type: Microsoft.DSC/Include
+              properties:
+                configurationFile: $includedPath
+            - name: Echo port
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "$expression"
+              dependsOn:
+              - "[resourceId('Microsoft.DSC/Include', 'service')]"
+"@
+        foreach ($operation in 'get', 'test', 'set') {
+            $out = dsc config $operation -i $config 2> $logPath | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $logPath -Raw)
+            $out.results[0].metadata.'Microsoft.DSC'.outputs.port.type | Should -BeExactly 'int'
+            $out.results[0].metadata.'Microsoft.DSC'.outputs.port.value | Should -Be 8080
+            $out.results[0].result[0].metadata.'Microsoft.DSC'.outputs | Should -BeNullOrEmpty
+            $state = switch ($operation) {
+                'get' { $out.results[1].result.actualState }
+                'test' { $out.results[1].result.actualState }
+                'set' { $out.results[1].result.afterState }
+            }
+            $state.output | Should -Be 8080 -Because $operation
+        }
+    }
+
+    It 'Output of an included configuration not matching its type returns error' {
+        $included = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: 1.5
+            outputs:
+              port:
+                type: int
+                value: "[reference(resourceId('Microsoft.DSC.Debug/Echo', 'Echo')).output]"
+'@
+        $includedPath = Join-Path $TestDrive 'invalid_output.dsc.yaml'
+        $included | Set-Content -Path $includedPath
+
+        $config = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: service
+              type: Microsoft.DSC/Include
+              properties:
+                configurationFile: $includedPath
+"@
+        $null = dsc config get -i $config 2> $logPath
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $logPath -Raw) | Should -BeLike "*Output 'port' type does not match expected type 'int'*"
+    }
+
+    It 'Outputs of a configuration that was not included returns error' {
+        $config = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[outputs('missing').port]"
+'@
+        $null = dsc config get -i $config 2> $logPath
+        $LASTEXITCODE | Should -Not -Be 0
+        (Get-Content $logPath -Raw) | Should -BeLike "*No outputs for included configuration 'missing'*"
+    }
+
+    Context 'Remote include sources' {
+        BeforeAll {
+            $oldCachePath = $env:DSC_INCLUDE_CACHE_PATH
+            $remoteConfig = "`$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json`nresources:`n- name: Echo`n  type: Microsoft.DSC.Debug/Echo`n  properties:`n    output: fromRemote`n"
+        }
+
+        BeforeEach {
+            $env:DSC_INCLUDE_CACHE_PATH = Join-Path $TestDrive "cache-$(New-Guid)"
+        }
+
+        AfterAll {
+            $env:DSC_INCLUDE_CACHE_PATH = $oldCachePath
+        }
+
+        It 'Configuration can be included from an archive and reused from the cache' {
+            $sourcePath = New-Item -ItemType Directory -Path (Join-Path $TestDrive "archive-$(New-Guid)")
+            $null = New-Item -ItemType Directory -Path (Join-Path $sourcePath 'configs')
+            [System.IO.File]::WriteAllText((Join-Path $sourcePath 'configs/baseline.dsc.yaml'), $remoteConfig)
+            $archivePath = Join-Path $TestDrive "baseline-$(New-Guid).tar.gz"
+            tar -czf $archivePath -C $sourcePath configs
+            $hash = (Get-FileHash -Path $archivePath -Algorithm SHA256).Hash
+
+            $config = @"
+                `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationArchive:
+                      uri: $archivePath
+                      path: configs/baseline.dsc.yaml
+                      sha256: sha256:$hash
+"@
+            $out = dsc config get -i $config 2> $logPath | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $logPath -Raw)
+            $out.results[0].result[0].result.actualState.output | Should -BeExactly 'fromRemote'
+
+            Remove-Item -Path $archivePath
+            $out = dsc -l info config get -i $config 2> $logPath | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $logPath -Raw)
+            $out.results[0].result[0].result.actualState.output | Should -BeExactly 'fromRemote'
+            (Get-Content $logPath -Raw) | Should -BeLike '*Using cached archive*'
+        }
+
+        It 'Archive not matching its sha256 returns error' {
+            $sourcePath = New-Item -ItemType Directory -Path (Join-Path $TestDrive "archive-$(New-Guid)")
+            [System.IO.File]::WriteAllText((Join-Path $sourcePath 'baseline.dsc.yaml'), $remoteConfig)
+            $archivePath = Join-Path $TestDrive "baseline-$(New-Guid).tar.gz"
+            tar -czf $archivePath -C $sourcePath baseline.dsc.yaml
+            $hash = '0' * 64
+
+            $config = @"
+                `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationArchive:
+                      uri: $archivePath
+                      path: baseline.dsc.yaml
+                      sha256: $hash
+"@
+            $null = dsc config get -i $config 2> $logPath
+            $LASTEXITCODE | Should -Be 2
+            (Get-Content $logPath -Raw) | Should -BeLike "*Integrity check failed*expected sha256 '$hash'*"
+            Get-ChildItem -Path $env:DSC_INCLUDE_CACHE_PATH -Recurse -File | Should -BeNullOrEmpty
+        }
+
+        It 'Configuration can be included from a git repository at a pinned commit' -Skip:(!(Get-Command git -ErrorAction Ignore)) {
+            $repoPath = New-Item -ItemType Directory -Path (Join-Path $TestDrive "repo-$(New-Guid)")
+            git -C $repoPath init --quiet
+            git -C $repoPath config core.autocrlf false
+            [System.IO.File]::WriteAllText((Join-Path $repoPath 'baseline.dsc.yaml'), $remoteConfig)
+            git -C $repoPath add baseline.dsc.yaml
+            git -C $repoPath -c user.name=test -c user.email=test@example.com commit --quiet -m baseline
+            $commit = git -C $repoPath rev-parse HEAD
+            $hash = (Get-FileHash -Path (Join-Path $repoPath 'baseline.dsc.yaml') -Algorithm SHA256).Hash
+
+            # later commits don't change the pinned content
+            [System.IO.File]::WriteAllText((Join-Path $repoPath 'baseline.dsc.yaml'), $remoteConfig.Replace('fromRemote', 'changed'))
+            git -C $repoPath -c user.name=test -c user.email=test@example.com commit --quiet -am changed
+
+            $config = @"
+                `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationGit:
+                      repository: $repoPath
+                      commit: $commit
+                      path: baseline.dsc.yaml
+                      sha256: $hash
+"@
+            $out = dsc config get -i $config 2> $logPath | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $logPath -Raw)
+            $out.results[0].result[0].result.actualState.output | Should -BeExactly 'fromRemote'
+
+            Remove-Item -Path $repoPath -Recurse -Force
+            $out = dsc -l info config get -i $config 2> $logPath | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $logPath -Raw)
+            $out.results[0].result[0].result.actualState.output | Should -BeExactly 'fromRemote'
+            (Get-Content $logPath -Raw) | Should -BeLike '*Using cached commit*'
+        }
+
+        It 'Git source requires a full commit hash' {
+            $config = @'
+                $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationGit:
+                      repository: https://example.com/baseline.git
+                      commit: main
+                      path: baseline.dsc.yaml
+                      sha256: 0000000000000000000000000000000000000000000000000000000000000000
+'@
+            $null = dsc config get -i $config 2> $logPath
+            $LASTEXITCODE | Should -Be 2
+            (Get-Content $logPath -Raw) | Should -BeLike "*Git commit 'main' must be a full commit hash*"
+        }
+
+        It 'Git repository starting with a dash returns error' {
+            $config = @'
+                $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationGit:
+                      repository: --upload-pack=touch
+                      commit: 0000000000000000000000000000000000000000
+                      path: baseline.dsc.yaml
+                      sha256: 0000000000000000000000000000000000000000000000000000000000000000
+'@
+            $null = dsc config get -i $config 2> $logPath
+            $LASTEXITCODE | Should -Be 2
+            (Get-Content $logPath -Raw) | Should -BeLike "*Git repository '--upload-pack=touch' must not start with '-'*"
+        }
+
+        It 'Archive containing a symbolic link returns error' -Skip:$IsWindows {
+            $sourcePath = New-Item -ItemType Directory -Path (Join-Path $TestDrive "archive-$(New-Guid)")
+            $null = New-Item -ItemType SymbolicLink -Path (Join-Path $sourcePath 'baseline.dsc.yaml') -Target '/etc/hostname'
+            $archivePath = Join-Path $TestDrive "baseline-$(New-Guid).tar.gz"
+            tar -czf $archivePath -C $sourcePath baseline.dsc.yaml
+            $hash = (Get-FileHash -Path $archivePath -Algorithm SHA256).Hash
+
+            $config = @"
+                `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+                resources:
+                - name: baseline
+                  type: Microsoft.DSC/Include
+                  properties:
+                    configurationArchive:
+                      uri: $archivePath
+                      path: baseline.dsc.yaml
+                      sha256: $hash
+"@
+            $null = dsc config get -i $config 2> $logPath
+            $LASTEXITCODE | Should -Be 2
+            (Get-Content $logPath -Raw) | Should -BeLike '*Archive contains symbolic link*'
+        }
+    }
 }
@@ -0,0 +1,165 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Configuration module tests' {
+    BeforeAll {
+        $modulePath = New-Item -ItemType Directory -Path (Join-Path $TestDrive 'modules')
+        @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  port:
+    type: int
+  hostName:
+    type: string
+    defaultValue: localhost
+variables:
+  url: "[concat('http://', parameters('hostName'), ':', string(parameters('port')))]"
+resources:
+- name: listener
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: "[parameters('port')]"
+- name: site
+  type: Microsoft.DSC.Debug/Echo
+  dependsOn:
+  - "[resourceId('Microsoft.DSC.Debug/Echo', 'listener')]"
+  properties:
+    output: "[concat(variables('url'), ' on ', string(reference(resourceId('Microsoft.DSC.Debug/Echo', 'listener')).output))]"
+outputs:
+  url:
+    type: string
+    value: "[variables('url')]"
+'@ | Set-Content -Path (Join-Path $modulePath 'web.dsc.yaml')
+
+        @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  port:
+    type: int
+resources:
+- name: inner
+  kind: module
+  properties:
+    source: web.dsc.yaml
+    parameters:
+      port: "[parameters('port')]"
+outputs:
+  url:
+    type: string
+    value: "[outputs('inner').url]"
+'@ | Set-Content -Path (Join-Path $modulePath 'nested.dsc.yaml')
+
+        @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: self
+  kind: module
+  properties:
+    source: recursive.dsc.yaml
+'@ | Set-Content -Path (Join-Path $modulePath 'recursive.dsc.yaml')
+    }
+
+    It 'Module is expanded into namespaced resources' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  webPort:
+    type: int
+    defaultValue: 8080
+resources:
+- name: web
+  kind: module
+  properties:
+    source: modules/web.dsc.yaml
+    parameters:
+      port: "[parameters('webPort')]"
+- name: after
+  type: Microsoft.DSC.Debug/Echo
+  dependsOn:
+  - "[resourceId('Microsoft.DSC/Module', 'web')]"
+  properties:
+    output: "[outputs('web').url]"
+'@
+        $configPath = Join-Path $TestDrive 'module.dsc.yaml'
+        $config | Set-Content -Path $configPath
+        $out = dsc config get -f $configPath 2>$TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results.Count | Should -Be 3
+        $out.results[0].name | Should -BeExactly 'web/listener'
+        $out.results[0].result.actualState.output | Should -Be 8080
+        $out.results[1].name | Should -BeExactly 'web/site'
+        $out.results[1].result.actualState.output | Should -BeExactly 'http://localhost:8080 on 8080'
+        $out.results[2].name | Should -BeExactly 'after'
+        $out.results[2].result.actualState.output | Should -BeExactly 'http://localhost:8080'
+    }
+
+    It 'Nested modules are expanded' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: outer
+  kind: module
+  properties:
+    source: modules/nested.dsc.yaml
+    parameters:
+      port: 443
+- name: after
+  type: Microsoft.DSC.Debug/Echo
+  dependsOn:
+  - "[resourceId('Microsoft.DSC/Module', 'outer')]"
+  properties:
+    output: "[outputs('outer').url]"
+'@
+        $configPath = Join-Path $TestDrive 'nested_module.dsc.yaml'
+        $config | Set-Content -Path $configPath
+        $out = dsc config get -f $configPath 2>$TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results.name | Should -Be @('outer/inner/listener', 'outer/inner/site', 'after')
+        $out.results[1].result.actualState.output | Should -BeExactly 'http://localhost:443 on 443'
+        $out.results[2].result.actualState.output | Should -BeExactly 'http://localhost:443'
+    }
+
+    It 'Module condition applies to its resources' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  condition: "[equals(1, 2)]"
+  properties:
+    source: modules/web.dsc.yaml
+    parameters:
+      port: 80
+'@
+        $configPath = Join-Path $TestDrive 'condition_module.dsc.yaml'
+        $config | Set-Content -Path $configPath
+        $out = dsc config get -f $configPath 2>$TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results | Should -BeNullOrEmpty
+    }
+
+    It 'Invalid module returns an error: <case>' -TestCases @(
+        @{ case = 'missing parameter'; source = 'modules/web.dsc.yaml'; parameters = '{}'; message = "Parameter 'port' is required" }
+        @{ case = 'unknown parameter'; source = 'modules/web.dsc.yaml'; parameters = '{"port": 1, "other": 2}'; message = "Parameter 'other' is not declared" }
+        @{ case = 'recursive template'; source = 'modules/recursive.dsc.yaml'; parameters = '{}'; message = 'recursively' }
+        @{ case = 'parent directory'; source = '../web.dsc.yaml'; parameters = '{}'; message = "must be a relative path that does not contain '..'" }
+        @{ case = 'absolute path'; source = '/web.dsc.yaml'; parameters = '{}'; message = "must be a relative path that does not contain '..'" }
+    ) {
+        param($source, $parameters, $message)
+
+        $config = @"
+`$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  properties:
+    source: $source
+    parameters: $parameters
+"@
+        $configPath = Join-Path $TestDrive 'invalid_module.dsc.yaml'
+        $config | Set-Content -Path $configPath
+        $null = dsc config get -f $configPath 2>$TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike "*$message*"
+    }
+}
@@ -38,4 +38,23 @@ Describe 'output tests' {
         $out.outputs.conditionSucceed | Should -Be 2
         $out.outputs.conditionFail | Should -BeNullOrEmpty
     }
+
+    It 'output with null value is not validated against its type' {
+        $configYaml = @'
+        $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+        resources:
+          - name: echo
+            type: Microsoft.DSC.Debug/Echo
+            properties:
+              output: This is a test
+        outputs:
+          nullValue:
+            type: string
+            value: "[null()]"
+'@
+        $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json -Depth 10
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.outputs.PSObject.Properties.Name | Should -Contain 'nullValue'
+        $out.outputs.nullValue | Should -BeNullOrEmpty
+    }
 }
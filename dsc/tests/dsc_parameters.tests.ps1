@@ -902,4 +902,318 @@ parameters:
         $LASTEXITCODE | Should -Be 4
         $out | Should -Match 'Failed to merge parameters'
     }
-}
\ No newline at end of file
+
+    It 'Object and array parameters are validated against their schema: <type>' -TestCases @(
+        @{ type = 'object'; value = @{ port = 8080; host = 'localhost' } }
+        @{ type = 'secureObject'; value = @{ port = 8080; host = 'localhost' } }
+        @{ type = 'array'; value = @(@{ port = 8080; host = 'localhost' }) }
+    ) {
+        param($type, $value)
+
+        $schema = if ($type -eq 'array') { '{ type: array, items: { type: object, required: [host, port], properties: { port: { type: integer, maximum: 65535 } } } }' } else { '{ type: object, required: [host, port], properties: { port: { type: integer, maximum: 65535 } } }' }
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: $type
+                schema: $schema
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: '[parameters(''param1'')]'
+"@
+        $params_json = @{ parameters = @{ param1 = $value }} | ConvertTo-Json -Depth 5
+        $null = $config_yaml | dsc config -p $params_json get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+    }
+
+    It 'Parameter not matching its schema returns error with the failing path: <path>' -TestCases @(
+        @{ type = 'object'; value = @{ port = 70000; host = 'localhost' }; path = '/port' }
+        @{ type = 'object'; value = @{ port = 8080 }; path = '/' }
+        @{ type = 'array'; value = @(@{ port = 8080; host = 'localhost' }, @{ port = 70000; host = 'localhost' }); path = '/1/port' }
+    ) {
+        param($type, $value, $path)
+
+        $schema = if ($type -eq 'array') { '{ type: array, items: { type: object, required: [host, port], properties: { port: { type: integer, maximum: 65535 } } } }' } else { '{ type: object, required: [host, port], properties: { port: { type: integer, maximum: 65535 } } }' }
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: $type
+                schema: $schema
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: '[parameters(''param1'')]'
+"@
+        $params_json = @{ parameters = @{ param1 = $value }} | ConvertTo-Json -Depth 5
+        $null = $config_yaml | dsc config -p $params_json get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 4
+        $errorLog = Get-Content $TestDrive/error.log -Raw
+        $errorLog | Should -BeLike "*Parameter 'param1' does not match its schema*"
+        $errorLog | Should -BeLike "*at '$path'*"
+    }
+
+    It 'Default value not matching the schema returns error' {
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: array
+                schema: { type: array, items: { type: string }, uniqueItems: true }
+                defaultValue: [one, one]
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: '[parameters(''param1'')]'
+"@
+        $null = $config_yaml | dsc config get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 4
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike "*Parameter 'param1' does not match its schema*"
+    }
+
+    It 'Secure object values are not included in schema errors' {
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: secureObject
+                schema: { type: object, properties: { password: { type: string, minLength: 20 } } }
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: '[parameters(''param1'')]'
+"@
+        $params_json = @{ parameters = @{ param1 = @{ password = 'mySecret' } }} | ConvertTo-Json -Depth 5
+        $null = $config_yaml | dsc config -p $params_json get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 4
+        $errorLog = Get-Content $TestDrive/error.log -Raw
+        $errorLog | Should -BeLike "*at '/password'*"
+        $errorLog | Should -Not -BeLike '*mySecret*'
+    }
+
+    It 'Schema on a string parameter returns error' {
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: string
+                schema: { type: string }
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: '[parameters(''param1'')]'
+"@
+        $params_json = @{ parameters = @{ param1 = 'hello' }} | ConvertTo-Json
+        $null = $config_yaml | dsc config -p $params_json get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 4
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike "*Parameter 'param1' has schema constraint*"
+    }
+
+    It 'Secure parameter values are scrubbed from results and traces: <type>' -TestCases @(
+        @{ type = 'secureString'; value = 'mySuperSecret'; output = "[parameters('param1')]" }
+        @{ type = 'secureObject'; value = @{ password = 'mySuperSecret' }; output = "[parameters('param1').password]" }
+    ) {
+        param($type, $value, $output)
+
+        $config_yaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: $type
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "$output"
+                showSecrets: true
+"@
+        $params_json = @{ parameters = @{ param1 = $value }} | ConvertTo-Json -Depth 5 -Compress
+        $out = $config_yaml | dsc -l trace -p json config -p $params_json get -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out | Should -Not -BeLike '*mySuperSecret*'
+        ($out | ConvertFrom-Json).results[0].result.actualState.output | Should -BeExactly '<secureValue>'
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike '*<secureValue>*'
+        (Get-Content $TestDrive/error.log -Raw) | Should -Not -BeLike '*mySuperSecret*'
+    }
+
+    It 'Secure parameter value matching a property name only scrubs values' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: secureString
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[parameters('param1')]"
+'@
+        $out = $config_yaml | dsc config -p '{"parameters":{"param1":"actualState"}}' get -f - -o json 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        ($out | ConvertFrom-Json).results[0].result.actualState.output | Should -BeExactly '<secureValue>'
+    }
+
+    It 'Secure parameter values are shown in results when DSC_SHOW_SECURE_VALUES is set' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              param1:
+                type: secureString
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[parameters('param1')]"
+                showSecrets: true
+'@
+        $oldShowSecureValues = $env:DSC_SHOW_SECURE_VALUES
+        try {
+            $env:DSC_SHOW_SECURE_VALUES = 'true'
+            $params_json = @{ parameters = @{ param1 = 'mySuperSecret' }} | ConvertTo-Json -Compress
+            $out = $config_yaml | dsc config -p $params_json get -f - 2> $TestDrive/error.log | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+            $out.results[0].result.actualState.output | Should -BeExactly 'mySuperSecret'
+        }
+        finally {
+            $env:DSC_SHOW_SECURE_VALUES = $oldShowSecureValues
+        }
+    }
+
+    It 'Multiple parameters files are merged in order' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              first:
+                type: string
+              second:
+                type: string
+              third:
+                type: string
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[concat(parameters('first'), ',', parameters('second'), ',', parameters('third'))]"
+'@
+        Set-Content -Path $TestDrive/one.parameters.yaml -Value "parameters: { first: one, second: one, third: one }"
+        Set-Content -Path $TestDrive/two.parameters.json -Value '{ "parameters": { "second": "two", "third": "two" } }'
+        $out = dsc config -f $TestDrive/one.parameters.yaml -f $TestDrive/two.parameters.json -p '{"parameters":{"third":"inline"}}' get -i $config_yaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results[0].result.actualState.output | Should -BeExactly 'one,two,inline'
+    }
+
+    It 'Parameters can be set from DSC_PARAM_ environment variables' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              myString:
+                type: string
+              myInt:
+                type: int
+              myObject:
+                type: object
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[concat(parameters('myString'), ',', string(parameters('myInt')), ',', parameters('myObject').name)]"
+'@
+        try {
+            $env:DSC_PARAM_MYSTRING = 'fromEnv'
+            $env:DSC_PARAM_myInt = '42'
+            $env:DSC_PARAM_MYOBJECT = '{"name":"envObject"}'
+            $out = dsc config get -i $config_yaml 2> $TestDrive/error.log | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+            $out.results[0].result.actualState.output | Should -BeExactly 'fromEnv,42,envObject'
+
+            $env:DSC_PARAM_MYOBJECT = 'notJson'
+            $null = dsc config get -i $config_yaml 2> $TestDrive/error.log
+            $LASTEXITCODE | Should -Be 4
+            (Get-Content $TestDrive/error.log -Raw) | Should -BeLike "*Environment variable 'DSC_PARAM_MYOBJECT' is not valid JSON*"
+        }
+        finally {
+            $env:DSC_PARAM_MYSTRING = $null
+            $env:DSC_PARAM_myInt = $null
+            $env:DSC_PARAM_MYOBJECT = $null
+        }
+    }
+
+    It 'DSC_PARAM_ environment variables are added as values to complex parameters input' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              myString:
+                type: string
+              myInt:
+                type: int
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[concat(parameters('myString'), ',', string(parameters('myInt')))]"
+'@
+        try {
+            $env:DSC_PARAM_MYINT = '42'
+            $out = dsc config -p '{"parameters":{"myString":{"value":"complex"}}}' get -i $config_yaml 2> $TestDrive/error.log | ConvertFrom-Json
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+            $out.results[0].result.actualState.output | Should -BeExactly 'complex,42'
+        }
+        finally {
+            $env:DSC_PARAM_MYINT = $null
+        }
+    }
+
+    It 'Effective parameters show the value and source of each parameter' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            parameters:
+              fromDefault:
+                type: string
+                defaultValue: default
+              fromFile:
+                type: string
+              fromEnv:
+                type: string
+              fromInline:
+                type: string
+              secret:
+                type: secureString
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[parameters('fromDefault')]"
+'@
+        $file_path = "$TestDrive/effective.parameters.yaml"
+        Set-Content -Path $file_path -Value "parameters: { fromFile: file, fromEnv: file, fromInline: file, secret: mySuperSecret }"
+        try {
+            $env:DSC_PARAM_FROMENV = 'env'
+            $env:DSC_PARAM_FROMINLINE = 'env'
+            $out = dsc config -f $file_path -p '{"parameters":{"fromInline":"inline"}}' --print-effective-parameters get -i $config_yaml -o json 2> $TestDrive/error.log
+            $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        }
+        finally {
+            $env:DSC_PARAM_FROMENV = $null
+            $env:DSC_PARAM_FROMINLINE = $null
+        }
+        $out | Should -Not -BeLike '*mySuperSecret*'
+        $effective = $out | ConvertFrom-Json
+        $effective.PSObject.Properties.Name | Should -Not -Contain 'results'
+        $effective.fromDefault.value | Should -BeExactly 'default'
+        $effective.fromDefault.source | Should -BeExactly 'default'
+        $effective.fromFile.value | Should -BeExactly 'file'
+        $effective.fromFile.source | Should -BeExactly "file:$file_path"
+        $effective.fromEnv.value | Should -BeExactly 'env'
+        $effective.fromEnv.source | Should -BeExactly 'env:DSC_PARAM_FROMENV'
+        $effective.fromInline.value | Should -BeExactly 'inline'
+        $effective.fromInline.source | Should -BeExactly 'inline'
+        $effective.secret.value | Should -BeExactly '<secureValue>'
+        $effective.secret.source | Should -BeExactly "file:$file_path"
+    }
+}
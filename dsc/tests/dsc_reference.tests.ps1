@@ -22,4 +22,66 @@ Describe 'Tests for config using reference function' {
 
         $out.results[1].result.actualState.Output | Should -BeExactly "The OS is $os"
     }
+
+    It 'Existing resource is only read for <operation>' -TestCases @(
+        @{ operation = 'get'; property = 'actualState'; count = 2 },
+        @{ operation = 'set'; property = 'afterState'; count = 1 },
+        @{ operation = 'test'; property = 'actualState'; count = 1 }
+    ) {
+        param($operation, $property, $count)
+
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: os
+  type: Microsoft/OSInfo
+  existing: true
+  properties: {}
+- name: Echo
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: "[concat('The OS is ', reference(resourceId('Microsoft/OSInfo','os')).family)]"
+  dependsOn:
+  - "[resourceId('Microsoft/OSInfo','os')]"
+'@
+        $out = dsc -l trace config $operation -i $config 2>$TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $os = if ($IsWindows) {
+            'Windows'
+        }
+        elseif ($IsLinux) {
+            'Linux'
+        }
+        else {
+            'macOS'
+        }
+
+        $out.results.Count | Should -Be $count
+        $out.results[-1].name | Should -BeExactly 'Echo'
+        $out.results[-1].result.$property.Output | Should -BeExactly "The OS is $os"
+        if ($operation -ne 'get') {
+            (Get-Content $TestDrive/error.log -Raw) | Should -BeLike "*Resource 'os' is existing so only get is performed*"
+        }
+    }
+
+    It 'Existing resource is not exported' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: os
+  type: Microsoft/OSInfo
+  existing: true
+  properties: {}
+- name: Echo
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: "[reference(resourceId('Microsoft/OSInfo','os')).family]"
+  dependsOn:
+  - "[resourceId('Microsoft/OSInfo','os')]"
+'@
+        $out = dsc config export -i $config 2>$TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.resources.Count | Should -Be 1
+        $out.resources[0].type | Should -BeExactly 'Microsoft.DSC.Debug/Echo'
+    }
 }
@@ -0,0 +1,95 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Tests for resources using a persistent host' {
+    BeforeAll {
+        $hostScript = @'
+while ($null -ne ($line = [Console]::In.ReadLine())) {
+    $request = $line | ConvertFrom-Json
+    if ($null -eq $request.id) {
+        # shutdown notification
+        break
+    }
+    $inputObject = if ($request.params.input) { $request.params.input | ConvertFrom-Json } else { [pscustomobject]@{} }
+    $state = @{ name = $inputObject.name; pid = $PID; operation = $request.method }
+    $response = @{ jsonrpc = '2.0'; id = $request.id; result = @{ exitCode = 0; stdout = ($state | ConvertTo-Json -Compress) } }
+    [Console]::Out.WriteLine(($response | ConvertTo-Json -Compress -Depth 5))
+    [Console]::Out.Flush()
+}
+'@
+        Set-Content -Path (Join-Path $TestDrive 'host.ps1') -Value $hostScript
+
+        $manifest = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/resource/manifest.json'
+            type = 'Test/Hosted'
+            version = '0.1.0'
+            host = @{
+                executable = 'pwsh'
+                args = @('-NoLogo', '-NonInteractive', '-NoProfile', '-File', 'host.ps1')
+            }
+            get = @{
+                executable = 'pwsh'
+                input = 'stdin'
+            }
+        }
+        $manifest | ConvertTo-Json -Depth 5 | Set-Content -Path (Join-Path $TestDrive 'hosted.dsc.resource.json')
+
+        $oldResourcePath = $env:DSC_RESOURCE_PATH
+        $env:DSC_RESOURCE_PATH = $TestDrive + [System.IO.Path]::PathSeparator + $env:PATH
+    }
+
+    AfterAll {
+        $env:DSC_RESOURCE_PATH = $oldResourcePath
+    }
+
+    It 'Resource get uses the host' {
+        $out = '{"name":"one"}' | dsc resource get -r Test/Hosted -f - 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.actualState.name | Should -BeExactly 'one'
+        $out.actualState.operation | Should -BeExactly 'get'
+    }
+
+    It 'Config get reuses a single host for all instances' {
+        $config_yaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: First
+              type: Test/Hosted
+              properties:
+                name: first
+            - name: Second
+              type: Test/Hosted
+              properties:
+                name: second
+'@
+        $out = $config_yaml | dsc config get -f - 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results.Count | Should -Be 2
+        $out.results[0].result.actualState.name | Should -BeExactly 'first'
+        $out.results[1].result.actualState.name | Should -BeExactly 'second'
+        $out.results[0].result.actualState.pid | Should -Be $out.results[1].result.actualState.pid
+        $out.results[0].result.actualState.pid | Should -Not -Be $PID
+    }
+
+    It 'Request fails when the host does not respond within the timeout' {
+        Set-Content -Path (Join-Path $TestDrive 'silent.ps1') -Value 'while ($null -ne [Console]::In.ReadLine()) { }'
+        $manifest = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/resource/manifest.json'
+            type = 'Test/SilentHost'
+            version = '0.1.0'
+            host = @{
+                executable = 'pwsh'
+                args = @('-NoLogo', '-NonInteractive', '-NoProfile', '-File', 'silent.ps1')
+                timeout = 2
+            }
+            get = @{
+                executable = 'pwsh'
+                input = 'stdin'
+            }
+        }
+        $manifest | ConvertTo-Json -Depth 5 | Set-Content -Path (Join-Path $TestDrive 'silent.dsc.resource.json')
+        $null = '{"name":"one"}' | dsc resource get -r Test/SilentHost -f - 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Not -Be 0
+        (Get-Content $TestDrive/error.log -Raw) | Should -BeLike '*did not respond within 2 seconds*'
+    }
+}
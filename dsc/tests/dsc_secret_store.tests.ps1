@@ -0,0 +1,140 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+Describe 'Local secret store tests' {
+    BeforeAll {
+        $oldPath = $env:DSC_SECRET_STORE_PATH
+        $oldKeyFile = $env:DSC_SECRET_STORE_KEY_FILE
+        $oldPassphrase = $env:DSC_SECRET_STORE_PASSPHRASE
+        $oldShowSecureValues = $env:DSC_SHOW_SECURE_VALUES
+        $env:DSC_SHOW_SECURE_VALUES = 'true'
+        $keyFile = Join-Path $TestDrive 'store.key'
+        Set-Content -Path $keyFile -Value 'correct horse battery staple'
+    }
+
+    BeforeEach {
+        $env:DSC_SECRET_STORE_PATH = Join-Path $TestDrive "secrets-$(New-Guid).json"
+        $env:DSC_SECRET_STORE_KEY_FILE = $keyFile
+        $env:DSC_SECRET_STORE_PASSPHRASE = $null
+    }
+
+    AfterAll {
+        $env:DSC_SECRET_STORE_PATH = $oldPath
+        $env:DSC_SECRET_STORE_KEY_FILE = $oldKeyFile
+        $env:DSC_SECRET_STORE_PASSPHRASE = $oldPassphrase
+        $env:DSC_SHOW_SECURE_VALUES = $oldShowSecureValues
+    }
+
+    It 'Secret can be set, retrieved, listed, and removed' {
+        'myPassword' | dsc secret set --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $null = 'otherValue' | dsc secret set --name Other --vault VaultA 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+
+        dsc secret get --name MySecret | Should -BeExactly 'myPassword'
+        dsc secret get --name Other --vault VaultA | Should -BeExactly 'otherValue'
+
+        $list = dsc secret list -o json | ConvertFrom-Json
+        $list.Count | Should -Be 2
+        ($list | Where-Object name -eq 'MySecret').vault | Should -BeExactly 'default'
+        ($list | Where-Object name -eq 'Other').vault | Should -BeExactly 'VaultA'
+        $list | ConvertTo-Json | Should -Not -Match 'myPassword'
+
+        $null = dsc secret remove --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $null = dsc secret get --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match "Secret 'MySecret' not found"
+    }
+
+    It 'Store is encrypted' {
+        $null = 'myPassword' | dsc secret set --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $store = Get-Content $env:DSC_SECRET_STORE_PATH -Raw
+        $store | Should -Not -Match 'myPassword'
+        $store | Should -Not -Match 'MySecret'
+        ($store | ConvertFrom-Json).version | Should -Be 1
+        if (!$IsWindows) {
+            (Get-Item $env:DSC_SECRET_STORE_PATH).UnixMode | Should -BeExactly '-rw-------'
+        }
+    }
+
+    It 'Wrong key returns error' {
+        $null = 'myPassword' | dsc secret set --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $env:DSC_SECRET_STORE_KEY_FILE = $null
+        $env:DSC_SECRET_STORE_PASSPHRASE = 'wrong passphrase'
+        $null = dsc secret get --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'the key is incorrect'
+    }
+
+    It 'Missing key returns error' {
+        $env:DSC_SECRET_STORE_KEY_FILE = $null
+        $null = 'myPassword' | dsc secret set --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'No key for the secret store'
+    }
+
+    It 'Passphrase can be used instead of a key file' {
+        $env:DSC_SECRET_STORE_KEY_FILE = $null
+        $env:DSC_SECRET_STORE_PASSPHRASE = 'my passphrase'
+        $null = 'myPassword' | dsc secret set --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        dsc secret get --name MySecret | Should -BeExactly 'myPassword'
+    }
+
+    It 'Secret in multiple vaults with different values requires a vault' {
+        $null = 'one' | dsc secret set --name MySecret --vault VaultA 2> $TestDrive/error.log
+        $null = 'two' | dsc secret set --name MySecret --vault VaultB 2> $TestDrive/error.log
+        $null = dsc secret get --name MySecret 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'specify a vault'
+        dsc secret get --name MySecret --vault VaultB | Should -BeExactly 'two'
+    }
+
+    It 'Extension is discovered with the secret capability' {
+        $out = dsc extension list 'Microsoft.DSC/SecretStore' -o json | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0
+        $out.capabilities | Should -Contain 'secret'
+    }
+
+    It 'Configuration can use secret() with the store: <vault>' -TestCases @(
+        @{ vault = $null; expected = 'myPassword' }
+        @{ vault = 'VaultA'; expected = 'otherValue' }
+    ) {
+        param($vault, $expected)
+
+        $null = 'myPassword' | dsc secret set --name StoreSecret 2> $TestDrive/error.log
+        $null = 'otherValue' | dsc secret set --name OtherSecret --vault VaultA 2> $TestDrive/error.log
+        $expression = if ($vault) { "[secret('OtherSecret', '$vault')]" } else { "[secret('StoreSecret')]" }
+        $configYaml = @"
+            `$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "$expression"
+                showSecrets: true
+"@
+        $out = dsc config get -i $configYaml 2> $TestDrive/error.log | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/error.log -Raw)
+        $out.results[0].result.actualState.Output | Should -BeExactly $expected
+    }
+
+    It 'Missing store does not fail other secret providers' {
+        $configYaml = @'
+            $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+            resources:
+            - name: Echo
+              type: Microsoft.DSC.Debug/Echo
+              properties:
+                output: "[secret('DoesNotExist')]"
+'@
+        $null = dsc config get -i $configYaml 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 2
+        $errorLog = Get-Content $TestDrive/error.log -Raw
+        $errorLog | Should -Match "Secret 'DoesNotExist' not found"
+        $errorLog | Should -Not -Match 'Microsoft.DSC/SecretStore'
+    }
+}
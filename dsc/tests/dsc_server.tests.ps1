@@ -71,6 +71,7 @@ Describe 'Tests for DSC server' {
         }
 
         $tools = @{
+            'diff_dsc_config'       = $false
             'invoke_dsc_config'     = $false
             'invoke_dsc_expression' = $false
             'invoke_dsc_function'   = $false
@@ -79,6 +80,8 @@ Describe 'Tests for DSC server' {
             'list_dsc_resources'    = $false
             'show_dsc_resource'     = $false
             'show_dsc_schema'       = $false
+            'validate_dsc_config'   = $false
+            'what_if_dsc_config'    = $false
         }
 
         $response = Send-McpRequest -request $mcpRequest
@@ -828,4 +831,696 @@ greeting: Hello from YAML parameters
         $response.error.code | Should -Be -32600
         $response.error.message | Should -BeExactly "Function 'add' invocation failed: Parser: Function 'add' does not accept string arguments, accepted types are: Number"
     }
+
+    It 'Resources/List returns functions, schemas, and examples' {
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 25
+            method  = "resources/list"
+            params  = @{}
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 25
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.resources.uri | Should -Contain 'dsc://functions' -Because $because
+        $response.result.resources.uri | Should -Contain 'dsc://schemas/configuration' -Because $because
+        $response.result.resources.uri | Should -Contain 'dsc://examples/osinfo_parameters.dsc.yaml' -Because $because
+        $examples = Get-ChildItem -Path "$PSScriptRoot/../examples" -Filter '*.dsc.yaml' | Where-Object Name -NotLike 'invalid_*'
+        foreach ($example in $examples) {
+            $response.result.resources.uri | Should -Contain "dsc://examples/$($example.Name)" -Because $because
+        }
+    }
+
+    It 'Resources/Templates/List returns resource manifest template' {
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 26
+            method  = "resources/templates/list"
+            params  = @{}
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 26
+        $response.result.resourceTemplates.uriTemplate | Should -Contain 'dsc://resources/{type}/manifest'
+    }
+
+    It 'Resources/Read returns <uri>' -TestCases @(
+        @{ uri = 'dsc://functions'; expected = '"name": "concat"' }
+        @{ uri = 'dsc://schemas/configuration'; expected = '"resources"' }
+        @{ uri = 'dsc://examples/osinfo_parameters.dsc.yaml'; expected = 'Microsoft/OSInfo' }
+        @{ uri = 'dsc://resources/Microsoft.DSC.Debug/Echo/manifest'; expected = '"type": "Microsoft.DSC.Debug/Echo"' }
+    ) {
+        param($uri, $expected)
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 27
+            method  = "resources/read"
+            params  = @{
+                uri = $uri
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 27
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.contents[0].uri | Should -BeExactly $uri -Because $because
+        $response.result.contents[0].text | Should -BeLike "*$expected*" -Because $because
+    }
+
+    It 'Resources/Read with unknown URI returns error' {
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 28
+            method  = "resources/read"
+            params  = @{
+                uri = 'dsc://examples/doesNotExist.dsc.yaml'
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 28
+        $response.error.message | Should -BeLike "*not found*"
+    }
+
+    It 'Prompts/List returns prompts' {
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 29
+            method  = "prompts/list"
+            params  = @{}
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 29
+        $response.result.prompts.name | Should -Contain 'author_configuration'
+        $response.result.prompts.name | Should -Contain 'explain_configuration'
+    }
+
+    It 'Prompts/Get for author_configuration includes resource schema' {
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 30
+            method  = "prompts/get"
+            params  = @{
+                name      = "author_configuration"
+                arguments = @{
+                    resource_type = "Microsoft.DSC.Debug/Echo"
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 30
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.messages[0].role | Should -BeExactly 'user' -Because $because
+        $response.result.messages[0].content.text | Should -BeLike "*Microsoft.DSC.Debug/Echo*" -Because $because
+        $response.result.messages[0].content.text | Should -BeLike "*output*" -Because $because
+    }
+
+    It 'Calling what_if_dsc_config returns what-if result' {
+        $config = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{
+                    name = 'Echo'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{
+                        output = 'Hello'
+                    }
+                }
+            )
+        } | ConvertTo-Json -Depth 10
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 31
+            method  = "tools/call"
+            params  = @{
+                name      = "what_if_dsc_config"
+                arguments = @{
+                    configuration = $config
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 31
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.structuredContent.result.metadata.'Microsoft.DSC'.executionType | Should -BeExactly 'whatIf' -Because $because
+        $response.result.structuredContent.result.results[0].type | Should -BeExactly 'Microsoft.DSC.Debug/Echo' -Because $because
+    }
+
+    It 'Calling validate_dsc_config returns diagnostics for invalid resource properties' {
+        $config = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{
+                    name = 'Valid'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{
+                        output = 'Hello'
+                    }
+                }
+                @{
+                    name = 'Invalid'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{
+                        notAProperty = 'Hello'
+                    }
+                }
+                @{
+                    name = 'Missing'
+                    type = 'Test/DoesNotExist'
+                    properties = @{}
+                }
+            )
+        } | ConvertTo-Json -Depth 10
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 32
+            method  = "tools/call"
+            params  = @{
+                name      = "validate_dsc_config"
+                arguments = @{
+                    configuration = $config
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 32
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.structuredContent.valid | Should -BeFalse -Because $because
+        $response.result.structuredContent.diagnostics.Count | Should -Be 2 -Because $because
+        $response.result.structuredContent.diagnostics[0].resourceName | Should -BeExactly 'Invalid' -Because $because
+        $response.result.structuredContent.diagnostics[1].resourceName | Should -BeExactly 'Missing' -Because $because
+    }
+
+    It 'Calling validate_dsc_config for valid configuration' {
+        $config = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{
+                    name = 'Valid'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{
+                        output = 'Hello'
+                    }
+                }
+            )
+        } | ConvertTo-Json -Depth 10
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 33
+            method  = "tools/call"
+            params  = @{
+                name      = "validate_dsc_config"
+                arguments = @{
+                    configuration = $config
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 33
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.structuredContent.valid | Should -BeTrue -Because $because
+        $response.result.structuredContent.diagnostics.Count | Should -Be 0 -Because $because
+    }
+
+    It 'Calling diff_dsc_config returns differing properties' {
+        $config = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{
+                    name = 'Operation'
+                    type = 'Test/Operation'
+                    properties = @{
+                        hello = 'World'
+                        action = 'test'
+                    }
+                }
+            )
+        } | ConvertTo-Json -Depth 10
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 34
+            method  = "tools/call"
+            params  = @{
+                name      = "diff_dsc_config"
+                arguments = @{
+                    configuration = $config
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 34
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error | Should -BeNullOrEmpty -Because $because
+        $response.result.structuredContent.resources[0].name | Should -BeExactly 'Operation' -Because $because
+        $response.result.structuredContent.resources[0].type | Should -BeExactly 'Test/Operation' -Because $because
+        $response.result.structuredContent.inDesiredState | Should -Be $response.result.structuredContent.resources[0].inDesiredState -Because $because
+    }
+
+    It 'Calling invoke_dsc_expression with configuration parameters and variables' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  greeting:
+    type: string
+    defaultValue: Hello
+variables:
+  target: World
+resources: []
+'@
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 35
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_expression"
+                arguments = @{
+                    expression    = "[concat(parameters('greeting'), ' ', variables('target'))]"
+                    configuration = $config
+                    parameters    = "greeting: Hi"
+                }
+            }
+        }
+
+        $response = Send-McpRequest -request $mcpRequest
+        $response.id | Should -Be 35
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.result.structuredContent.result | Should -BeExactly 'Hi World' -Because $because
+    }
+
+    It 'Calling invoke_dsc_config with progress token sends progress notifications' {
+        $config = @{
+            '$schema' = 'https://aka.ms/dsc/schemas/v3/bundled/config/document.json'
+            resources = @(
+                @{
+                    name = 'One'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{ output = 'one' }
+                }
+                @{
+                    name = 'Two'
+                    type = 'Microsoft.DSC.Debug/Echo'
+                    properties = @{ output = 'two' }
+                }
+            )
+        } | ConvertTo-Json -Depth 10
+
+        $mcpRequest = @{
+            jsonrpc = "2.0"
+            id      = 36
+            method  = "tools/call"
+            params  = @{
+                _meta     = @{ progressToken = 'progress-36' }
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "get"
+                    configuration = $config
+                }
+            }
+        }
+
+        Send-McpRequest -request $mcpRequest -notify
+        $notifications = @()
+        do {
+            $message = $mcp.StandardOutput.ReadLine() | ConvertFrom-Json -Depth 30
+            if ($message.method -eq 'notifications/progress') {
+                $notifications += $message
+            }
+        } until ($message.id -eq 36)
+
+        $because = ($notifications | ConvertTo-Json -Depth 20 | Out-String)
+        $message.error | Should -BeNullOrEmpty
+        $notifications.Count | Should -Be 4 -Because $because
+        $notifications.params.progressToken | Should -Be @('progress-36', 'progress-36', 'progress-36', 'progress-36') -Because $because
+        $notifications.params.total | Should -Be @(2, 2, 2, 2) -Because $because
+        $notifications[-1].params.progress | Should -Be 2 -Because $because
+        $notifications[0].params.message | Should -Match 'One' -Because $because
+        $notifications[-1].params.message | Should -Match 'Two' -Because $because
+    }
+}
+
+Describe 'Tests for DSC server over HTTP' {
+    BeforeAll {
+        $port = Get-Random -Minimum 20000 -Maximum 40000
+        $uri = "http://127.0.0.1:$port/mcp"
+        $oldToken = $env:DSC_MCP_BEARER_TOKEN
+        $env:DSC_MCP_BEARER_TOKEN = 'test-token'
+        $mcp = Start-Process -FilePath dsc -ArgumentList 'server', '--transport', 'http', '--listen', "127.0.0.1:$port" -PassThru -RedirectStandardError $TestDrive/server.log
+        $env:DSC_MCP_BEARER_TOKEN = $oldToken
+
+        $initialize = @{
+            jsonrpc = "2.0"
+            id      = 1
+            method  = "initialize"
+            params  = @{
+                protocolVersion = "2025-03-26"
+                capabilities    = @{}
+                clientInfo      = @{
+                    name    = "Test Client"
+                    version = "1.0.0"
+                }
+            }
+        } | ConvertTo-Json -Depth 10 -Compress
+        $headers = @{ Accept = 'application/json, text/event-stream' }
+
+        # wait for the server to start listening
+        $started = $false
+        for ($i = 0; $i -lt 50 -and -not $started; $i++) {
+            try {
+                $null = Invoke-WebRequest -Uri $uri -Method Post -Body $initialize -ContentType 'application/json' -Headers $headers -SkipHttpErrorCheck
+                $started = $true
+            }
+            catch {
+                Start-Sleep -Milliseconds 100
+            }
+        }
+    }
+
+    AfterAll {
+        $mcp | Stop-Process -Force -ErrorAction Ignore
+    }
+
+    It 'Request without bearer token is rejected' {
+        $response = Invoke-WebRequest -Uri $uri -Method Post -Body $initialize -ContentType 'application/json' -Headers $headers -SkipHttpErrorCheck
+        $response.StatusCode | Should -Be 401
+    }
+
+    It 'Initialization works with bearer token' {
+        $headers['Authorization'] = 'Bearer test-token'
+        $response = Invoke-WebRequest -Uri $uri -Method Post -Body $initialize -ContentType 'application/json' -Headers $headers -SkipHttpErrorCheck
+        $response.StatusCode | Should -Be 200 -Because (Get-Content $TestDrive/server.log -Raw)
+        $response.Headers['Mcp-Session-Id'] | Should -Not -BeNullOrEmpty
+        $response.Content | Should -Match '"instructions"'
+    }
+
+    It 'Non-loopback address requires allow-remote' {
+        $null = dsc server --transport http --listen 0.0.0.0:$port 2> $TestDrive/error.log
+        $LASTEXITCODE | Should -Be 9
+        (Get-Content $TestDrive/error.log -Raw) | Should -Match 'allow-remote'
+    }
+
+    It 'Non-loopback address requires a bearer token' {
+        $oldToken = $env:DSC_MCP_BEARER_TOKEN
+        try {
+            $env:DSC_MCP_BEARER_TOKEN = $null
+            $null = dsc server --transport http --listen 0.0.0.0:$port --allow-remote 2> $TestDrive/error.log
+            $LASTEXITCODE | Should -Be 9
+            (Get-Content $TestDrive/error.log -Raw) | Should -Match 'DSC_MCP_BEARER_TOKEN'
+        }
+        finally {
+            $env:DSC_MCP_BEARER_TOKEN = $oldToken
+        }
+    }
+}
+
+Describe 'Tests for DSC server policy' {
+    BeforeAll {
+        $processStartInfo = [System.Diagnostics.ProcessStartInfo]::new()
+        $processStartInfo.FileName = "dsc"
+        $processStartInfo.Arguments = "--trace-format plaintext server --mode read-only --allow-resource Test/Operation --allow-resource Microsoft.DSC/Group --allow-resource Microsoft.DSC/Include"
+        # module sources are resolved against the working directory of the server
+        $processStartInfo.WorkingDirectory = $TestDrive
+        $processStartInfo.UseShellExecute = $false
+        $processStartInfo.RedirectStandardError = $true
+        $processStartInfo.RedirectStandardOutput = $true
+        $processStartInfo.RedirectStandardInput = $true
+        $mcp = [System.Diagnostics.Process]::Start($processStartInfo)
+
+        function Send-McpRequest($request, [switch]$notify) {
+            $request = $request | ConvertTo-Json -Compress -Depth 10
+            $mcp.StandardInput.WriteLine($request)
+            $mcp.StandardInput.Flush()
+            if (!$notify) {
+                while ($mcp.StandardOutput.Peek() -eq -1) {
+                    Start-Sleep -Milliseconds 100
+                }
+                $stdout = $mcp.StandardOutput.ReadLine()
+                return ($stdout | ConvertFrom-Json -Depth 30)
+            }
+        }
+
+        $null = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 1
+            method  = "initialize"
+            params  = @{
+                protocolVersion = "2024-11-05"
+                capabilities    = @{}
+                clientInfo      = @{
+                    name    = "Test Client"
+                    version = "1.0.0"
+                }
+            }
+        }
+        Send-McpRequest -request @{ jsonrpc = "2.0"; method = "notifications/initialized" } -notify
+
+        function Invoke-DscResourceTool($operation, $resourceType) {
+            Send-McpRequest -request @{
+                jsonrpc = "2.0"
+                id      = 2
+                method  = "tools/call"
+                params  = @{
+                    name      = "invoke_dsc_resource"
+                    arguments = @{
+                        operation       = $operation
+                        resource_type   = $resourceType
+                        properties_json = (@{ hello = "World"; action = $operation } | ConvertTo-Json)
+                    }
+                }
+            }
+        }
+    }
+
+    AfterAll {
+        $mcp.StandardInput.Close()
+        $mcp.WaitForExit()
+    }
+
+    It 'Read-only mode allows operation: <operation>' -TestCases @(
+        @{ operation = 'get' }
+        @{ operation = 'test' }
+    ) {
+        param($operation)
+
+        $response = Invoke-DscResourceTool -operation $operation -resourceType 'Test/Operation'
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error | Should -BeNullOrEmpty -Because $because
+        $response.result.structuredContent.result | Should -Not -BeNullOrEmpty -Because $because
+    }
+
+    It 'Read-only mode denies operation: <operation>' -TestCases @(
+        @{ operation = 'set' }
+        @{ operation = 'delete' }
+    ) {
+        param($operation)
+
+        $response = Invoke-DscResourceTool -operation $operation -resourceType 'Test/Operation'
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*read-only*' -Because $because
+        $response.error.data.policy.mode | Should -BeExactly 'readOnly' -Because $because
+        $response.error.data.operation | Should -BeExactly $operation -Because $because
+    }
+
+    It 'Read-only mode allows set of a configuration with only existing resources' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Existing
+  type: Test/Operation
+  existing: true
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 7
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "set"
+                    configuration = $config
+                }
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error | Should -BeNullOrEmpty -Because $because
+    }
+
+    It 'Resource not in allow-list is denied' {
+        $response = Invoke-DscResourceTool -operation 'get' -resourceType 'Microsoft.DSC.Debug/Echo'
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*not allowed*' -Because $because
+        $response.error.data.resourceType | Should -BeExactly 'Microsoft.DSC.Debug/Echo' -Because $because
+        $response.error.data.policy.allowedResources[0] | Should -Contain 'Test/Operation' -Because $because
+    }
+
+    It 'Resource in a group not in allow-list is denied' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Group
+  type: Microsoft.DSC/Group
+  properties:
+    $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+    resources:
+    - name: Echo
+      type: Microsoft.DSC.Debug/Echo
+      properties:
+        output: hello
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 3
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "get"
+                    configuration = $config
+                }
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*not allowed*' -Because $because
+        $response.error.data.resourceType | Should -BeExactly 'Microsoft.DSC.Debug/Echo' -Because $because
+    }
+
+    It 'Include is denied when resources are restricted' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Include
+  type: Microsoft.DSC/Include
+  properties:
+    configurationContent: |
+      $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+      resources:
+      - name: Echo
+        type: Microsoft.DSC.Debug/Echo
+        properties:
+          output: hello
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 4
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "get"
+                    configuration = $config
+                }
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*Included configurations are not allowed*' -Because $because
+        $response.error.data.resourceType | Should -BeExactly 'Microsoft.DSC/Include' -Because $because
+    }
+
+    It 'Resources of a module are checked against the allow-list' {
+        @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Echo
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: hello
+'@ | Set-Content -Path (Join-Path $TestDrive 'policy_module.dsc.yaml')
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Module
+  kind: module
+  properties:
+    source: policy_module.dsc.yaml
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 5
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "get"
+                    configuration = $config
+                }
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*not allowed*' -Because $because
+        $response.error.data.resourceType | Should -BeExactly 'Microsoft.DSC.Debug/Echo' -Because $because
+    }
+
+    It 'Module nested in a group is denied when resources are restricted' {
+        $config = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: Group
+  type: Microsoft.DSC/Group
+  properties:
+    $schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+    resources:
+    - name: Module
+      kind: module
+      properties:
+        source: policy_module.dsc.yaml
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 6
+            method  = "tools/call"
+            params  = @{
+                name      = "invoke_dsc_config"
+                arguments = @{
+                    operation     = "get"
+                    configuration = $config
+                }
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike "*Nested module 'Module' is not allowed*" -Because $because
+    }
+
+    It 'Calling <tool> with a resource not in allow-list is denied' -TestCases @(
+        @{ tool = 'validate_dsc_config'; arguments = @{} }
+        @{ tool = 'invoke_dsc_expression'; arguments = @{ expression = "[parameters('greeting')]"; parameters = 'greeting: Hi' } }
+    ) {
+        param($tool, $arguments)
+
+        $arguments.configuration = @'
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  greeting:
+    type: string
+resources:
+- name: Echo
+  type: Microsoft.DSC.Debug/Echo
+  properties:
+    output: hello
+'@
+        $response = Send-McpRequest -request @{
+            jsonrpc = "2.0"
+            id      = 8
+            method  = "tools/call"
+            params  = @{
+                name      = $tool
+                arguments = $arguments
+            }
+        }
+        $because = ($response | ConvertTo-Json -Depth 20 | Out-String)
+        $response.error.message | Should -BeLike '*not allowed*' -Because $because
+        $response.error.data.resourceType | Should -BeExactly 'Microsoft.DSC.Debug/Echo' -Because $because
+        $response.error.data.operation | Should -BeExactly 'get' -Because $because
+    }
 }
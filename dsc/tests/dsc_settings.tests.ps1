@@ -121,6 +121,35 @@ Describe 'tests for dsc settings' {
         "$TestDrive/tracing.txt" | Should -FileContentMatchExactly 'Using Resource Path: Defaultv1SettingsDir'
     }
 
+    It 'trust policy refuses manifests that are not in the hash allowlist' {
+        $manifest = Join-Path $script:dscHome 'echo.dsc.resource.json'
+        $executable = (Get-Command dscecho -CommandType Application | Select-Object -First 1).Path
+        $hashes = @($manifest, $executable) | ForEach-Object { (Get-FileHash -Algorithm SHA256 -Path $_).Hash }
+        $script:dscDefaultv1Settings | Add-Member -Force -NotePropertyName 'trustPolicy' -NotePropertyValue @{
+            enforce = $true
+            allowedHashes = $hashes
+        }
+        $script:dscDefaultv1Settings | ConvertTo-Json -Depth 90 | Set-Content -Force -Path $script:dscSettingsFilePath
+
+        $out = dsc resource list 2> $TestDrive/tracing.txt | ConvertFrom-Json
+        $LASTEXITCODE | Should -Be 0 -Because (Get-Content $TestDrive/tracing.txt -Raw)
+        $out.type | Should -Contain 'Microsoft.DSC.Debug/Echo'
+        $out.type | Should -Not -Contain 'Test/Version'
+        "$TestDrive/tracing.txt" | Should -FileContentMatch 'Refusing untrusted manifest'
+    }
+
+    It 'trust policy with an invalid trusted key refuses all manifests' {
+        $script:dscDefaultv1Settings | Add-Member -Force -NotePropertyName 'trustPolicy' -NotePropertyValue @{
+            enforce = $true
+            trustedKeys = @('not a key')
+        }
+        $script:dscDefaultv1Settings | ConvertTo-Json -Depth 90 | Set-Content -Force -Path $script:dscSettingsFilePath
+
+        $out = dsc resource list 2> $TestDrive/tracing.txt | ConvertFrom-Json
+        $out | Should -BeNullOrEmpty
+        "$TestDrive/tracing.txt" | Should -FileContentMatch "Invalid 'trustPolicy' setting"
+    }
+
     It 'DSC_IGNORE_SETTINGS_FILE environment variable disables settings file' {
         $oldEnv = $env:DSC_IGNORE_SETTINGS_FILE
         try {
@@ -0,0 +1,29 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+[CmdletBinding()]
+param(
+    [Parameter()]
+    [string]$Name
+)
+
+$ErrorActionPreference = 'Stop'
+
+$request = [Console]::In.ReadToEnd() | ConvertFrom-Json
+switch ($Name) {
+    'test.reverse' {
+        $chars = $request.args[0].ToCharArray()
+        [array]::Reverse($chars)
+        ConvertTo-Json -InputObject (-join $chars) -Compress
+    }
+    'test.sum' {
+        ConvertTo-Json -InputObject ($request.args | Measure-Object -Sum).Sum -Compress
+    }
+    'test.mistyped' {
+        ConvertTo-Json -InputObject 'not a number' -Compress
+    }
+    default {
+        [Console]::Error.WriteLine("Unknown function '$Name'")
+        exit 1
+    }
+}
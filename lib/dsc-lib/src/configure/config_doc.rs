@@ -9,6 +9,7 @@ use serde_json::{Map, Value};
 use std::{collections::HashMap, fmt::Display};
 
 use crate::{
+    extensions::secret::SecretMetrics,
     schemas::{
         dsc_repo::DscRepoSchema,
         transforms::{idiomaticize_externally_tagged_enum, idiomaticize_string_enum}
@@ -119,6 +120,9 @@ pub struct MicrosoftDscMetadata {
     /// The operation being performed
     #[serde(skip_serializing_if = "Option::is_none")]
     pub operation: Option<Operation>,
+    /// The typed outputs of an included configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<HashMap<String, ConfigurationOutput>>,
     /// Indicates what needs to be restarted after the configuration operation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restart_required: Option<Vec<RestartRequired>>,
@@ -190,6 +194,9 @@ pub struct ExecutionInformation {
     /// Information about what-if operations performed during this execution, if any
     #[serde(skip_serializing_if = "Option::is_none")]
     pub what_if: Option<Value>,
+    /// Counts of the secrets retrieved during this execution, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SecretMetrics>,
 }
 
 impl ExecutionInformation {
@@ -206,6 +213,7 @@ impl ExecutionInformation {
             start_datetime: None,
             version: None,
             what_if: None,
+            secrets: None,
         }
     }
 
@@ -227,11 +235,25 @@ pub struct ConfigDirective {
     /// The required security context of the configuration operation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub security_context: Option<SecurityContextKind>,
+    /// Options for retrieving secrets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SecretDirective>,
     /// Required version of DSC
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<SemanticVersionReq>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SecretDirective {
+    /// The vault used when a secret is retrieved without specifying a vault
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_vault: Option<String>,
+    /// The extensions queried for secrets in order, the first one returning the secret is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_order: Option<Vec<FullyQualifiedTypeName>>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
 #[serde(rename_all = "camelCase")]
 #[dsc_repo_schema(base_name = "directive", folder_path = "resource")]
@@ -298,6 +320,17 @@ pub struct Output {
     pub value_or_copy: ValueOrCopy,
 }
 
+/// The value of a configuration output along with its declared type, used to pass the outputs of
+/// an included configuration to the including configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigurationOutput {
+    /// The declared type of the output
+    pub r#type: DataType,
+    /// The value of the output
+    pub value: Value,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 #[dsc_repo_schema(
@@ -350,6 +383,9 @@ pub struct Parameter {
     pub min_length: Option<i64>,
     #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
     pub max_length: Option<i64>,
+    /// A JSON schema that the value of an `object`, `secureObject`, or `array` parameter must be valid against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -391,6 +427,14 @@ impl Display for DataType {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[schemars(transform = idiomaticize_string_enum)]
+pub enum ResourceKind {
+    /// The instance is expanded into the resources of a configuration template
+    #[serde(rename = "module")]
+    Module,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[schemars(transform = idiomaticize_string_enum)]
 pub enum CopyMode {
@@ -433,9 +477,12 @@ pub struct Copy {
 pub struct Resource {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
-    /// The fully qualified name of the resource type
-    #[serde(rename = "type")]
+    /// The fully qualified name of the resource type, not used for a module
+    #[serde(rename = "type", default, skip_serializing_if = "is_module_type")]
     pub resource_type: FullyQualifiedTypeName,
+    /// The kind of the instance, a `module` is expanded into the resources of a configuration template
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ResourceKind>,
     #[serde(skip_serializing_if = "Option::is_none", alias = "apiVersion")]
     pub require_version: Option<ResourceVersionReq>,
     /// A friendly name for the resource instance
@@ -450,6 +497,9 @@ pub struct Resource {
     pub depends_on: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub copy: Option<Copy>,
+    /// Whether the instance only reads an existing resource, so only get is performed for it in any operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub properties: Option<Map<String, Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -486,6 +536,7 @@ impl Resource {
     #[must_use]
     pub fn new() -> Self {
         Self {
+            kind: None,
             resource_type: FullyQualifiedTypeName::default(),
             name: String::new(),
             depends_on: None,
@@ -495,12 +546,17 @@ impl Resource {
             metadata: None,
             condition: None,
             copy: None,
+            existing: None,
             resources: None,
             require_version: None,
         }
     }
 }
 
+fn is_module_type(resource_type: &FullyQualifiedTypeName) -> bool {
+    resource_type.is_empty()
+}
+
 impl Default for Resource {
     fn default() -> Self {
         Self::new()
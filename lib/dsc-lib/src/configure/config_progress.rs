@@ -4,6 +4,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::progress::Progress;
 use crate::schemas::transforms::idiomaticize_string_enum;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
@@ -29,3 +30,12 @@ pub struct ConfigurationResourceCompletedEvent {
     pub status: ConfigurationResourceCompletionStatus,
     pub errors: Option<Vec<String>>,
 }
+
+/// Receives events as the resources of a configuration are processed.
+pub trait ConfigurationProgressListener: Send + Sync {
+    /// Called when processing of a resource instance starts.
+    fn resource_started(&self, event: &ConfigurationResourceStartedEvent, progress: &Progress);
+
+    /// Called when processing of a resource instance completes.
+    fn resource_completed(&self, event: &ConfigurationResourceCompletedEvent, progress: &Progress);
+}
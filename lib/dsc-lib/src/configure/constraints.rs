@@ -1,8 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::configure::config_doc::Parameter;
+use crate::configure::config_doc::{DataType, Parameter};
 use crate::DscError;
+use jsonschema::Validator;
 use rust_i18n::t;
 use serde_json::Value;
 
@@ -168,4 +169,56 @@ pub fn check_allowed_values(name: &str, value: &Value, constraint: &Parameter) -
     Ok(())
 }
 
+/// Checks that the given value is valid against the JSON schema of the parameter.
+///
+/// # Arguments
+///
+/// * `name` - The name of the parameter.
+/// * `value` - The value of the parameter.
+/// * `constraint` - The constraints on the parameter.
+///
+/// # Returns
+///
+/// * `Ok(())` if the value matches the constraints.
+/// * `Err(DscError::Validation)` if the value does not match the constraints.
+///
+/// # Errors
+///
+/// * `DscError::Validation` if the schema is invalid or the value does not match the schema.
+pub fn check_schema(name: &str, value: &Value, constraint: &Parameter) -> Result<(), DscError> {
+    let Some(schema) = &constraint.schema else {
+        return Ok(());
+    };
+
+    let value = match constraint.parameter_type {
+        DataType::Object | DataType::Array => value,
+        // the value may already be wrapped as a secure object
+        DataType::SecureObject => value.get("secureObject").unwrap_or(value),
+        _ => return Err(DscError::Validation(t!("configure.constraints.schemaNotObjectOrArray", name = name).to_string())),
+    };
+
+    let validator = Validator::new(schema).map_err(|err| {
+        DscError::Validation(t!("configure.constraints.invalidSchema", name = name, error = err.to_string()).to_string())
+    })?;
+    let errors: Vec<String> = validator.iter_errors(value).map(|err| {
+        let path = match err.instance_path().as_str() {
+            "" => "/".to_string(),
+            path => path.to_string(),
+        };
+        // don't include the failing value of a secure object in the message
+        let message = if constraint.parameter_type == DataType::SecureObject {
+            err.masked().to_string()
+        } else {
+            err.to_string()
+        };
+        t!("configure.constraints.schemaError", path = path, error = message).to_string()
+    }).collect();
+
+    if !errors.is_empty() {
+        return Err(DscError::Validation(t!("configure.constraints.notMatchSchema", name = name, errors = errors.join("; ")).to_string()));
+    }
+
+    Ok(())
+}
+
 // TODO: check nullable
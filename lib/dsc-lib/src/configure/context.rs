@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use chrono::{DateTime, Local};
-use crate::{configure::config_doc::{ExecutionKind, Operation, UserFunctionDefinition}, extensions::dscextension::DscExtension};
+use crate::{configure::config_doc::{ExecutionKind, Operation, UserFunctionDefinition}, extensions::{dscextension::DscExtension, secret::SecretCache}};
 use dsc_lib_security_context::{get_security_context, SecurityContext};
 use serde_json::{Map, Value};
 use std::{collections::HashMap, path::PathBuf};
@@ -21,6 +21,7 @@ pub enum ProcessMode {
 
 #[derive(Clone, Debug)]
 pub struct Context {
+    pub configuration_outputs: HashMap<String, Map<String, Value>>,
     pub copy: HashMap<String, i64>,
     pub copy_current_loop_name: String,
     pub dsc_version: Option<String>,
@@ -37,6 +38,7 @@ pub struct Context {
     pub processing_parameter_defaults: bool,
     pub references: Map<String, Value>,
     pub restart_required: Option<Vec<RestartRequired>>,
+    pub secrets: std::cell::RefCell<SecretCache>,
     pub security_context: SecurityContextKind,
     pub start_datetime: DateTime<Local>,
     pub state_changed: HashMap<String, bool>,
@@ -50,6 +52,7 @@ impl Context {
     #[must_use]
     pub fn new() -> Self {
         Self {
+            configuration_outputs: HashMap::new(),
             copy: HashMap::new(),
             copy_current_loop_name: String::new(),
             dsc_version: None,
@@ -66,6 +69,7 @@ impl Context {
             processing_parameter_defaults: false,
             references: Map::new(),
             restart_required: None,
+            secrets: std::cell::RefCell::new(SecretCache::default()),
             security_context: match get_security_context() {
                 SecurityContext::Admin => SecurityContextKind::Elevated,
                 SecurityContext::User => SecurityContextKind::Restricted,
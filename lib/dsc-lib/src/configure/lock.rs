@@ -0,0 +1,170 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::discovery::discovery_trait::DiscoveryFilter;
+use crate::discovery::fix_semver;
+use crate::dscerror::DscError;
+use crate::dscresources::dscresource::DscResource;
+use crate::types::{FullyQualifiedTypeName, ResourceVersion, ResourceVersionReq};
+use crate::util::get_file_hash;
+use rust_i18n::t;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A lock file recording the exact resources that discovery selected for a configuration.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ConfigurationLock {
+    /// The resources selected for each distinct type, version and adapter requirement in the configuration.
+    pub resources: Vec<LockedResource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct LockedResource {
+    /// The type name of the resource as used in the configuration.
+    #[serde(rename = "type")]
+    pub resource_type: FullyQualifiedTypeName,
+    /// The version requirement specified in the configuration, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_version: Option<ResourceVersionReq>,
+    /// The adapter requirement specified in the configuration, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_adapter: Option<FullyQualifiedTypeName>,
+    /// The exact version of the resource selected by discovery.
+    pub version: ResourceVersion,
+    /// The path to the manifest of the selected resource.
+    pub path: PathBuf,
+    /// The SHA-256 hash of the manifest, absent when the path isn't a file such as for some adapted resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl LockedResource {
+    /// Create a new lock entry from a discovery filter and the resource it resolved to.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The discovery filter derived from the configuration.
+    /// * `resource` - The resource selected by discovery for the filter.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the manifest can't be read to compute the hash.
+    pub fn new(filter: &DiscoveryFilter, resource: &DscResource) -> Result<Self, DscError> {
+        Ok(Self {
+            resource_type: filter.resource_type().clone(),
+            require_version: filter.require_version().cloned(),
+            require_adapter: filter.require_adapter().cloned(),
+            version: resource.version.clone(),
+            path: resource.path.clone(),
+            hash: get_manifest_hash(&resource.path)?,
+        })
+    }
+
+    /// Check whether this entry was recorded for the specified discovery filter.
+    #[must_use]
+    pub fn matches_filter(&self, filter: &DiscoveryFilter) -> bool {
+        self.resource_type == *filter.resource_type()
+            && self.require_version.as_ref() == filter.require_version()
+            && self.require_adapter.as_ref() == filter.require_adapter()
+    }
+
+    /// Get the version requirement that only matches the locked version.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the locked version can't be converted to a requirement.
+    pub fn pinned_version(&self) -> Result<ResourceVersionReq, DscError> {
+        Ok(ResourceVersionReq::parse(&fix_semver(&self.version.to_string()))?)
+    }
+
+    /// Verify that the resource selected by discovery is the one recorded in the lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource selected by discovery.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Validation` if the version, manifest path, or manifest hash differ.
+    pub fn verify(&self, resource: &DscResource) -> Result<(), DscError> {
+        if resource.version != self.version {
+            return Err(DscError::Validation(t!("configure.lock.versionMismatch", resource = self.resource_type, locked = self.version, found = resource.version).to_string()));
+        }
+        if resource.path != self.path {
+            return Err(DscError::Validation(t!("configure.lock.pathMismatch", resource = self.resource_type, locked = self.path.display(), found = resource.path.display()).to_string()));
+        }
+        let hash = get_manifest_hash(&resource.path)?;
+        if hash != self.hash {
+            return Err(DscError::Validation(t!("configure.lock.hashMismatch", resource = self.resource_type, path = resource.path.display()).to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl ConfigurationLock {
+    /// Find the lock entry recorded for the specified discovery filter.
+    #[must_use]
+    pub fn find(&self, filter: &DiscoveryFilter) -> Option<&LockedResource> {
+        self.resources.iter().find(|locked| locked.matches_filter(filter))
+    }
+}
+
+/// Compute the SHA-256 hash of a manifest file.
+///
+/// # Arguments
+///
+/// * `path` - The path to the manifest.
+///
+/// # Returns
+///
+/// The hash as a `sha256:` prefixed lowercase hex string or `None` if the path isn't a file.
+///
+/// # Errors
+///
+/// This function will return an error if the file exists but can't be read.
+pub fn get_manifest_hash(path: &Path) -> Result<Option<String>, DscError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(get_file_hash(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn test_resource(version: &str, path: &Path) -> DscResource {
+        let mut resource = DscResource::new();
+        resource.type_name = FullyQualifiedTypeName::parse("Test/Lock").unwrap();
+        resource.version = ResourceVersion::parse(version).unwrap();
+        resource.path = path.to_path_buf();
+        resource
+    }
+
+    #[test]
+    fn test_manifest_hash() {
+        let path = std::env::temp_dir().join(format!("dsc_lock_test_{}.dsc.resource.json", std::process::id()));
+        fs::write(&path, "{}").unwrap();
+        let hash = get_manifest_hash(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(hash.as_deref(), Some("sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"));
+        assert_eq!(get_manifest_hash(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let filter = DiscoveryFilter::new(&FullyQualifiedTypeName::parse("Test/Lock").unwrap(), None, None);
+        let path = PathBuf::from("does_not_exist.dsc.resource.json");
+        let locked = LockedResource::new(&filter, &test_resource("1.0.0", &path)).unwrap();
+        assert!(locked.matches_filter(&filter));
+        assert!(locked.verify(&test_resource("1.0.0", &path)).is_ok());
+        assert!(locked.verify(&test_resource("1.1.0", &path)).is_err());
+        assert!(locked.verify(&test_resource("1.0.0", Path::new("other.dsc.resource.json"))).is_err());
+        assert!(locked.pinned_version().unwrap().matches(&ResourceVersion::parse("1.0.0").unwrap()));
+        assert!(!locked.pinned_version().unwrap().matches(&ResourceVersion::parse("1.0.1").unwrap()));
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::configure::config_doc::{ExecutionInformation, ResourceDirective};
+use crate::configure::config_doc::{ConfigurationOutput, ExecutionInformation, ResourceDirective};
 use crate::configure::context::{Context, ProcessMode};
 use crate::configure::parameters::{SecureObject, SecureString, import_parameters};
 use crate::configure::{config_doc::{ExecutionKind, IntOrExpression, Metadata, Parameter, Resource, ResourceDiscoveryMode, RestartRequired, ValueOrCopy}};
@@ -10,29 +10,41 @@ use crate::dscerror::DscError;
 use crate::dscresources::{
     {dscresource::{Capability, Invoke, get_diff, validate_properties, get_adapter_input_kind},
     invoke_result::{DeleteResult, DeleteResultKind, GetResult, SetResult, TestResult, ExportResult, ResourceSetResponse}},
+    resource_host::ResourceHostScope,
     resource_manifest::{AdapterInputKind, Kind},
 };
 use crate::DscResource;
+use crate::extensions::secret::SecretCache;
 use crate::discovery::Discovery;
 use crate::parser::Statement;
 use crate::progress::{Failure, ProgressBar, ProgressFormat};
-use crate::types::{FullyQualifiedTypeName, SemanticVersion};
+use crate::scrub::register_secure_value;
+use crate::types::{FullyQualifiedTypeName, ResourceVersionReq, SemanticVersion};
 use crate::util::resource_id;
+use self::config_progress::ConfigurationProgressListener;
 use self::config_doc::{Configuration, DataType, MicrosoftDscMetadata, Operation, SecurityContextKind};
 use self::depends_on::get_resource_invocation_order;
 use self::config_result::{ConfigurationExportResult, ConfigurationGetResult, ConfigurationSetResult, ConfigurationTestResult};
-use self::constraints::{check_length, check_number_limits, check_allowed_values};
+use self::constraints::{check_length, check_number_limits, check_allowed_values, check_schema};
+use self::lock::{ConfigurationLock, LockedResource};
+use self::module::expand_modules;
 use dsc_lib_security_context::{SecurityContext, get_security_context};
 use rust_i18n::t;
 use serde_json::{Map, Value};
+use std::cell::RefCell;
 use std::path::PathBuf;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{debug, info, trace, warn};
 pub mod context;
 pub mod config_doc;
+pub mod config_progress;
 pub mod config_result;
 pub mod constraints;
 pub mod depends_on;
+pub mod lock;
+pub mod module;
 pub mod parameters;
 
 pub struct Configurator {
@@ -42,6 +54,8 @@ pub struct Configurator {
     discovery: Discovery,
     statement_parser: Statement,
     progress_format: ProgressFormat,
+    progress_listener: Option<Arc<dyn ConfigurationProgressListener>>,
+    cancelled: Option<Arc<AtomicBool>>,
 }
 
 /// Invokes the [`Discovery::find_resource`] method to retrieve a specific resource or raise a
@@ -286,6 +300,22 @@ fn get_require_adapter_from_directive(resource_directives: &Option<ResourceDirec
     None
 }
 
+fn get_discovery_filter(resource: &Resource) -> DiscoveryFilter {
+    let adapter = get_require_adapter_from_directive(&resource.directives);
+    DiscoveryFilter::new(&resource.resource_type, resource.require_version.clone(), adapter)
+}
+
+fn pin_locked_resources(resources: &mut [Resource], locked: &LockedResource, pinned_version: &ResourceVersionReq) {
+    for resource in resources {
+        if locked.matches_filter(&get_discovery_filter(resource)) {
+            resource.require_version = Some(pinned_version.clone());
+        }
+        if let Some(nested) = &mut resource.resources {
+            pin_locked_resources(nested, locked, pinned_version);
+        }
+    }
+}
+
 fn check_security_context(metadata: Option<&Metadata>, directive_security_context: Option<&SecurityContextKind>) -> Result<(), DscError> {
     if metadata.is_none() && directive_security_context.is_none() {
         return Ok(());
@@ -330,6 +360,33 @@ fn check_security_context(metadata: Option<&Metadata>, directive_security_contex
     Ok(())
 }
 
+/// Move the typed outputs reported by the results of an included configuration to the metadata of
+/// the including resource and make them available to expressions by the resource name.
+fn take_configuration_outputs<'a>(context: &mut Context, name: &str, results: impl Iterator<Item = &'a mut Option<Metadata>>, metadata: &mut Metadata) -> Result<(), DscError> {
+    let mut outputs = None;
+    for result_metadata in results {
+        if let Some(microsoft) = result_metadata.as_mut().and_then(|result_metadata| result_metadata.microsoft.as_mut())
+            && let Some(result_outputs) = microsoft.outputs.take() {
+                outputs = Some(result_outputs);
+            }
+    }
+    let Some(outputs) = outputs else {
+        return Ok(());
+    };
+
+    let mut values = Map::new();
+    for (output_name, output) in &outputs {
+        validate_output_type(output_name, &output.value, &output.r#type)?;
+        values.insert(output_name.clone(), output.value.clone());
+    }
+    debug!("{}", t!("configure.mod.configurationOutputs", name = name));
+    context.configuration_outputs.insert(name.to_string(), values);
+    if let Some(microsoft) = metadata.microsoft.as_mut() {
+        microsoft.outputs = Some(outputs);
+    }
+    Ok(())
+}
+
 fn get_metadata_from_result(mut context: Option<&mut Context>, properties: &mut Value, metadata: &mut Metadata, execution_information: &mut ExecutionInformation) -> Result<(), DscError> {
     if let Some(restart_required) = properties.get("_restartRequired") {
         if let Ok(restart_required) = serde_json::from_value::<Vec<RestartRequired>>(restart_required.clone()) {
@@ -453,6 +510,8 @@ impl Configurator {
             discovery: discovery.clone(),
             statement_parser: Statement::new()?,
             progress_format,
+            progress_listener: None,
+            cancelled: None,
         };
         config.validate_config()?;
         for extension in discovery.extensions.values() {
@@ -480,6 +539,37 @@ impl Configurator {
         &mut self.discovery
     }
 
+    /// Set a listener notified as each resource instance starts and completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to notify.
+    pub fn set_progress_listener(&mut self, listener: Arc<dyn ConfigurationProgressListener>) {
+        self.progress_listener = Some(listener);
+    }
+
+    /// Set a flag that stops the operation after the current resource instance when set.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancelled` - The flag to check before each resource instance.
+    pub fn set_cancellation_flag(&mut self, cancelled: Arc<AtomicBool>) {
+        self.cancelled = Some(cancelled);
+    }
+
+    fn new_progress_bar(&self, total_items: u64) -> Result<ProgressBar, DscError> {
+        let mut progress = ProgressBar::new(total_items, self.progress_format)?;
+        progress.set_listener(self.progress_listener.clone());
+        Ok(progress)
+    }
+
+    fn check_cancelled(&self) -> Result<(), DscError> {
+        if self.cancelled.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::SeqCst)) {
+            return Err(DscError::Operation(t!("configure.mod.operationCancelled").to_string()));
+        }
+        Ok(())
+    }
+
     fn get_properties(&mut self, resource: &Resource, resource_kind: &Kind) -> Result<Option<Map<String, Value>>, DscError> {
         // Restore copy loop context from resource metadata under Microsoft.DSC/copyLoops if present
         if let Some(metadata) = &resource.metadata
@@ -520,12 +610,14 @@ impl Configurator {
     ///
     /// This function will return an error if the underlying resource fails.
     pub fn invoke_get(&mut self) -> Result<ConfigurationGetResult, DscError> {
+        let _host_scope = ResourceHostScope::enter();
         let mut result = ConfigurationGetResult::new();
         self.context.operation = Some(Operation::Get);
         let resources = get_resource_invocation_order(&self.config, &mut self.statement_parser, &mut self.context)?;
-        let mut progress = ProgressBar::new(resources.len() as u64, self.progress_format)?;
+        let mut progress = self.new_progress_bar(resources.len() as u64)?;
         let discovery = &mut self.discovery.clone();
         for resource in resources {
+            self.check_cancelled()?;
             let evaluated_name = self.evaluate_resource_name(&resource.name)?;
 
             progress.set_resource(&evaluated_name, &resource.resource_type);
@@ -564,6 +656,7 @@ impl Configurator {
                     get_metadata_from_result(Some(&mut self.context), &mut resource_result.actual_state, &mut metadata, &mut execution_information)?;
                 },
                 GetResult::Group(group) => {
+                    take_configuration_outputs(&mut self.context, &evaluated_name, group.iter_mut().map(|result| &mut result.metadata), &mut metadata)?;
                     let mut results = Vec::<Value>::new();
                     for result in group {
                         results.push(serde_json::to_value(&result.result)?);
@@ -611,12 +704,14 @@ impl Configurator {
     /// This function will return an error if the underlying resource fails.
     #[allow(clippy::too_many_lines)]
     pub fn invoke_set(&mut self, skip_test: bool) -> Result<ConfigurationSetResult, DscError> {
+        let _host_scope = ResourceHostScope::enter();
         let mut result = ConfigurationSetResult::new();
         self.context.operation = Some(Operation::Set);
         let resources = get_resource_invocation_order(&self.config, &mut self.statement_parser, &mut self.context)?;
-        let mut progress = ProgressBar::new(resources.len() as u64, self.progress_format)?;
+        let mut progress = self.new_progress_bar(resources.len() as u64)?;
         let discovery = &mut self.discovery.clone();
         for resource in resources {
+            self.check_cancelled()?;
             let evaluated_name = self.evaluate_resource_name(&resource.name)?;
 
             progress.set_resource(&evaluated_name, &resource.resource_type);
@@ -631,6 +726,10 @@ impl Configurator {
             find_resource_or_error!(dsc_resource, discovery, resource, adapter);
             let properties = self.get_properties(&resource, &dsc_resource.kind)?;
             debug!("resource_type {}", &resource.resource_type);
+            if resource.existing == Some(true) {
+                self.get_existing_resource(dsc_resource, &resource, properties, &evaluated_name, &mut progress)?;
+                continue;
+            }
             // see if the properties contains `_exist` and is false
             let exist = match &properties {
                 Some(property_map) => {
@@ -757,6 +856,7 @@ impl Configurator {
                     get_metadata_from_result(Some(&mut self.context), &mut resource_result.after_state, &mut metadata, &mut execution_information)?;
                 },
                 SetResult::Group(group) => {
+                    take_configuration_outputs(&mut self.context, &evaluated_name, group.iter_mut().map(|result| &mut result.metadata), &mut metadata)?;
                     let mut results = Vec::<Value>::new();
                     for result in group {
                         results.push(serde_json::to_value(&result.result)?);
@@ -800,12 +900,14 @@ impl Configurator {
     ///
     /// This function will return an error if the underlying resource fails.
     pub fn invoke_test(&mut self) -> Result<ConfigurationTestResult, DscError> {
+        let _host_scope = ResourceHostScope::enter();
         let mut result = ConfigurationTestResult::new();
         self.context.operation = Some(Operation::Test);
         let resources = get_resource_invocation_order(&self.config, &mut self.statement_parser, &mut self.context)?;
-        let mut progress = ProgressBar::new(resources.len() as u64, self.progress_format)?;
+        let mut progress = self.new_progress_bar(resources.len() as u64)?;
         let discovery = &mut self.discovery.clone();
         for resource in resources {
+            self.check_cancelled()?;
             let evaluated_name = self.evaluate_resource_name(&resource.name)?;
 
             progress.set_resource(&evaluated_name, &resource.resource_type);
@@ -820,6 +922,10 @@ impl Configurator {
             find_resource_or_error!(dsc_resource, discovery, resource, adapter);
             let properties = self.get_properties(&resource, &dsc_resource.kind)?;
             debug!("resource_type {}", &resource.resource_type);
+            if resource.existing == Some(true) {
+                self.get_existing_resource(dsc_resource, &resource, properties, &evaluated_name, &mut progress)?;
+                continue;
+            }
             let expected = add_metadata(dsc_resource, properties, resource.metadata.clone())?;
             trace!("{}", t!("configure.mod.expectedState", state = expected));
             let start_datetime = chrono::Local::now();
@@ -845,6 +951,7 @@ impl Configurator {
                     get_metadata_from_result(Some(&mut self.context), &mut resource_test_result.actual_state, &mut metadata, &mut execution_information)?;
                 },
                 TestResult::Group(group) => {
+                    take_configuration_outputs(&mut self.context, &evaluated_name, group.iter_mut().map(|result| &mut result.metadata), &mut metadata)?;
                     let mut results = Vec::<Value>::new();
                     for result in group {
                         results.push(serde_json::to_value(&result.result)?);
@@ -887,15 +994,17 @@ impl Configurator {
     ///
     /// This function will return an error if the underlying resource fails.
     pub fn invoke_export(&mut self) -> Result<ConfigurationExportResult, DscError> {
+        let _host_scope = ResourceHostScope::enter();
         let mut result = ConfigurationExportResult::new();
         self.context.operation = Some(Operation::Export);
         let mut conf = config_doc::Configuration::new();
         conf.metadata.clone_from(&self.config.metadata);
 
-        let mut progress = ProgressBar::new(self.config.resources.len() as u64, self.progress_format)?;
+        let mut progress = self.new_progress_bar(self.config.resources.len() as u64)?;
         let resources = self.config.resources.clone();
         let discovery = &mut self.discovery.clone();
         for resource in &resources {
+            self.check_cancelled()?;
             let evaluated_name = self.evaluate_resource_name(&resource.name)?;
 
             progress.set_resource(&evaluated_name, &resource.resource_type);
@@ -910,6 +1019,10 @@ impl Configurator {
             find_resource_or_error!(dsc_resource, discovery, resource, adapter);
             let properties = self.get_properties(resource, &dsc_resource.kind)?;
             debug!("resource_type {}", &resource.resource_type);
+            if resource.existing == Some(true) {
+                self.get_existing_resource(dsc_resource, resource, properties, &evaluated_name, &mut progress)?;
+                continue;
+            }
             let input = add_metadata(dsc_resource, properties, resource.metadata.clone())?;
             trace!("{}", t!("configure.mod.exportInput", input = input));
             let export_result = match add_resource_export_results_to_configuration(dsc_resource, &mut conf, input.as_str()) {
@@ -947,6 +1060,31 @@ impl Configurator {
         Ok(result)
     }
 
+    /// Get the current state of a resource marked as `existing` and make it available to `reference()`.
+    ///
+    /// An existing resource is only read so set, test, and export perform get for it instead and
+    /// don't include it in their results.  The result or failure is reported to the progress bar.
+    fn get_existing_resource(&mut self, dsc_resource: &DscResource, resource: &Resource, properties: Option<Map<String, Value>>, name: &str, progress: &mut ProgressBar) -> Result<(), DscError> {
+        info!("{}", t!("configure.mod.existingResource", name = name));
+        let filter = add_metadata(dsc_resource, properties, resource.metadata.clone())?;
+        let get_result = match dsc_resource.get(&filter) {
+            Ok(result) => result,
+            Err(e) => {
+                progress.set_failure(get_failure_from_error(&e));
+                progress.write_increment(1);
+                return Err(e);
+            },
+        };
+        let reference = match &get_result {
+            GetResult::Resource(resource_result) => serde_json::to_value(&resource_result.actual_state)?,
+            GetResult::Group(group) => Value::Array(group.iter().map(|result| serde_json::to_value(&result.result)).collect::<Result<_, _>>()?),
+        };
+        self.context.references.insert(resource_id(&resource.resource_type, name), reference);
+        progress.set_result(&serde_json::to_value(get_result)?);
+        progress.write_increment(1);
+        Ok(())
+    }
+
     fn skip_resource(&mut self, resource: &Resource) -> Result<bool, DscError> {
         if let Some(condition) = &resource.condition {
             let condition_result = self.statement_parser.parse_and_execute(condition, &self.context)?;
@@ -984,13 +1122,7 @@ impl Configurator {
                         continue;
                     }
                     // TODO: handle nullable when supported
-                    if value_result.is_string() && output.r#type != DataType::String ||
-                        value_result.is_i64() && output.r#type != DataType::Int ||
-                        value_result.is_boolean() && output.r#type != DataType::Bool ||
-                        value_result.is_array() && output.r#type != DataType::Array ||
-                        value_result.is_object() && output.r#type != DataType::Object {
-                            return Err(DscError::Validation(t!("configure.mod.outputTypeNotMatch", name = name, expected_type = output.r#type).to_string()));
-                    }
+                    validate_output_type(name, &value_result, &output.r#type)?;
                     self.context.outputs.insert(name.clone(), value_result);
                 } else {
                     warn!("{}", t!("configure.mod.copyNotSupported", name = name));
@@ -1000,6 +1132,27 @@ impl Configurator {
         Ok(())
     }
 
+    /// Get the processed outputs of the configuration along with their declared types.
+    ///
+    /// # Returns
+    ///
+    /// The typed outputs or `None` if the configuration has no processed outputs.
+    #[must_use]
+    pub fn get_typed_outputs(&self) -> Option<HashMap<String, ConfigurationOutput>> {
+        let declared = self.config.outputs.as_ref()?;
+        let outputs: HashMap<String, ConfigurationOutput> = self.context.outputs.iter()
+            .filter_map(|(name, value)| declared.get(name).map(|output| (name.clone(), ConfigurationOutput {
+                r#type: output.r#type.clone(),
+                value: value.clone(),
+            })))
+            .collect();
+        if outputs.is_empty() {
+            None
+        } else {
+            Some(outputs)
+        }
+    }
+
     /// Set the mounted path for the configuration.
     ///
     /// # Arguments
@@ -1022,6 +1175,8 @@ impl Configurator {
         let config = serde_json::from_str::<Configuration>(self.json.as_str())?;
 
         self.context.extensions = self.discovery.extensions.values().cloned().collect();
+        let secret_directive = config.directives.as_ref().and_then(|directives| directives.secrets.as_ref());
+        self.context.secrets = RefCell::new(SecretCache::new(secret_directive));
         self.set_parameters(parameters_input, &config)?;
         self.set_variables(&config)?;
         self.set_user_functions(&config)?;
@@ -1039,15 +1194,19 @@ impl Configurator {
 
         // process input parameters first
         if let Some(parameters_input) = parameters_input {
-            let input_parameters: HashMap<String, Value> = import_parameters(parameters_input)?;
+            let input_parameters: HashMap<String, Value> = import_parameters(parameters_input, &self.context)?;
             for (name, value) in input_parameters {
                 if let Some(constraint) = parameters.get(&name) {
                     debug!("Validating parameter '{name}'");
-                    check_length(&name, &value, constraint)?;
-                    check_allowed_values(&name, &value, constraint)?;
-                    check_number_limits(&name, &value, constraint)?;
-                    // TODO: additional array constraints
-                    // TODO: object constraints
+                    // a secret resolved from a reference is validated using its value
+                    let constraint_value = match serde_json::from_value::<SecureString>(value.clone()) {
+                        Ok(secure_string) => Value::String(secure_string.secure_string),
+                        Err(_) => value.clone(),
+                    };
+                    check_length(&name, &constraint_value, constraint)?;
+                    check_allowed_values(&name, &constraint_value, constraint)?;
+                    check_number_limits(&name, &constraint_value, constraint)?;
+                    check_schema(&name, &constraint_value, constraint)?;
 
                     let value = match &constraint.parameter_type {
                         DataType::SecureString => {
@@ -1056,6 +1215,9 @@ impl Configurator {
                                     secure_string: string_value.to_string(),
                                 };
                                 serde_json::to_value(secure_string)?
+                            } else if serde_json::from_value::<SecureString>(value.clone()).is_ok() {
+                                // resolved from a secret reference
+                                value.clone()
                             } else {
                                 return Err(DscError::Validation(t!("configure.mod.secureStringMustBeString", name = name).to_string()));
                             }
@@ -1079,6 +1241,9 @@ impl Configurator {
                         info!("{}", t!("configure.mod.setParameter", name = name, value = value));
                     }
 
+                    if constraint.parameter_type == DataType::SecureString || constraint.parameter_type == DataType::SecureObject {
+                        register_secure_value(&value);
+                    }
                     self.context.parameters.insert(name.clone(), (value.clone(), constraint.parameter_type.clone()));
                     if let Some(parameters) = &mut self.config.parameters
                         && let Some(parameter) = parameters.get_mut(&name) {
@@ -1133,7 +1298,11 @@ impl Configurator {
                     check_length(name, &value, parameter)?;
                     check_allowed_values(name, &value, parameter)?;
                     check_number_limits(name, &value, parameter)?;
+                    check_schema(name, &value, parameter)?;
                     validate_parameter_type(name, &value, &parameter.parameter_type)?;
+                    if parameter.parameter_type == DataType::SecureString || parameter.parameter_type == DataType::SecureObject {
+                        register_secure_value(&value);
+                    }
                     self.context.parameters.insert(name.to_string(), (value, parameter.parameter_type.clone()));
                     resolved_in_this_pass.push(name.clone());
                 } else {
@@ -1204,6 +1373,7 @@ impl Configurator {
                     end_datetime: Some(end_datetime.to_rfc3339()),
                     execution_type: Some(self.context.execution_type.clone()),
                     operation: Some(operation),
+                    outputs: None,
                     restart_required: self.context.restart_required.clone(),
                     security_context: Some(self.context.security_context.clone()),
                     start_datetime: Some(self.context.start_datetime.to_rfc3339()),
@@ -1225,10 +1395,100 @@ impl Configurator {
         execution_information.operation = Some(operation);
         execution_information.restart_required = self.context.restart_required.clone();
         execution_information.security_context = Some(self.context.security_context.clone());
+        let secret_metrics = self.context.secrets.borrow().metrics().clone();
+        if secret_metrics.requests > 0 {
+            execution_information.secrets = Some(secret_metrics);
+        }
+    }
+
+    /// Create a lock recording the exact resources selected by discovery for the configuration.
+    ///
+    /// Nested resources and the resources of group resources are included so child configurations
+    /// can be verified against the same lock.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a resource can't be found or its manifest can't be read.
+    pub fn create_lock(&mut self) -> Result<ConfigurationLock, DscError> {
+        let mut lock = ConfigurationLock::default();
+        for filter in self.get_discovery_filters()? {
+            let Some(dsc_resource) = self.discovery.find_resource(&filter)? else {
+                return Err(DscError::ResourceNotFound(
+                    filter.resource_type().to_string(),
+                    filter.require_version().map(|r| r.to_string()).unwrap_or_default()
+                ));
+            };
+            lock.resources.push(LockedResource::new(&filter, dsc_resource)?);
+        }
+        Ok(lock)
+    }
+
+    /// Verify the resources selected by discovery against a lock and pin each resource to its locked version.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock` - The lock to apply.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Validation` if a resource isn't in the lock or differs from it.
+    pub fn apply_lock(&mut self, lock: &ConfigurationLock) -> Result<(), DscError> {
+        for filter in self.get_discovery_filters()? {
+            let Some(locked) = lock.find(&filter) else {
+                return Err(DscError::Validation(t!("configure.lock.resourceNotLocked", resource = filter.resource_type()).to_string()));
+            };
+            let Some(dsc_resource) = self.discovery.find_resource(&filter)? else {
+                return Err(DscError::ResourceNotFound(
+                    filter.resource_type().to_string(),
+                    filter.require_version().map(|r| r.to_string()).unwrap_or_default()
+                ));
+            };
+            locked.verify(dsc_resource)?;
+            debug!("{}", t!("configure.lock.pinningResource", resource = filter.resource_type(), version = &locked.version));
+            pin_locked_resources(&mut self.config.resources, locked, &locked.pinned_version()?);
+        }
+        Ok(())
+    }
+
+    /// Get the discovery filters for all resources in the configuration, including nested resources
+    /// and the resources of group resources.
+    fn get_discovery_filters(&mut self) -> Result<Vec<DiscoveryFilter>, DscError> {
+        let mut discovery_filter: Vec<DiscoveryFilter> = Vec::new();
+        let mut pending = self.config.resources.clone();
+        while !pending.is_empty() {
+            let mut level_filter: Vec<DiscoveryFilter> = Vec::new();
+            let mut nested: Vec<Resource> = Vec::new();
+            for resource in &pending {
+                let filter = get_discovery_filter(resource);
+                if !discovery_filter.contains(&filter) {
+                    discovery_filter.push(filter.clone());
+                    level_filter.push(filter);
+                }
+                if let Some(resources) = &resource.resources {
+                    nested.extend(resources.iter().cloned());
+                }
+            }
+
+            // group resources are only known after discovery, so discover each level before inspecting it
+            self.discovery.find_additional_resources(&level_filter, self.progress_format)?;
+            for resource in &pending {
+                let Some(resources) = resource.properties.as_ref().and_then(|properties| properties.get("resources")) else {
+                    continue;
+                };
+                let is_group = self.discovery.find_resource(&get_discovery_filter(resource))?
+                    .is_some_and(|dsc_resource| dsc_resource.kind == Kind::Group);
+                if is_group {
+                    nested.extend(serde_json::from_value::<Vec<Resource>>(resources.clone())?);
+                }
+            }
+            pending = nested;
+        }
+        Ok(discovery_filter)
     }
 
     fn validate_config(&mut self) -> Result<(), DscError> {
-        let config: Configuration = serde_json::from_str(self.json.as_str())?;
+        let mut config: Configuration = serde_json::from_str(self.json.as_str())?;
+        expand_modules(&mut config)?;
         let config_security_context = if let Some(directives) = &config.directives {
             directives.security_context.clone()
         } else {
@@ -1394,6 +1654,34 @@ pub fn invoke_property_expressions(parser: &mut Statement, context: &Context, pr
 }
 
 
+/// Validate that the value of an output matches its declared type.
+///
+/// # Arguments
+///
+/// * `name` - The name of the output.
+/// * `value` - The value of the output.
+/// * `output_type` - The declared type of the output.
+///
+/// # Errors
+///
+/// This function will return an error if the value doesn't match the type.
+pub fn validate_output_type(name: &str, value: &Value, output_type: &DataType) -> Result<(), DscError> {
+    // TODO: handle nullable when supported, until then null values aren't validated
+    let matches = match value {
+        Value::String(_) => matches!(output_type, DataType::String | DataType::SecureString),
+        Value::Bool(_) => *output_type == DataType::Bool,
+        Value::Array(_) => *output_type == DataType::Array,
+        Value::Object(_) => matches!(output_type, DataType::Object | DataType::SecureObject),
+        Value::Number(_) if value.is_i64() => *output_type == DataType::Int,
+        _ => true,
+    };
+    if !matches {
+        return Err(DscError::Validation(t!("configure.mod.outputTypeNotMatch", name = name, expected_type = output_type).to_string()));
+    }
+
+    Ok(())
+}
+
 /// Validate that a parameter value matches the expected type.
 ///
 /// # Arguments
@@ -0,0 +1,570 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::configure::config_doc::{Configuration, DataType, Resource, ResourceKind, ValueOrCopy};
+use crate::dscerror::DscError;
+use regex::{Captures, Regex};
+use rust_i18n::t;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{debug, info};
+
+/// The resource type used to reference a module with `resourceId()` in `dependsOn`.
+pub const MODULE_RESOURCE_TYPE: &str = "Microsoft.DSC/Module";
+
+/// The environment variable for the directory that relative module sources are resolved against.
+const DSC_CONFIG_ROOT: &str = "DSC_CONFIG_ROOT";
+
+/// Matches the lookups a module template or its caller uses that need to be rewritten on expansion.
+static LOOKUP_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Matches an expression that is only a single `parameters()` or `variables()` lookup.
+static WHOLE_LOOKUP_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Matches a `dependsOn` entry that refers to a module.
+static MODULE_DEPENDENCY_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// The properties of a resource instance with the `module` kind.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ModuleProperties {
+    /// The path of the configuration template, relative paths are resolved against the directory of
+    /// the including document.
+    pub source: String,
+    /// The values for the parameters declared by the template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Map<String, Value>>,
+}
+
+/// A lookup in an expression that may need to be rewritten when expanding a module.
+enum Lookup<'a> {
+    /// A `parameters()`, `variables()`, or `outputs()` lookup with the function and the name.
+    Value(&'a str, String),
+    /// A `resourceId()` lookup with the type and the name.
+    ResourceId(String, String),
+}
+
+/// The result of expanding a module, used to rewrite references to the module in its caller.
+struct ExpandedModule {
+    /// The `resourceId()` lookups for the resources the module expanded into.
+    resource_ids: Vec<String>,
+    /// The outputs of the module as expression text.
+    outputs: Vec<(String, String)>,
+}
+
+/// Expand the resource instances of kind `module` in a configuration into the resources of their
+/// templates.
+///
+/// The resources of each template are renamed to `<module>/<name>`, `parameters()` and
+/// `variables()` lookups in them are replaced with the values for the module, and their
+/// `resourceId()` lookups for each other are rewritten to the new names. A `dependsOn` entry for
+/// `[resourceId('Microsoft.DSC/Module', '<module>')]` is rewritten to depend on every resource of
+/// the module and `outputs('<module>')` returns the outputs declared by the template.
+///
+/// # Arguments
+///
+/// * `config` - The configuration to expand the modules of.
+///
+/// # Errors
+///
+/// This function will return an error if a template can't be read, is included recursively, or
+/// the module doesn't provide valid values for the template parameters.
+pub fn expand_modules(config: &mut Configuration) -> Result<(), DscError> {
+    if !config.resources.iter().any(|resource| resource.kind == Some(ResourceKind::Module)) {
+        return config.resources.iter().try_for_each(validate_resource_type);
+    }
+
+    let base_path = match std::env::var(DSC_CONFIG_ROOT) {
+        Ok(root) => PathBuf::from(root),
+        Err(_) => std::env::current_dir()?,
+    };
+    expand_configuration(config, &base_path, &mut Vec::new())
+}
+
+fn validate_resource_type(resource: &Resource) -> Result<(), DscError> {
+    if resource.resource_type.is_empty() {
+        return Err(DscError::Validation(t!("configure.module.missingType", name = resource.name).to_string()));
+    }
+    Ok(())
+}
+
+fn expand_configuration(config: &mut Configuration, base_path: &Path, stack: &mut Vec<PathBuf>) -> Result<(), DscError> {
+    let mut modules = HashMap::<String, ExpandedModule>::new();
+    let mut resources = Vec::<Resource>::new();
+    for resource in config.resources.drain(..) {
+        if resource.kind != Some(ResourceKind::Module) {
+            validate_resource_type(&resource)?;
+            resources.push(resource);
+            continue;
+        }
+
+        if modules.contains_key(&resource.name) {
+            return Err(DscError::Validation(t!("configure.module.duplicateModule", name = resource.name).to_string()));
+        }
+        let (children, expanded) = expand_module(&resource, base_path, stack)?;
+        resources.extend(children);
+        modules.insert(resource.name, expanded);
+    }
+    if modules.is_empty() {
+        config.resources = resources;
+        return Ok(());
+    }
+
+    for resource in &mut resources {
+        if let Some(depends_on) = resource.depends_on.take() {
+            resource.depends_on = Some(expand_module_dependencies(depends_on, &modules));
+        }
+    }
+
+    let replace_outputs = |lookup: &Lookup| -> Option<String> {
+        let Lookup::Value("outputs", name) = lookup else {
+            return None;
+        };
+        let module = modules.get(name)?;
+        if module.outputs.is_empty() {
+            return Some("createObject()".to_string());
+        }
+        let members = module.outputs.iter().map(|(name, value)| format!("{}, {value}", quote(name))).collect::<Vec<_>>();
+        Some(format!("createObject({})", members.join(", ")))
+    };
+    resources = resources.into_iter().map(|resource| rewrite_resource(resource, &replace_outputs, &HashMap::new())).collect::<Result<_, _>>()?;
+    if let Some(outputs) = &mut config.outputs {
+        for output in outputs.values_mut() {
+            if let Some(condition) = &mut output.condition {
+                *condition = rewrite_expression(condition, &replace_outputs);
+            }
+            if let ValueOrCopy::Value(value) = &mut output.value_or_copy {
+                *value = rewrite_expression(value, &replace_outputs);
+            }
+        }
+    }
+
+    config.resources = resources;
+    Ok(())
+}
+
+fn expand_module(module: &Resource, base_path: &Path, stack: &mut Vec<PathBuf>) -> Result<(Vec<Resource>, ExpandedModule), DscError> {
+    if module.name.is_empty() || module.name.starts_with('[') {
+        return Err(DscError::Validation(t!("configure.module.invalidName", name = module.name).to_string()));
+    }
+    if module.copy.is_some() || module.resources.is_some() || module.directives.is_some() || module.require_version.is_some() || module.execution_information.is_some() || module.existing.is_some() || !module.resource_type.is_empty() {
+        return Err(DscError::Validation(t!("configure.module.unsupportedProperty", name = module.name).to_string()));
+    }
+    let Some(properties) = &module.properties else {
+        return Err(DscError::Validation(t!("configure.module.missingSource", name = module.name).to_string()));
+    };
+    let properties: ModuleProperties = serde_json::from_value(Value::Object(properties.clone()))
+        .map_err(|err| DscError::Validation(t!("configure.module.invalidProperties", name = module.name, error = err.to_string()).to_string()))?;
+
+    let source = Path::new(&properties.source);
+    // joining an absolute path would replace the base path
+    if source.is_absolute() || source.components().any(|component| matches!(component, Component::ParentDir | Component::RootDir | Component::Prefix(_))) {
+        return Err(DscError::Validation(t!("configure.module.invalidSource", name = module.name, source = properties.source).to_string()));
+    }
+    let template_path = base_path.join(source);
+    let canonical_path = template_path.canonicalize()
+        .map_err(|err| DscError::Validation(t!("configure.module.failedReadTemplate", name = module.name, path = template_path.to_string_lossy(), error = err.to_string()).to_string()))?;
+    if stack.contains(&canonical_path) {
+        return Err(DscError::Validation(t!("configure.module.recursiveModule", name = module.name, path = canonical_path.to_string_lossy()).to_string()));
+    }
+
+    info!("{}", t!("configure.module.expandingModule", name = module.name, path = canonical_path.to_string_lossy()));
+    let content = std::fs::read_to_string(&canonical_path)
+        .map_err(|err| DscError::Validation(t!("configure.module.failedReadTemplate", name = module.name, path = canonical_path.to_string_lossy(), error = err.to_string()).to_string()))?;
+    let mut template: Configuration = serde_yaml::from_str(&content)
+        .map_err(|err| DscError::Validation(t!("configure.module.invalidTemplate", path = canonical_path.to_string_lossy(), error = err.to_string()).to_string()))?;
+    if template.functions.is_some() {
+        return Err(DscError::Validation(t!("configure.module.functionsNotSupported", path = canonical_path.to_string_lossy()).to_string()));
+    }
+
+    let template_directory = canonical_path.parent().unwrap_or(base_path).to_path_buf();
+    stack.push(canonical_path);
+    let result = expand_configuration(&mut template, &template_directory, stack);
+    stack.pop();
+    result?;
+
+    instantiate_template(module, &properties, template)
+}
+
+fn instantiate_template(module: &Resource, properties: &ModuleProperties, template: Configuration) -> Result<(Vec<Resource>, ExpandedModule), DscError> {
+    let values = get_parameter_values(module, properties, &template)?;
+    let mut variables = HashMap::<String, Value>::new();
+    if let Some(template_variables) = &template.variables {
+        let replace_parameters = |lookup: &Lookup| lookup_value(lookup, "parameters", &values);
+        for (name, value) in template_variables {
+            let mut value = value.clone();
+            rewrite_value(&mut value, &replace_parameters, &values_by_kind(&values, &HashMap::new()));
+            variables.insert(name.clone(), value);
+        }
+    }
+
+    let local_names = template.resources.iter().map(|resource| resource.name.clone()).collect::<HashSet<_>>();
+    let replace = |lookup: &Lookup| -> Option<String> {
+        match lookup {
+            Lookup::Value("parameters", _) => lookup_value(lookup, "parameters", &values),
+            Lookup::Value("variables", _) => lookup_value(lookup, "variables", &variables),
+            Lookup::Value(_, _) => None,
+            Lookup::ResourceId(type_name, name) => local_names.contains(name)
+                .then(|| format!("resourceId({}, {})", quote(type_name), quote(&format!("{}/{name}", module.name)))),
+        }
+    };
+    let whole_values = values_by_kind(&values, &variables);
+
+    let mut children = Vec::<Resource>::new();
+    let mut resource_ids = Vec::<String>::new();
+    for resource in template.resources {
+        let mut child = rewrite_resource(resource, &replace, &whole_values)?;
+        child.name = if child.name.starts_with("[[") {
+            format!("{}/{}", module.name, &child.name[1..])
+        } else if child.name.starts_with('[') {
+            format!("[concat({}, {})]", quote(&format!("{}/", module.name)), expression_text(&Value::String(child.name)))
+        } else {
+            format!("{}/{}", module.name, child.name)
+        };
+        if let Some(module_depends_on) = &module.depends_on {
+            let mut depends_on = module_depends_on.clone();
+            for dependency in child.depends_on.take().unwrap_or_default() {
+                if !depends_on.contains(&dependency) {
+                    depends_on.push(dependency);
+                }
+            }
+            child.depends_on = Some(depends_on);
+        }
+        child.condition = match (&module.condition, child.condition.take()) {
+            (Some(module_condition), Some(condition)) => Some(format!("[and({}, {})]", expression_text(&Value::String(module_condition.clone())), expression_text(&Value::String(condition)))),
+            (Some(module_condition), None) => Some(module_condition.clone()),
+            (None, condition) => condition,
+        };
+        debug!("{}", t!("configure.module.expandedResource", name = child.name, module = module.name));
+        resource_ids.push(format!("[resourceId({}, {})]", quote(&child.resource_type), expression_text(&Value::String(child.name.clone()))));
+        children.push(child);
+    }
+
+    let mut outputs = Vec::<(String, String)>::new();
+    let mut template_outputs = template.outputs.unwrap_or_default().into_iter().collect::<Vec<_>>();
+    template_outputs.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, output) in template_outputs {
+        let ValueOrCopy::Value(value) = &output.value_or_copy else {
+            return Err(DscError::Validation(t!("configure.module.copyOutputNotSupported", name = name, module = module.name).to_string()));
+        };
+        if output.r#type == DataType::SecureString || output.r#type == DataType::SecureObject {
+            continue;
+        }
+        let mut value = Value::String(value.clone());
+        rewrite_value(&mut value, &replace, &whole_values);
+        let mut text = expression_text(&value);
+        if let Some(condition) = &output.condition {
+            let mut condition = Value::String(condition.clone());
+            rewrite_value(&mut condition, &replace, &whole_values);
+            text = format!("if({}, {text}, null())", expression_text(&condition));
+        }
+        outputs.push((name, text));
+    }
+
+    Ok((children, ExpandedModule { resource_ids, outputs }))
+}
+
+fn get_parameter_values(module: &Resource, properties: &ModuleProperties, template: &Configuration) -> Result<HashMap<String, Value>, DscError> {
+    let declared = template.parameters.clone().unwrap_or_default();
+    let provided = properties.parameters.clone().unwrap_or_default();
+    if let Some(name) = provided.keys().find(|name| !declared.contains_key(*name)) {
+        return Err(DscError::Validation(t!("configure.module.unknownParameter", name = name, module = module.name).to_string()));
+    }
+
+    let mut values = provided.into_iter().collect::<HashMap<_, _>>();
+    let provided_values = values.clone();
+    let no_variables = HashMap::new();
+    let replace_parameters = |lookup: &Lookup| lookup_value(lookup, "parameters", &provided_values);
+    let whole_values = values_by_kind(&provided_values, &no_variables);
+    for (name, parameter) in declared {
+        if values.contains_key(&name) {
+            continue;
+        }
+        let Some(mut default_value) = parameter.default_value else {
+            return Err(DscError::Validation(t!("configure.module.missingParameter", name = name, module = module.name).to_string()));
+        };
+        rewrite_value(&mut default_value, &replace_parameters, &whole_values);
+        values.insert(name, default_value);
+    }
+    Ok(values)
+}
+
+fn values_by_kind<'a>(parameters: &'a HashMap<String, Value>, variables: &'a HashMap<String, Value>) -> HashMap<&'static str, &'a HashMap<String, Value>> {
+    HashMap::from([("parameters", parameters), ("variables", variables)])
+}
+
+fn lookup_value(lookup: &Lookup, kind: &str, values: &HashMap<String, Value>) -> Option<String> {
+    match lookup {
+        Lookup::Value(lookup_kind, name) if *lookup_kind == kind => values.get(name).map(expression_text),
+        _ => None,
+    }
+}
+
+fn expand_module_dependencies(depends_on: Vec<String>, modules: &HashMap<String, ExpandedModule>) -> Vec<String> {
+    let regex = MODULE_DEPENDENCY_REGEX.get_or_init(|| {
+        Regex::new(&format!(r"^\[\s*resourceId\(\s*'{}'\s*,\s*'((?:[^']|'')*)'\s*\)\s*\]$", regex::escape(MODULE_RESOURCE_TYPE))).unwrap()
+    });
+    let mut expanded = Vec::<String>::new();
+    for dependency in depends_on {
+        let module = regex.captures(&dependency).and_then(|captures| modules.get(&unescape(&captures[1])));
+        let ids = match module {
+            Some(module) => module.resource_ids.clone(),
+            None => vec![dependency],
+        };
+        for id in ids {
+            if !expanded.contains(&id) {
+                expanded.push(id);
+            }
+        }
+    }
+    expanded
+}
+
+fn rewrite_resource(resource: Resource, replace: &dyn Fn(&Lookup) -> Option<String>, whole_values: &HashMap<&'static str, &HashMap<String, Value>>) -> Result<Resource, DscError> {
+    let mut value = serde_json::to_value(resource)?;
+    rewrite_value(&mut value, replace, whole_values);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Rewrite the lookups in every expression within a value.
+///
+/// A string that is only a `parameters()` or `variables()` lookup is replaced with the value itself
+/// so the type of the value is kept, other lookups are replaced within the expression text.
+fn rewrite_value(value: &mut Value, replace: &dyn Fn(&Lookup) -> Option<String>, whole_values: &HashMap<&'static str, &HashMap<String, Value>>) {
+    match value {
+        Value::String(text) => {
+            let whole_regex = WHOLE_LOOKUP_REGEX.get_or_init(|| Regex::new(r"^\[\s*(parameters|variables)\(\s*'((?:[^']|'')*)'\s*\)\s*\]$").unwrap());
+            if let Some(captures) = whole_regex.captures(text)
+                && let Some(replacement) = whole_values.get(&captures[1]).and_then(|values| values.get(&unescape(&captures[2]))) {
+                    *value = replacement.clone();
+                    return;
+                }
+            *text = rewrite_expression(text, replace);
+        },
+        Value::Array(items) => {
+            for item in items {
+                rewrite_value(item, replace, whole_values);
+            }
+        },
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                rewrite_value(item, replace, whole_values);
+            }
+        },
+        _ => {}
+    }
+}
+
+fn rewrite_expression(text: &str, replace: &dyn Fn(&Lookup) -> Option<String>) -> String {
+    if !text.starts_with('[') || text.starts_with("[[") {
+        return text.to_string();
+    }
+    // a single pass so a replacement is never rewritten again
+    let regex = LOOKUP_REGEX.get_or_init(|| {
+        Regex::new(r"\b(?:(parameters|variables|outputs)\(\s*'((?:[^']|'')*)'\s*\)|resourceId\(\s*'((?:[^']|'')*)'\s*,\s*'((?:[^']|'')*)'\s*\))").unwrap()
+    });
+    regex.replace_all(text, |captures: &Captures| {
+        let lookup = match captures.get(1) {
+            Some(kind) => Lookup::Value(kind.as_str(), unescape(&captures[2])),
+            None => Lookup::ResourceId(unescape(&captures[3]), unescape(&captures[4])),
+        };
+        replace(&lookup).unwrap_or_else(|| captures[0].to_string())
+    }).into_owned()
+}
+
+/// Convert a value to the text of an expression argument that evaluates to the value.
+fn expression_text(value: &Value) -> String {
+    match value {
+        Value::String(text) if text.starts_with("[[") => quote(&text[1..]),
+        Value::String(text) if text.starts_with('[') && text.ends_with(']') => text[1..text.len() - 1].trim().to_string(),
+        Value::String(text) => quote(text),
+        Value::Number(number) if number.is_i64() => number.to_string(),
+        Value::Bool(boolean) => boolean.to_string(),
+        Value::Null => "null()".to_string(),
+        _ => format!("json({})", quote(&value.to_string())),
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("''", "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const TEMPLATE: &str = r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+parameters:
+  port:
+    type: int
+  greeting:
+    type: string
+    defaultValue: hello
+variables:
+  message: "[concat(parameters('greeting'), ' world')]"
+resources:
+- name: first
+  type: Test/Echo
+  properties:
+    output: "[parameters('port')]"
+    text: "[variables('message')]"
+- name: second
+  type: Test/Echo
+  dependsOn:
+  - "[resourceId('Test/Echo', 'first')]"
+  properties:
+    output: "[reference(resourceId('Test/Echo', 'first')).actualState.output]"
+outputs:
+  port:
+    type: int
+    value: "[parameters('port')]"
+"#;
+
+    fn expand(name: &str, config_yaml: &str) -> Result<Configuration, DscError> {
+        let path = std::env::temp_dir().join(format!("dsc_module_test_{}_{name}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("template.dsc.yaml"), TEMPLATE).unwrap();
+        let mut config: Configuration = serde_yaml::from_str(config_yaml).unwrap();
+        let result = expand_configuration(&mut config, &path, &mut Vec::new());
+        fs::remove_dir_all(&path).unwrap();
+        result.map(|()| config)
+    }
+
+    #[test]
+    fn test_expand_module() {
+        let config = expand("expand", r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: before
+  type: Test/Echo
+- name: web
+  kind: module
+  dependsOn:
+  - "[resourceId('Test/Echo', 'before')]"
+  properties:
+    source: template.dsc.yaml
+    parameters:
+      port: 8080
+- name: after
+  type: Test/Echo
+  dependsOn:
+  - "[resourceId('Microsoft.DSC/Module', 'web')]"
+  properties:
+    output: "[outputs('web').port]"
+"#).unwrap();
+
+        let names = config.resources.iter().map(|resource| resource.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["before", "web/first", "web/second", "after"]);
+        let first = &config.resources[1];
+        assert_eq!(first.depends_on, Some(vec!["[resourceId('Test/Echo', 'before')]".to_string()]));
+        let properties = first.properties.as_ref().unwrap();
+        assert_eq!(properties["output"], Value::from(8080));
+        assert_eq!(properties["text"], Value::String("[concat('hello', ' world')]".to_string()));
+        let second = &config.resources[2];
+        assert_eq!(second.depends_on, Some(vec![
+            "[resourceId('Test/Echo', 'before')]".to_string(),
+            "[resourceId('Test/Echo', 'web/first')]".to_string(),
+        ]));
+        assert_eq!(second.properties.as_ref().unwrap()["output"], Value::String("[reference(resourceId('Test/Echo', 'web/first')).actualState.output]".to_string()));
+        let after = &config.resources[3];
+        assert_eq!(after.depends_on, Some(vec![
+            "[resourceId('Test/Echo', 'web/first')]".to_string(),
+            "[resourceId('Test/Echo', 'web/second')]".to_string(),
+        ]));
+        assert_eq!(after.properties.as_ref().unwrap()["output"], Value::String("[createObject('port', 8080).port]".to_string()));
+    }
+
+    #[test]
+    fn test_expression_parameter() {
+        let config = expand("expression", r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  condition: "[equals(1, 1)]"
+  properties:
+    source: template.dsc.yaml
+    parameters:
+      port: "[parameters('webPort')]"
+      greeting: "it's"
+"#).unwrap();
+
+        let first = &config.resources[0];
+        assert_eq!(first.condition.as_deref(), Some("[equals(1, 1)]"));
+        let properties = first.properties.as_ref().unwrap();
+        assert_eq!(properties["output"], Value::String("[parameters('webPort')]".to_string()));
+        assert_eq!(properties["text"], Value::String("[concat('it''s', ' world')]".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let missing = expand("missing", r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  properties:
+    source: template.dsc.yaml
+"#);
+        assert!(missing.is_err());
+
+        let unknown = expand("unknown", r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  properties:
+    source: template.dsc.yaml
+    parameters:
+      port: 80
+      other: 1
+"#);
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_absolute_source() {
+        let template_path = std::env::temp_dir().join(format!("dsc_module_test_{}_absolute.dsc.yaml", std::process::id()));
+        fs::write(&template_path, TEMPLATE).unwrap();
+        let result = expand("absolute", &format!(r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: web
+  kind: module
+  properties:
+    source: "{}"
+    parameters:
+      port: 80
+"#, template_path.to_string_lossy().replace('\\', "/")));
+        fs::remove_file(&template_path).unwrap();
+        assert!(matches!(result, Err(DscError::Validation(_))));
+    }
+
+    #[test]
+    fn test_missing_type() {
+        let mut config: Configuration = serde_yaml::from_str(r#"
+$schema: https://aka.ms/dsc/schemas/v3/bundled/config/document.json
+resources:
+- name: untyped
+"#).unwrap();
+        assert!(expand_modules(&mut config).is_err());
+    }
+
+    #[test]
+    fn test_expression_text() {
+        assert_eq!(expression_text(&Value::String("[parameters('a')]".to_string())), "parameters('a')");
+        assert_eq!(expression_text(&Value::String("[[literal]".to_string())), "'[literal]'");
+        assert_eq!(expression_text(&Value::String("it's".to_string())), "'it''s'");
+        assert_eq!(expression_text(&Value::Bool(true)), "true");
+        assert_eq!(expression_text(&Value::Null), "null()");
+        assert_eq!(expression_text(&serde_json::json!({"a": 1})), r#"json('{"a":1}')"#);
+    }
+}
@@ -2,12 +2,13 @@
 // Licensed under the MIT License.
 
 use crate::dscerror::DscError;
+use crate::configure::context::Context;
 use rust_i18n::t;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{collections::HashMap, fmt::Display};
-use tracing::trace;
+use tracing::{debug, trace};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
 pub struct SimpleInput {
@@ -19,9 +20,50 @@ pub struct ComplexInput {
     pub parameters: HashMap<String, InputObject>,
 }
 
+/// The value of a parameter in the complex input format.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct InputObject {
-    pub value: Value,
+#[serde(untagged)]
+pub enum InputObject {
+    /// A literal value.
+    Value {
+        value: Value,
+    },
+    /// A secret resolved through the extensions supporting secrets.
+    Reference {
+        reference: SecretReference,
+    },
+}
+
+/// A reference to a secret, the value of the parameter is the secret as a secure string.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SecretReference {
+    /// The vault containing the secret, every vault is searched if not specified.
+    pub key_vault: Option<KeyVaultReference>,
+    pub secret_name: String,
+}
+
+/// The vault containing a referenced secret.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyVaultReference {
+    /// The name of the vault.
+    Name(String),
+    /// The resource ID of the vault where the last segment is its name.
+    Id {
+        id: String,
+    },
+}
+
+impl KeyVaultReference {
+    /// Get the name of the vault.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            KeyVaultReference::Name(name) => name,
+            KeyVaultReference::Id { id } => id.trim_end_matches('/').rsplit('/').next().unwrap_or(id),
+        }
+    }
 }
 
 pub const SECURE_VALUE_REDACTED: &str = "<secureValue>";
@@ -79,13 +121,36 @@ pub enum SecureKind {
     SecureObject(SecureObject),
 }
 
-pub fn import_parameters(parameters: &Value) -> Result<HashMap<String, Value>, DscError> {
+/// Import the parameters from the simple or complex input format.
+///
+/// Parameters referencing a secret are resolved through the extensions supporting secrets
+/// and returned as a secure string.
+///
+/// # Arguments
+///
+/// * `parameters` - The parameters input.
+/// * `context` - The context with the discovered extensions and secret cache used to resolve secret references.
+///
+/// # Errors
+///
+/// This function will return an error if the input is invalid or a referenced secret can't be resolved.
+pub fn import_parameters(parameters: &Value, context: &Context) -> Result<HashMap<String, Value>, DscError> {
     let parameters = match serde_json::from_value::<ComplexInput>(parameters.clone()) {
         Ok(complex_input) => {
             trace!("{}", t!("configure.parameters.importingParametersFromComplexInput"));
             let mut result: HashMap<String, Value> = HashMap::new();
             for (name, input_object) in complex_input.parameters {
-                result.insert(name, input_object.value);
+                let value = match input_object {
+                    InputObject::Value { value } => value,
+                    InputObject::Reference { reference } => {
+                        debug!("{}", t!("configure.parameters.resolvingSecretReference", name = name, secret = reference.secret_name));
+                        let vault = reference.key_vault.as_ref().map(KeyVaultReference::name);
+                        let secure_string = context.secrets.borrow_mut().get_secret(&context.extensions, &reference.secret_name, vault)
+                            .map_err(|err| DscError::Validation(t!("configure.parameters.failedToResolveReference", name = name, error = err).to_string()))?;
+                        serde_json::to_value(secure_string)?
+                    },
+                };
+                result.insert(name, value);
             }
             result
         },
@@ -103,3 +168,40 @@ pub fn import_parameters(parameters: &Value) -> Result<HashMap<String, Value>, D
     };
     Ok(parameters)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn complex_input_with_values() {
+        let input = json!({ "parameters": { "a": { "value": 1 }, "b": { "value": "two" } } });
+        let parameters = import_parameters(&input, &Context::new()).unwrap();
+        assert_eq!(parameters["a"], json!(1));
+        assert_eq!(parameters["b"], json!("two"));
+    }
+
+    #[test]
+    fn simple_input_object_is_not_a_reference() {
+        let input = json!({ "parameters": { "a": { "reference": "not a secret reference" } } });
+        let parameters = import_parameters(&input, &Context::new()).unwrap();
+        assert_eq!(parameters["a"], json!({ "reference": "not a secret reference" }));
+    }
+
+    #[test]
+    fn reference_without_extensions() {
+        let input = json!({ "parameters": { "a": { "reference": { "keyVault": { "id": "/subscriptions/x/providers/Microsoft.KeyVault/vaults/myVault" }, "secretName": "mySecret" } } } });
+        let result = import_parameters(&input, &Context::new());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("'a'"));
+    }
+
+    #[test]
+    fn key_vault_name() {
+        let by_id = KeyVaultReference::Id { id: "/subscriptions/x/resourceGroups/y/providers/Microsoft.KeyVault/vaults/myVault".to_string() };
+        assert_eq!(by_id.name(), "myVault");
+        let by_name = KeyVaultReference::Name("otherVault".to_string());
+        assert_eq!(by_name.name(), "otherVault");
+    }
+}
@@ -8,6 +8,7 @@ use crate::dscresources::adapted_resource_manifest::AdaptedPathOrContent;
 use crate::dscresources::dscresource::{Capability, DscResource, ImplementedAs};
 use crate::dscresources::resource_manifest::{Kind, ResourceManifest, SchemaKind};
 use crate::dscresources::command_resource::invoke_command;
+use crate::discovery::trust::get_trust_policy;
 use crate::dscerror::DscError;
 use crate::extensions::dscextension::{self, DscExtension, Capability as ExtensionCapability};
 use crate::extensions::extension_manifest::ExtensionManifest;
@@ -20,7 +21,7 @@ use serde_json::Value;
 use std::{collections::{HashMap, HashSet}, sync::{LazyLock, RwLock}};
 use std::env;
 use std::ffi::OsStr;
-use std::fs::{create_dir_all, read, read_to_string, write};
+use std::fs::{create_dir_all, read, write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use tracing::{debug, info, trace, warn};
@@ -275,6 +276,10 @@ impl ResourceDiscovery for CommandDiscovery {
                                 let imported_manifests = match load_manifest(&path)
                                 {
                                     Ok(r) => r,
+                                    Err(DscError::Untrusted(reason)) => {
+                                        warn!("{}", t!("discovery.commandDiscovery.untrustedManifest", path = path.to_string_lossy(), reason = reason));
+                                        continue;
+                                    },
                                     Err(e) => {
                                         // At this point we can't determine whether or not the bad manifest contains
                                         // resource that is requested by resource/config operation
@@ -731,21 +736,24 @@ pub fn load_manifest_content(content: &Value) -> Result<Vec<ImportedManifest>, D
 ///
 /// * Returns a `DscError` if the manifest could not be loaded or parsed.
 pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
-    let contents = read_to_string(path)?;
+    // read the manifest once so the verified content is the content that gets parsed
+    let bytes = read(path)?;
+    get_trust_policy().verify_contents(path, &bytes)?;
+    let contents = std::str::from_utf8(&bytes)?;
     let Some(file_name_lowercase) = path.file_name().and_then(OsStr::to_str).map(|s| s.to_lowercase()) else {
         return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidManifestFileName", path = path.to_string_lossy()).to_string()));
     };
     let extension_is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
     if DSC_ADAPTED_RESOURCE_EXTENSIONS.iter().any(|ext| file_name_lowercase.ends_with(ext)) {
         let resource = if extension_is_json {
-            match serde_json::from_str::<AdaptedDscResourceManifest>(&contents) {
+            match serde_json::from_str::<AdaptedDscResourceManifest>(contents) {
                 Ok(resource) => resource,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidAdaptedResourceManifest", resource = path.to_string_lossy(), err = err).to_string()));
                 }
             }
         } else {
-            match serde_yaml::from_str::<AdaptedDscResourceManifest>(&contents) {
+            match serde_yaml::from_str::<AdaptedDscResourceManifest>(contents) {
                 Ok(resource) => resource,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidAdaptedResourceManifest", resource = path.to_string_lossy(), err = err).to_string()));
@@ -761,14 +769,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
     }
     if DSC_RESOURCE_EXTENSIONS.iter().any(|ext| file_name_lowercase.ends_with(ext)) {
         let manifest = if extension_is_json {
-            match serde_json::from_str::<ResourceManifest>(&contents) {
+            match serde_json::from_str::<ResourceManifest>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidResourceManifest", resource = path.to_string_lossy(), err = err).to_string()));
                 }
             }
         } else {
-            match serde_yaml::from_str::<ResourceManifest>(&contents) {
+            match serde_yaml::from_str::<ResourceManifest>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidResourceManifest", resource = path.to_string_lossy(), err = err).to_string()));
@@ -784,14 +792,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
     }
     if DSC_EXTENSION_EXTENSIONS.iter().any(|ext| file_name_lowercase.ends_with(ext)) {
         let manifest = if extension_is_json {
-            match serde_json::from_str::<ExtensionManifest>(&contents) {
+            match serde_json::from_str::<ExtensionManifest>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidExtensionManifest", resource = path.to_string_lossy(), err = err).to_string()));
                 }
             }
         } else {
-            match serde_yaml::from_str::<ExtensionManifest>(&contents) {
+            match serde_yaml::from_str::<ExtensionManifest>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidExtensionManifest", resource = path.to_string_lossy(), err = err).to_string()));
@@ -808,14 +816,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
     if DSC_MANIFEST_LIST_EXTENSIONS.iter().any(|ext| file_name_lowercase.ends_with(ext)) {
         let mut resources: Vec<ImportedManifest> = vec![];
         let manifest_list = if extension_is_json {
-            match serde_json::from_str::<ManifestList>(&contents) {
+            match serde_json::from_str::<ManifestList>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidManifestList", resource = path.to_string_lossy(), err = err).to_string()));
                 }
             }
         } else {
-            match serde_yaml::from_str::<ManifestList>(&contents) {
+            match serde_yaml::from_str::<ManifestList>(contents) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.invalidManifestList", resource = path.to_string_lossy(), err = err).to_string()));
@@ -828,7 +836,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
                     debug!("{}", t!("discovery.commandDiscovery.conditionNotMet", path = path.to_string_lossy(), condition = resource.condition.as_ref() : {:?}, resource = resource.type_name));
                     continue;
                 }
-                let resource = load_adapted_resource_manifest(path, resource)?;
+                let resource = match load_adapted_resource_manifest(path, resource) {
+                    Ok(resource) => resource,
+                    Err(DscError::Untrusted(reason)) => {
+                        warn!("{}", t!("discovery.commandDiscovery.untrustedResource", resource = resource.type_name, reason = reason));
+                        continue;
+                    },
+                    Err(err) => return Err(err),
+                };
                 resources.push(ImportedManifest::Resource(resource));
             }
         }
@@ -838,7 +853,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
                     debug!("{}", t!("discovery.commandDiscovery.conditionNotMet", path = path.to_string_lossy(), condition = res_manifest.condition.as_ref() : {:?}, resource = res_manifest.resource_type));
                     continue;
                 }
-                let resource = load_resource_manifest(path, res_manifest)?;
+                let resource = match load_resource_manifest(path, res_manifest) {
+                    Ok(resource) => resource,
+                    Err(DscError::Untrusted(reason)) => {
+                        warn!("{}", t!("discovery.commandDiscovery.untrustedResource", resource = res_manifest.resource_type, reason = reason));
+                        continue;
+                    },
+                    Err(err) => return Err(err),
+                };
                 resources.push(ImportedManifest::Resource(resource));
             }
         }
@@ -848,7 +870,14 @@ pub fn load_manifest(path: &Path) -> Result<Vec<ImportedManifest>, DscError> {
                     debug!("{}", t!("discovery.commandDiscovery.conditionNotMet", path = path.to_string_lossy(), condition = ext_manifest.condition.as_ref() : {:?}, resource = ext_manifest.r#type));
                     continue;
                 }
-                let extension = load_extension_manifest(path, ext_manifest)?;
+                let extension = match load_extension_manifest(path, ext_manifest) {
+                    Ok(extension) => extension,
+                    Err(DscError::Untrusted(reason)) => {
+                        warn!("{}", t!("discovery.commandDiscovery.untrustedResource", resource = ext_manifest.r#type, reason = reason));
+                        continue;
+                    },
+                    Err(err) => return Err(err),
+                };
                 resources.push(ImportedManifest::Extension(extension));
             }
         }
@@ -874,6 +903,9 @@ pub fn load_adapted_resource_manifest(path: &Path, manifest: &AdaptedDscResource
             if !resource_path.exists() {
                 return Err(DscError::InvalidManifest(t!("discovery.commandDiscovery.adaptedResourcePathNotFound", path = resource_path.to_string_lossy(), resource = manifest.type_name).to_string()));
             }
+            if resource_path.is_file() {
+                get_trust_policy().verify_file(&resource_path)?;
+            }
             resource.path = resource_path;
         },
         AdaptedPathOrContent::Content(content) => {
@@ -916,11 +948,11 @@ fn load_resource_manifest(path: &Path, manifest: &ResourceManifest) -> Result<Ds
 
     let mut capabilities: HashSet<Capability> = HashSet::new();
     if let Some(get) = &manifest.get {
-        verify_executable(&manifest.resource_type, "get", &get.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "get", &get.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Get);
     }
     if let Some(set) = &manifest.set {
-        verify_executable(&manifest.resource_type, "set", &set.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "set", &set.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Set);
         if set.handles_exist == Some(true) {
             capabilities.insert(Capability::SetHandlesExist);
@@ -930,31 +962,40 @@ fn load_resource_manifest(path: &Path, manifest: &ResourceManifest) -> Result<Ds
         }
     }
     if let Some(test) = &manifest.test {
-        verify_executable(&manifest.resource_type, "test", &test.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "test", &test.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Test);
     }
     if let Some(delete) = &manifest.delete {
-        verify_executable(&manifest.resource_type, "delete", &delete.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "delete", &delete.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Delete);
         if let Some(args) = &delete.args && args_contains_what_if(args) {
             capabilities.insert(Capability::DeleteWhatIf);
         }
     }
     if let Some(export) = &manifest.export {
-        verify_executable(&manifest.resource_type, "export", &export.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "export", &export.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Export);
     }
     if let Some(resolve) = &manifest.resolve {
-        verify_executable(&manifest.resource_type, "resolve", &resolve.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "resolve", &resolve.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::Resolve);
     }
     if let Some(SchemaKind::Command(command)) = &manifest.schema {
-        verify_executable(&manifest.resource_type, "schema", &command.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "schema", &command.executable, path.parent().unwrap())?;
     }
     if let Some(what_if) = &manifest.what_if {
-        verify_executable(&manifest.resource_type, "what-if", &what_if.executable, path.parent().unwrap());
+        verify_executable(&manifest.resource_type, "what-if", &what_if.executable, path.parent().unwrap())?;
         capabilities.insert(Capability::SetWhatIf);
     }
+    if let Some(validate) = &manifest.validate {
+        verify_executable(&manifest.resource_type, "validate", &validate.executable, path.parent().unwrap())?;
+    }
+    if let Some(adapter) = &manifest.adapter && let Some(list) = &adapter.list {
+        verify_executable(&manifest.resource_type, "list", &list.executable, path.parent().unwrap())?;
+    }
+    if let Some(host) = &manifest.host {
+        verify_executable(&manifest.resource_type, "host", &host.executable, path.parent().unwrap())?;
+    }
 
     let mut resource = DscResource::new();
     let mut capabilities: Vec<Capability> = capabilities.into_iter().collect();
@@ -980,15 +1021,25 @@ fn args_contains_what_if(args: &[SetDeleteArgKind]) -> bool {
 fn load_extension_manifest(path: &Path, manifest: &ExtensionManifest) -> Result<DscExtension, DscError> {
     let mut capabilities: Vec<dscextension::Capability> = vec![];
     if let Some(discover) = &manifest.discover {
-        verify_executable(&manifest.r#type, "discover", &discover.executable, path.parent().unwrap());
+        verify_executable(&manifest.r#type, "discover", &discover.executable, path.parent().unwrap())?;
         capabilities.push(dscextension::Capability::Discover);
     }
     if let Some(secret) = &manifest.secret {
-        verify_executable(&manifest.r#type, "secret", &secret.executable, path.parent().unwrap());
+        verify_executable(&manifest.r#type, "secret", &secret.executable, path.parent().unwrap())?;
         capabilities.push(dscextension::Capability::Secret);
     }
+    let mut functions = Vec::new();
+    if let Some(function) = &manifest.function {
+        verify_executable(&manifest.r#type, "function", &function.executable, path.parent().unwrap())?;
+        if function.functions.iter().all(|f| f.name.contains('.')) {
+            capabilities.push(dscextension::Capability::Function);
+            functions.clone_from(&function.functions);
+        } else {
+            warn!("{}", t!("discovery.commandDiscovery.functionNotNamespaced", extension = manifest.r#type));
+        }
+    }
     let import = if let Some(import) = &manifest.import {
-        verify_executable(&manifest.r#type, "import", &import.executable, path.parent().unwrap());
+        verify_executable(&manifest.r#type, "import", &import.executable, path.parent().unwrap())?;
         capabilities.push(dscextension::Capability::Import);
         if import.file_extensions.is_empty() {
             warn!("{}", t!("discovery.commandDiscovery.importExtensionsEmpty", extension = manifest.r#type));
@@ -1010,16 +1061,18 @@ fn load_extension_manifest(path: &Path, manifest: &ExtensionManifest) -> Result<
         path: path.to_path_buf(),
         directory: path.parent().unwrap().to_path_buf(),
         manifest: serde_json::to_value(manifest)?,
+        functions,
         ..Default::default()
     };
 
     Ok(extension)
 }
 
-fn verify_executable(resource: &str, operation: &str, executable: &str, directory: &Path) {
+fn verify_executable(resource: &str, operation: &str, executable: &str, directory: &Path) -> Result<(), DscError> {
     if canonicalize_which(executable, Some(directory)).is_err() {
         info!("{}", t!("discovery.commandDiscovery.executableNotFound", resource = resource, operation = operation, executable = executable));
     }
+    get_trust_policy().verify_executable(executable, directory)
 }
 
 fn add_resources_to_lookup_table(adapted_resources: &DiscoveryResourceCache)
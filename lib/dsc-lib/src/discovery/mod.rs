@@ -3,18 +3,24 @@
 
 pub mod command_discovery;
 pub mod discovery_trait;
+pub mod native_discovery;
+pub mod trust;
 
 use crate::configure::config_doc::ResourceDiscoveryMode;
 use crate::discovery::discovery_trait::{DiscoveryKind, ResourceDiscovery, DiscoveryFilter};
 use crate::dscerror::DscError;
 use crate::extensions::dscextension::{Capability, DscExtension};
 use crate::types::{FullyQualifiedTypeName, TypeNameFilter};
+use crate::dscresources::native_resource::{NativeResource, is_native_resource, to_dsc_resource};
 use crate::{dscresources::dscresource::DscResource, progress::ProgressFormat};
 use core::result::Result::Ok;
 use semver::Version;
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use command_discovery::{CommandDiscovery, ImportedManifest};
-use tracing::error;
+use native_discovery::NativeDiscovery;
+use rust_i18n::t;
+use tracing::{debug, error};
 
 /// Defines the caching [`BTreeMap`] for discovered DSC extensions.
 type DiscoveryExtensionCache = BTreeMap<FullyQualifiedTypeName, DscExtension>;
@@ -28,6 +34,7 @@ pub struct Discovery {
     pub resources: DiscoveryResourceCache,
     pub extensions: DiscoveryExtensionCache,
     pub refresh_cache: bool,
+    native_resources: BTreeMap<FullyQualifiedTypeName, DscResource>,
 }
 
 impl Discovery {
@@ -43,6 +50,7 @@ impl Discovery {
             resources: DiscoveryResourceCache::new(),
             extensions: DiscoveryExtensionCache::new(),
             refresh_cache: false,
+            native_resources: BTreeMap::new(),
         }
     }
 
@@ -64,8 +72,10 @@ impl Discovery {
         adapter_name_filter: Option<&TypeNameFilter>,
         progress_format: ProgressFormat
     ) -> Vec<ImportedManifest> {
+        // native resources are listed last so they take precedence over a manifest with the same type and version
         let discovery_types: Vec<Box<dyn ResourceDiscovery>> = vec![
             Box::new(command_discovery::CommandDiscovery::new(progress_format)),
+            Box::new(NativeDiscovery::new(self.native_resources.values().cloned().collect())),
         ];
 
         let mut resources: BTreeMap<String, ImportedManifest> = BTreeMap::new();
@@ -139,6 +149,42 @@ impl Discovery {
         }
     }
 
+    /// Register an in-process resource with this instance.
+    ///
+    /// A resource registered with the same type name replaces the previous registration.  If this
+    /// instance already discovered resources, the resource is also added to its cache ahead of any
+    /// resource with the same type name so it takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource to register.
+    pub fn register_native_resource(&mut self, resource: Arc<dyn NativeResource>) {
+        let dsc_resource = to_dsc_resource(resource);
+        debug!("{}", t!("dscresources.nativeResource.registering", resource = dsc_resource.type_name));
+        self.native_resources.insert(dsc_resource.type_name.clone(), dsc_resource.clone());
+        if !self.resources.is_empty() {
+            let resources = self.resources.entry(dsc_resource.type_name.clone()).or_default();
+            resources.retain(|existing| !is_native_resource(existing));
+            resources.insert(0, dsc_resource);
+        }
+    }
+
+    /// Remove an in-process resource registered with this instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name` - The type name of the resource to remove.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the resource was registered.
+    pub fn unregister_native_resource(&mut self, type_name: &FullyQualifiedTypeName) -> bool {
+        if let Some(resources) = self.resources.get_mut(type_name) {
+            resources.retain(|existing| !is_native_resource(existing));
+        }
+        self.native_resources.remove(type_name).is_some()
+    }
+
     /// Find resources based on the required resource types.
     ///
     /// # Arguments
@@ -156,12 +202,27 @@ impl Discovery {
             self.extensions.clear();
             command_discovery.set_discovery_mode(&ResourceDiscoveryMode::DuringDeployment);
         }
+        // native resources are found first so they take precedence and don't require enumerating manifests
         let discovery_types: Vec<Box<dyn ResourceDiscovery>> = vec![
+            Box::new(NativeDiscovery::new(self.native_resources.values().cloned().collect())),
             Box::new(command_discovery),
         ];
+        let mut remaining_filters = required_resource_types.to_vec();
         for mut discovery_type in discovery_types {
+            if !required_resource_types.is_empty() && remaining_filters.is_empty() {
+                // extensions are only found by the remaining discovery types, so load them separately
+                if self.extensions.is_empty() {
+                    self.list_available(&DiscoveryKind::Extension, &TypeNameFilter::default(), None, progress_format);
+                }
+                break;
+            }
 
-            let discovered_resources = discovery_type.find_resources(required_resource_types)?;
+            let discovered_resources = discovery_type.find_resources(&remaining_filters)?;
+            remaining_filters.retain(|filter| {
+                !discovered_resources.get(filter.resource_type()).is_some_and(|resources| {
+                    resources.iter().any(|resource| matches_filter(resource, filter))
+                })
+            });
             for (resource_name, resources) in discovered_resources {
                 self.resources.entry(resource_name).or_default().extend(resources);
             }
@@ -172,6 +233,61 @@ impl Discovery {
         }
         Ok(())
     }
+
+    /// Discover resources for the filters that aren't satisfied by the already discovered resources.
+    ///
+    /// # Arguments
+    ///
+    /// * `required_resource_types` - The filters for the resources to discover.
+    /// * `progress_format` - The format of the progress output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if discovery fails.
+    pub fn find_additional_resources(&mut self, required_resource_types: &[DiscoveryFilter], progress_format: ProgressFormat) -> Result<(), DscError> {
+        if self.refresh_cache {
+            // resources are discovered on each lookup when the cache is refreshed
+            return Ok(());
+        }
+        if self.resources.is_empty() {
+            return self.find_resources(required_resource_types, progress_format);
+        }
+
+        let missing_filters: Vec<DiscoveryFilter> = required_resource_types.iter().filter(|filter| {
+            !self.resources.get(filter.resource_type()).is_some_and(|resources| {
+                resources.iter().any(|resource| matches_filter(resource, filter))
+            })
+        }).cloned().collect();
+        if missing_filters.is_empty() {
+            return Ok(());
+        }
+
+        let mut discovery = Discovery {
+            native_resources: self.native_resources.clone(),
+            ..Discovery::new()
+        };
+        discovery.find_resources(&missing_filters, progress_format)?;
+        for (resource_name, resources) in discovery.resources {
+            self.resources.entry(resource_name).or_default().extend(resources);
+        }
+        for (extension_name, extension) in discovery.extensions {
+            self.extensions.entry(extension_name).or_insert(extension);
+        }
+        Ok(())
+    }
+}
+
+/// Check if a resource satisfies the version and adapter requirements specified in the filter.
+///
+/// # Arguments
+/// * `resource` - The resource to check.
+/// * `filter` - The discovery filter containing the requirements.
+///
+/// # Returns
+/// `true` if the resource matches the filter, `false` otherwise.
+fn matches_filter(resource: &DscResource, filter: &DiscoveryFilter) -> bool {
+    filter.require_version().is_none_or(|version_req| version_req.matches(&resource.version))
+        && matches_adapter_requirement(resource, filter)
 }
 
 /// Check if a resource matches the adapter requirement specified in the filter.
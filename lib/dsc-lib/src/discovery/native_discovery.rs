@@ -0,0 +1,79 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::configure::config_doc::ResourceDiscoveryMode;
+use crate::discovery::command_discovery::ImportedManifest;
+use crate::discovery::discovery_trait::{DiscoveryFilter, DiscoveryKind, ResourceDiscovery};
+use crate::discovery::{DiscoveryExtensionCache, DiscoveryManifestCache, DiscoveryResourceCache};
+use crate::dscerror::DscError;
+use crate::dscresources::dscresource::DscResource;
+use crate::types::TypeNameFilter;
+use rust_i18n::t;
+use tracing::trace;
+
+/// Discovers the in-process resources registered with
+/// [`Discovery::register_native_resource`](crate::discovery::Discovery::register_native_resource).
+#[derive(Default)]
+pub struct NativeDiscovery {
+    resources: Vec<DscResource>,
+}
+
+impl NativeDiscovery {
+    #[must_use]
+    pub fn new(resources: Vec<DscResource>) -> Self {
+        Self { resources }
+    }
+}
+
+impl ResourceDiscovery for NativeDiscovery {
+    fn discover(&mut self, _kind: &DiscoveryKind, _filter: &TypeNameFilter) -> Result<(), DscError> {
+        // registered resources are always available so there is nothing to discover
+        Ok(())
+    }
+
+    fn discover_adapted_resources(&mut self, _name_filter: &TypeNameFilter, _adapter_filter: &TypeNameFilter) -> Result<(), DscError> {
+        Ok(())
+    }
+
+    fn list_available(&mut self, kind: &DiscoveryKind, type_name_filter: &TypeNameFilter, adapter_name_filter: Option<&TypeNameFilter>) -> Result<DiscoveryManifestCache, DscError> {
+        let mut manifests = DiscoveryManifestCache::new();
+        // in-process resources are never adapted
+        if *kind != DiscoveryKind::Resource || adapter_name_filter.is_some() {
+            return Ok(manifests);
+        }
+
+        for resource in &self.resources {
+            if type_name_filter.is_match(&resource.type_name) {
+                trace!("{}", t!("discovery.nativeDiscovery.resourceFound", resource = resource.type_name, version = resource.version));
+                manifests.entry(resource.type_name.clone()).or_default().push(ImportedManifest::Resource(resource.clone()));
+            }
+        }
+        Ok(manifests)
+    }
+
+    fn find_resources(&mut self, required_resource_types: &[DiscoveryFilter]) -> Result<DiscoveryResourceCache, DscError> {
+        let mut found_resources = DiscoveryResourceCache::new();
+        for filter in required_resource_types {
+            if filter.require_adapter().is_some() {
+                continue;
+            }
+            for resource in &self.resources {
+                if resource.type_name != *filter.resource_type() {
+                    continue;
+                }
+                if filter.require_version().is_some_and(|version_req| !version_req.matches(&resource.version)) {
+                    continue;
+                }
+                trace!("{}", t!("discovery.nativeDiscovery.resourceFound", resource = resource.type_name, version = resource.version));
+                found_resources.entry(resource.type_name.clone()).or_default().push(resource.clone());
+            }
+        }
+        Ok(found_resources)
+    }
+
+    fn get_extensions(&mut self) -> Result<DiscoveryExtensionCache, DscError> {
+        Ok(DiscoveryExtensionCache::new())
+    }
+
+    fn set_discovery_mode(&mut self, _mode: &ResourceDiscoveryMode) {}
+}
@@ -0,0 +1,382 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::dscerror::DscError;
+use crate::util::{canonicalize_which, get_content_hash, get_setting};
+use base64::{Engine as _, engine::general_purpose};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rust_i18n::t;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tracing::{debug, error, trace};
+use which::which;
+
+/// The extension appended to a file name to locate its detached signature.
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+static TRUST_POLICY: LazyLock<TrustPolicy> = LazyLock::new(TrustPolicy::from_settings);
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct TrustPolicySetting {
+    /// whether manifests and their executables must be trusted to be discovered
+    #[serde(default)]
+    pub enforce: bool,
+    /// SHA-256 hashes of trusted manifests and executables
+    #[serde(default)]
+    pub allowed_hashes: Vec<String>,
+    /// base64 encoded Ed25519 public keys trusted to sign manifests and executables
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+}
+
+/// The trust policy applied to manifests during discovery and to executables when they're invoked.
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicy {
+    enforce: bool,
+    allowed_hashes: HashSet<String>,
+    trusted_keys: Vec<VerifyingKey>,
+}
+
+impl TrustPolicy {
+    /// Create a trust policy from the `trustPolicy` setting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a hash or public key in the setting is invalid.
+    pub fn new(setting: &TrustPolicySetting) -> Result<Self, DscError> {
+        let allowed_hashes = setting.allowed_hashes.iter().map(|hash| normalize_hash(hash)).collect();
+        let mut trusted_keys = Vec::new();
+        for key in &setting.trusted_keys {
+            let Ok(bytes) = general_purpose::STANDARD.decode(key) else {
+                return Err(DscError::Setting(t!("discovery.trust.invalidPublicKey", key = key).to_string()));
+            };
+            let Ok(bytes) = <[u8; 32]>::try_from(bytes.as_slice()) else {
+                return Err(DscError::Setting(t!("discovery.trust.invalidPublicKey", key = key).to_string()));
+            };
+            let Ok(verifying_key) = VerifyingKey::from_bytes(&bytes) else {
+                return Err(DscError::Setting(t!("discovery.trust.invalidPublicKey", key = key).to_string()));
+            };
+            trusted_keys.push(verifying_key);
+        }
+
+        Ok(Self {
+            enforce: setting.enforce,
+            allowed_hashes,
+            trusted_keys,
+        })
+    }
+
+    fn from_settings() -> Self {
+        let setting = match get_trust_policy_setting() {
+            Ok(Some(setting)) => setting,
+            Ok(None) => return Self::default(),
+            Err(err) => {
+                // fail closed so a malformed policy doesn't silently disable enforcement
+                error!("{}", t!("discovery.trust.invalidPolicy", err = err));
+                return Self { enforce: true, ..Self::default() };
+            }
+        };
+
+        match Self::new(&setting) {
+            Ok(policy) => policy,
+            Err(err) => {
+                error!("{}", t!("discovery.trust.invalidPolicy", err = err));
+                Self { enforce: true, ..Self::default() }
+            }
+        }
+    }
+
+    /// Whether the policy requires manifests and executables to be trusted.
+    #[must_use]
+    pub fn is_enforced(&self) -> bool {
+        self.enforce
+    }
+
+    /// Verify that a file is trusted by the policy.
+    ///
+    /// A file is trusted when its hash is in the allowlist or when a detached signature
+    /// next to it, with the `.sig` extension appended, is valid for one of the trusted keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file to verify.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Untrusted` if the policy is enforced and the file isn't trusted.
+    pub fn verify_file(&self, path: &Path) -> Result<(), DscError> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        let contents = fs::read(path)?;
+        self.verify_contents(path, &contents)
+    }
+
+    /// Verify that content read from a file is trusted by the policy.
+    ///
+    /// Callers that use the content should read the file once and verify that same buffer
+    /// so the file can't be replaced between verification and use.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path the content was read from, used to locate the detached signature.
+    /// * `contents` - The content of the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Untrusted` if the policy is enforced and the content isn't trusted.
+    pub fn verify_contents(&self, path: &Path, contents: &[u8]) -> Result<(), DscError> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        let hash = get_content_hash(contents);
+        if self.allowed_hashes.contains(&hash) {
+            trace!("{}", t!("discovery.trust.hashAllowed", path = path.display()));
+            return Ok(());
+        }
+
+        if let Some(signature) = read_signature(path)? {
+            if self.trusted_keys.iter().any(|key| key.verify(contents, &signature).is_ok()) {
+                trace!("{}", t!("discovery.trust.signatureValid", path = path.display()));
+                return Ok(());
+            }
+            return Err(DscError::Untrusted(t!("discovery.trust.signatureInvalid", path = path.display()).to_string()));
+        }
+
+        Err(DscError::Untrusted(t!("discovery.trust.notTrusted", path = path.display(), hash = hash).to_string()))
+    }
+
+    /// Verify that the executable used by a manifest is trusted by the policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `executable` - The executable as specified in the manifest.
+    /// * `directory` - The directory containing the manifest.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Untrusted` if the policy is enforced and the executable
+    /// can't be found or isn't trusted.
+    pub fn verify_executable(&self, executable: &str, directory: &Path) -> Result<(), DscError> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        let Some(path) = resolve_executable(executable, directory) else {
+            return Err(DscError::Untrusted(t!("discovery.trust.executableNotResolved", executable = executable).to_string()));
+        };
+        self.verify_file(&path)
+    }
+
+    /// Verify that a command run for a manifest is trusted by the policy.
+    ///
+    /// Besides the executable, every argument that names a file in the manifest directory,
+    /// like the script run by an interpreter, must be trusted.
+    ///
+    /// # Arguments
+    ///
+    /// * `executable` - The executable as specified in the manifest.
+    /// * `args` - The arguments passed to the executable.
+    /// * `directory` - The directory containing the manifest.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a `DscError::Untrusted` if the policy is enforced and the executable
+    /// or a file argument can't be found or isn't trusted.
+    pub fn verify_command(&self, executable: &str, args: &[String], directory: &Path) -> Result<(), DscError> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        self.verify_executable(executable, directory)?;
+        for path in args.iter().filter_map(|arg| resolve_file_arg(arg, directory)) {
+            self.verify_file(&path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the trust policy loaded from the settings for the current process.
+#[must_use]
+pub fn get_trust_policy() -> &'static TrustPolicy {
+    &TRUST_POLICY
+}
+
+fn get_trust_policy_setting() -> Result<Option<TrustPolicySetting>, DscError> {
+    let Ok(v) = get_setting("trustPolicy") else {
+        debug!("{}", t!("discovery.trust.noPolicy"));
+        return Ok(None);
+    };
+
+    // if there is a policy value defined - use it; otherwise use setting value
+    let value = if v.policy != serde_json::Value::Null {
+        v.policy
+    } else if v.setting != serde_json::Value::Null {
+        v.setting
+    } else {
+        return Ok(None);
+    };
+
+    match serde_json::from_value::<TrustPolicySetting>(value) {
+        Ok(setting) => Ok(Some(setting)),
+        Err(e) => Err(DscError::Setting(format!("{e}"))),
+    }
+}
+
+fn normalize_hash(hash: &str) -> String {
+    let hash = hash.trim().to_lowercase();
+    if hash.starts_with("sha256:") {
+        hash
+    } else {
+        format!("sha256:{hash}")
+    }
+}
+
+fn read_signature(path: &Path) -> Result<Option<Signature>, DscError> {
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".");
+    signature_path.push(SIGNATURE_EXTENSION);
+    let signature_path = PathBuf::from(signature_path);
+    if !signature_path.is_file() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&signature_path)?;
+    let Ok(bytes) = general_purpose::STANDARD.decode(contents.trim()) else {
+        return Err(DscError::Untrusted(t!("discovery.trust.invalidSignature", path = signature_path.display()).to_string()));
+    };
+    let Ok(signature) = Signature::from_slice(&bytes) else {
+        return Err(DscError::Untrusted(t!("discovery.trust.invalidSignature", path = signature_path.display()).to_string()));
+    };
+    Ok(Some(signature))
+}
+
+fn resolve_executable(executable: &str, directory: &Path) -> Option<PathBuf> {
+    let executable = canonicalize_which(executable, Some(directory)).ok()?;
+    which(executable).ok()
+}
+
+fn resolve_file_arg(arg: &str, directory: &Path) -> Option<PathBuf> {
+    let directory = fs::canonicalize(directory).ok()?;
+    let path = fs::canonicalize(directory.join(arg)).ok()?;
+    (path.is_file() && path.starts_with(&directory)).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn write_temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("dsc_trust_test_{}_{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_not_enforced() {
+        let policy = TrustPolicy::default();
+        assert!(policy.verify_file(Path::new("does_not_exist.dsc.resource.json")).is_ok());
+    }
+
+    #[test]
+    fn test_hash_allowlist() {
+        let path = write_temp_file("hash.dsc.resource.json", b"{}");
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec!["44136FA355B3678A1146AD16F7E8649E94FB4FC21FE77E8310C060F61CAAFF8A".to_string()],
+            trusted_keys: vec![],
+        };
+        let policy = TrustPolicy::new(&setting).unwrap();
+        assert!(policy.verify_file(&path).is_ok());
+        fs::write(&path, b"{ }").unwrap();
+        assert!(matches!(policy.verify_file(&path), Err(DscError::Untrusted(_))));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_verify_contents_uses_buffer() {
+        let path = write_temp_file("contents.dsc.resource.json", b"{ }");
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec!["44136FA355B3678A1146AD16F7E8649E94FB4FC21FE77E8310C060F61CAAFF8A".to_string()],
+            trusted_keys: vec![],
+        };
+        let policy = TrustPolicy::new(&setting).unwrap();
+        assert!(policy.verify_contents(&path, b"{}").is_ok());
+        assert!(matches!(policy.verify_contents(&path, b"{ }"), Err(DscError::Untrusted(_))));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_detached_signature() {
+        let path = write_temp_file("signed.dsc.resource.json", b"{}");
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let signature = signing_key.sign(b"{}");
+        let signature_path = PathBuf::from(format!("{}.{SIGNATURE_EXTENSION}", path.display()));
+        fs::write(&signature_path, general_purpose::STANDARD.encode(signature.to_bytes())).unwrap();
+
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec![],
+            trusted_keys: vec![general_purpose::STANDARD.encode(signing_key.verifying_key().to_bytes())],
+        };
+        let policy = TrustPolicy::new(&setting).unwrap();
+        assert!(policy.verify_file(&path).is_ok());
+
+        let other_key = SigningKey::from_bytes(&[9u8; 32]);
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec![],
+            trusted_keys: vec![general_purpose::STANDARD.encode(other_key.verifying_key().to_bytes())],
+        };
+        let policy = TrustPolicy::new(&setting).unwrap();
+        assert!(matches!(policy.verify_file(&path), Err(DscError::Untrusted(_))));
+
+        fs::remove_file(&signature_path).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_command_script_argument() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = std::env::temp_dir().join(format!("dsc_trust_test_{}_command", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let interpreter = directory.join("interpreter");
+        fs::write(&interpreter, b"{}").unwrap();
+        fs::set_permissions(&interpreter, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(directory.join("script.ps1"), b"{ }").unwrap();
+        let args = vec!["-File".to_string(), "script.ps1".to_string()];
+
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec!["44136FA355B3678A1146AD16F7E8649E94FB4FC21FE77E8310C060F61CAAFF8A".to_string()],
+            trusted_keys: vec![],
+        };
+        let policy = TrustPolicy::new(&setting).unwrap();
+        assert!(policy.verify_command("interpreter", &[], &directory).is_ok());
+        assert!(matches!(policy.verify_command("interpreter", &args, &directory), Err(DscError::Untrusted(_))));
+
+        fs::write(directory.join("script.ps1"), b"{}").unwrap();
+        assert!(policy.verify_command("interpreter", &args, &directory).is_ok());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        let setting = TrustPolicySetting {
+            enforce: true,
+            allowed_hashes: vec![],
+            trusted_keys: vec!["not a key".to_string()],
+        };
+        assert!(matches!(TrustPolicy::new(&setting), Err(DscError::Setting(_))));
+    }
+}
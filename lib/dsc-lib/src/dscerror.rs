@@ -193,4 +193,7 @@ pub enum DscError {
 
     #[error("{t}: {0}", t = t!("dscerror.setting"))]
     Setting(String),
+
+    #[error("{t}: {0}", t = t!("dscerror.untrusted"))]
+    Untrusted(String),
 }
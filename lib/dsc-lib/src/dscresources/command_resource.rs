@@ -9,9 +9,12 @@ use serde::Deserialize;
 use serde_json::{Map, Value};
 use std::{collections::HashMap, env, path::Path, process::Stdio};
 use crate::{configure::{config_doc::{ExecutionKind, SecurityContextKind}, config_result::{ResourceGetResult, ResourceTestResult}}, dscresources::resource_manifest::{ExportSchemaKind, ExportSchemaOrFiltering, SchemaArgKind}, types::{ExitCodesMap}, util::canonicalize_which};
+use crate::discovery::trust::get_trust_policy;
 use crate::dscerror::DscError;
+use crate::scrub::scrub;
 use super::{
     dscresource::{get_diff, redact, DscResource},
+    resource_host::invoke_host,
     invoke_result::{
         DeleteResult, DeleteResultKind, ExportResult,
         GetResult, ResolveResult, SetResult, TestResult, ValidateResult,
@@ -57,7 +60,7 @@ pub fn invoke_get(resource: &DscResource, filter: &str, target_resource: Option<
     }
 
     info!("{}", t!("dscresources.commandResource.invokeGetUsing", resource = &resource.type_name, executable = &get.executable));
-    let (_exit_code, stdout, stderr) = invoke_command(&get.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (_exit_code, stdout, stderr) = invoke_resource_command(resource, "get", &get.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
     if resource.kind == Kind::Resource {
         debug!("{}", t!("dscresources.commandResource.verifyOutputUsing", resource = &resource.type_name, executable = &get.executable));
         verify_json_from_manifest(resource, &stdout, target_resource)?;
@@ -186,7 +189,7 @@ pub fn invoke_set(resource: &DscResource, desired: &str, skip_test: bool, execut
     let command_input = get_command_input(get.input.as_ref(), desired)?;
 
     info!("{}", t!("dscresources.commandResource.setGetCurrent", resource = &command_resource.type_name, executable = &get.executable));
-    let (exit_code, stdout, stderr) = invoke_command(&get.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (exit_code, stdout, stderr) = invoke_resource_command(resource, "get", &get.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
 
     if resource.kind == Kind::Resource {
         debug!("{}", t!("dscresources.commandResource.setVerifyGet", resource = &resource.type_name, executable = &get.executable));
@@ -227,7 +230,7 @@ pub fn invoke_set(resource: &DscResource, desired: &str, skip_test: bool, execut
         },
     }
 
-    let (exit_code, stdout, stderr) = invoke_command(&set.executable, args, input_desired, Some(&resource.directory), env, manifest.exit_codes.as_ref())?;
+    let (exit_code, stdout, stderr) = invoke_resource_command(resource, "set", &set.executable, args, input_desired, env, manifest.exit_codes.as_ref())?;
 
     let return_kind = if execution_type == &ExecutionKind::WhatIf {
         set.what_if_returns.as_ref().or(set.returns.as_ref())
@@ -341,7 +344,7 @@ pub fn invoke_test(resource: &DscResource, expected: &str, target_resource: Opti
     let command_input = get_command_input(test.input.as_ref(), expected)?;
 
     info!("{}", t!("dscresources.commandResource.invokeTestUsing", resource = &command_resource.type_name, executable = &test.executable));
-    let (exit_code, stdout, stderr) = invoke_command(&test.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (exit_code, stdout, stderr) = invoke_resource_command(resource, "test", &test.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
 
     if command_resource.kind == Kind::Importer {
         debug!("{}", t!("dscresources.commandResource.testGroupTestResponse"));
@@ -498,7 +501,7 @@ pub fn invoke_delete(resource: &DscResource, filter: &str, target_resource: Opti
     let command_input = get_command_input(delete.input.as_ref(), filter)?;
 
     info!("{}", t!("dscresources.commandResource.invokeDeleteUsing", resource = &command_resource.type_name, executable = &delete.executable));
-    let (_exit_code, stdout, _stderr) = invoke_command(&delete.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (_exit_code, stdout, _stderr) = invoke_resource_command(resource, "delete", &delete.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
     let result = if execution_type == &ExecutionKind::WhatIf {
         let delete_result: DeleteResult = serde_json::from_str(&stdout)?;
         DeleteResultKind::ResourceWhatIf(delete_result)
@@ -541,7 +544,7 @@ pub fn invoke_validate(resource: &DscResource, config: &str, target_resource: Op
     let command_input = get_command_input(validate.input.as_ref(), config)?;
 
     info!("{}", t!("dscresources.commandResource.invokeValidateUsing", resource = &resource.type_name, executable = &validate.executable));
-    let (_exit_code, stdout, _stderr) = invoke_command(&validate.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (_exit_code, stdout, _stderr) = invoke_resource_command(resource, "validate", &validate.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
     let result: ValidateResult = serde_json::from_str(&stdout)?;
     Ok(result)
 }
@@ -576,7 +579,7 @@ pub fn get_schema(resource: &DscResource, target_resource: Option<&DscResource>)
     match schema_kind {
         SchemaKind::Command(command) => {
             let args = process_schema_args(command.args.as_ref(), target_resource);
-            let (_exit_code, stdout, _stderr) = invoke_command(&command.executable, args, None, Some(&resource.directory), None, manifest.exit_codes.as_ref())?;
+            let (_exit_code, stdout, _stderr) = invoke_resource_command(resource, "schema", &command.executable, args, None, None, manifest.exit_codes.as_ref())?;
             Ok(stdout)
         },
         SchemaKind::Embedded(schema) => {
@@ -603,7 +606,7 @@ fn verify_with_export_schema(input: &str, resource: &DscResource, target_resourc
     let schema = match export.schema_or_filtering {
         Some(ExportSchemaOrFiltering::Schema(ExportSchemaKind::Command(ref command))) => {
             let args = process_schema_args(command.args.as_ref(), command_resource);
-            let (_exit_code, stdout, _stderr) = invoke_command(&command.executable, args, None, Some(&resource.directory), None, manifest.exit_codes.as_ref())?;
+            let (_exit_code, stdout, _stderr) = invoke_resource_command(resource, "schema", &command.executable, args, None, None, manifest.exit_codes.as_ref())?;
             stdout
         },
         Some(ExportSchemaOrFiltering::Schema(ExportSchemaKind::Embedded(ref schema))) => {
@@ -711,7 +714,7 @@ pub fn invoke_export(resource: &DscResource, input: Option<&str>, target_resourc
         args = process_get_args(export.args.as_ref(), "", command_resource);
     }
 
-    let (_exit_code, stdout, stderr) = invoke_command(&export.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (_exit_code, stdout, stderr) = invoke_resource_command(resource, "export", &export.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
     let mut instances: Vec<Value> = Vec::new();
     for line in stdout.lines()
     {
@@ -763,7 +766,7 @@ pub fn invoke_resolve(resource: &DscResource, input: &str) -> Result<ResolveResu
     let command_input = get_command_input(resolve.input.as_ref(), input)?;
 
     info!("{}", t!("dscresources.commandResource.invokeResolveUsing", resource = &resource.type_name, executable = &resolve.executable));
-    let (_exit_code, stdout, _stderr) = invoke_command(&resolve.executable, args, command_input.stdin.as_deref(), Some(&resource.directory), command_input.env, manifest.exit_codes.as_ref())?;
+    let (_exit_code, stdout, _stderr) = invoke_resource_command(resource, "resolve", &resolve.executable, args, command_input.stdin.as_deref(), command_input.env, manifest.exit_codes.as_ref())?;
     let result: ResolveResult = serde_json::from_str(&stdout)?;
     Ok(result)
 }
@@ -829,7 +832,7 @@ async fn run_process_async(executable: &str, args: Option<Vec<String>>, input: O
     let mut stderr_reader = BufReader::new(stderr).lines();
 
     if let Some(input) = input {
-        trace!("Writing to command STDIN: {input}");
+        trace!("Writing to command STDIN: {}", scrub(input));
         let Some(mut stdin) = child.stdin.take() else {
             return Err(DscError::CommandOperation(t!("dscresources.commandResource.processChildStdin").to_string(), executable.to_string()));
         };
@@ -896,6 +899,29 @@ async fn run_process_async(executable: &str, args: Option<Vec<String>>, input: O
     }
 }
 
+/// Invoke a command for a resource operation, using the persistent host if the manifest defines one.
+///
+/// # Arguments
+///
+/// * `resource` - The resource the operation is for
+/// * `operation` - The name of the operation
+/// * `executable` - The command to execute
+/// * `args` - Optional arguments to pass to the command
+/// * `input` - Optional input to pass to the command
+/// * `env` - Optional environment variable mappings to add or update
+/// * `exit_codes` - Descriptions of exit codes defined by the manifest
+///
+/// # Errors
+///
+/// Error is returned if the command or host fails.
+fn invoke_resource_command(resource: &DscResource, operation: &str, executable: &str, args: Option<Vec<String>>, input: Option<&str>, env: Option<HashMap<String, String>>, exit_codes: &ExitCodesMap) -> Result<(i32, String, String), DscError> {
+    if let Some(host) = resource.manifest.as_ref().and_then(|manifest| manifest.host.as_ref()) {
+        info!("{}", t!("dscresources.commandResource.invokeUsingHost", operation = operation, resource = &resource.type_name, executable = &host.executable));
+        return invoke_host(host, operation, executable, args.as_ref(), input, &resource.directory, env.as_ref(), exit_codes);
+    }
+    invoke_command(executable, args, input, Some(&resource.directory), env, exit_codes)
+}
+
 /// Invoke a command and return the exit code, stdout, and stderr.
 ///
 /// # Arguments
@@ -910,7 +936,7 @@ async fn run_process_async(executable: &str, args: Option<Vec<String>>, input: O
 ///
 /// # Errors
 ///
-/// Error is returned if the command fails to execute or stdin/stdout/stderr cannot be opened.
+/// Error is returned if the command isn't trusted by the trust policy, fails to execute or stdin/stdout/stderr cannot be opened.
 ///
 /// # Panics
 ///
@@ -918,6 +944,10 @@ async fn run_process_async(executable: &str, args: Option<Vec<String>>, input: O
 ///
 #[allow(clippy::implicit_hasher)]
 pub fn invoke_command(executable: &str, args: Option<Vec<String>>, input: Option<&str>, cwd: Option<&Path>, env: Option<HashMap<String, String>>, exit_codes: &ExitCodesMap) -> Result<(i32, String, String), DscError> {
+    if let Some(cwd) = cwd {
+        // verified when run so files replaced after discovery aren't trusted
+        get_trust_policy().verify_command(executable, args.as_deref().unwrap_or_default(), cwd)?;
+    }
     let executable = canonicalize_which(executable, cwd)?;
 
     let run_async = async {
@@ -1237,10 +1267,11 @@ pub fn log_stderr_line<'a>(process_id: &u32, trace_line: &'a str) -> &'a str
                 include_target = false;
                 0
             };
+            let message = scrub(&trace_object.fields.message);
             let trace_message = if include_target {
-                format!("PID {process_id}: {target}: {line_number}: {}", trace_object.fields.message)
+                format!("PID {process_id}: {target}: {line_number}: {message}")
             } else {
-                format!("PID {process_id}: {}", trace_object.fields.message)
+                format!("PID {process_id}: {message}")
             };
             match trace_object.level {
                 TraceLevel::Error => {
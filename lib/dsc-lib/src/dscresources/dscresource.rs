@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
 use crate::schemas::dsc_repo::DscRepoSchema;
@@ -23,6 +24,7 @@ use super::{
     invoke_result::{
         DeleteResultKind, ExportResult, GetResult, ResolveResult, ResourceTestResponse, SetResult, TestResult, ValidateResult
     },
+    native_resource::{self, NativeResource, is_native_resource},
     resource_manifest::ResourceManifest,
 };
 
@@ -63,6 +65,10 @@ pub struct DscResource {
     pub manifest: Option<ResourceManifest>,
     /// The content of the adapted resource, if available.
     pub adapted_content: Option<Map<String, Value>>,
+    /// The implementation of an in-process resource.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub(crate) native: Option<Arc<dyn NativeResource>>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema, Ord, PartialOrd)]
@@ -119,6 +125,7 @@ impl DscResource {
             target_resource: None,
             manifest: None,
             adapted_content: None,
+            native: None,
         }
     }
 
@@ -414,6 +421,9 @@ impl Invoke for DscResource {
             Some(ImplementedAs::Command) => {
                 command_resource::invoke_get(self, filter, self.target_resource.as_deref())
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::invoke_get(self, filter)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -433,6 +443,9 @@ impl Invoke for DscResource {
             Some(ImplementedAs::Command) => {
                 command_resource::invoke_set(self, desired, skip_test, execution_type, self.target_resource.as_deref())
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::invoke_set(self, desired, skip_test, execution_type)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -484,6 +497,9 @@ impl Invoke for DscResource {
                     command_resource::invoke_test(self, expected, self.target_resource.as_deref())
                 }
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::invoke_test(self, expected)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -503,6 +519,9 @@ impl Invoke for DscResource {
             Some(ImplementedAs::Command) => {
                 command_resource::invoke_delete(self, filter, self.target_resource.as_deref(), execution_type)
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::invoke_delete(self, filter, execution_type)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -522,6 +541,9 @@ impl Invoke for DscResource {
             Some(ImplementedAs::Command) => {
                 command_resource::invoke_validate(self, config, self.target_resource.as_deref())
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::invoke_validate(self, config)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -544,6 +566,9 @@ impl Invoke for DscResource {
             Some(ImplementedAs::Command) => {
                 command_resource::get_schema(self, self.target_resource.as_deref())
             },
+            Some(ImplementedAs::Custom(_)) if is_native_resource(self) => {
+                native_resource::get_schema(self)
+            },
             _ => {
                 Err(DscError::NotImplemented(t!("dscresources.dscresource.customResourceNotSupported").to_string()))
             },
@@ -558,6 +583,9 @@ impl Invoke for DscResource {
         if let Some(adapter) = &self.require_adapter {
             return self.invoke_export_with_adapter(adapter, self, input);
         }
+        if is_native_resource(self) {
+            return native_resource::invoke_export(self, Some(input));
+        }
 
         command_resource::invoke_export(self, Some(input), self.target_resource.as_deref())
     }
@@ -570,6 +598,9 @@ impl Invoke for DscResource {
         if self.require_adapter.is_some() {
             return Err(DscError::NotSupported(t!("dscresources.dscresource.invokeResolveNotSupported", resource = self.type_name).to_string()));
         }
+        if is_native_resource(self) {
+            return Err(DscError::NotSupported(t!("dscresources.dscresource.invokeResolveNotSupported", resource = self.type_name).to_string()));
+        }
 
         command_resource::invoke_resolve(self, input)
     }
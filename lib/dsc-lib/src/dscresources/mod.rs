@@ -5,6 +5,8 @@ pub mod adapted_resource_manifest;
 pub mod command_resource;
 pub mod dscresource;
 pub mod invoke_result;
+pub mod native_resource;
+pub mod resource_host;
 pub mod resource_manifest;
 
 use super::dscerror;
@@ -0,0 +1,377 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::configure::config_doc::ExecutionKind;
+use crate::dscerror::DscError;
+use crate::dscresources::dscresource::{Capability, DscResource, ImplementedAs, get_diff, redact, validate_json};
+use crate::dscresources::invoke_result::{
+    DeleteResultKind, ExportResult, GetResult, ResourceGetResponse, ResourceSetResponse, ResourceTestResponse, SetResult, TestResult, ValidateResult
+};
+use crate::dscresources::resource_manifest::Kind;
+use crate::types::{FullyQualifiedTypeName, ResourceVersion};
+use rust_i18n::t;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// The name used with [`ImplementedAs::Custom`] for resources implemented in-process.
+pub const NATIVE_IMPLEMENTATION: &str = "native";
+
+/// The interface for a DSC resource implemented in Rust and invoked in-process.
+///
+/// Implementations are registered with [`DscManager::register_native_resource`](crate::DscManager::register_native_resource)
+/// or [`Discovery::register_native_resource`](crate::discovery::Discovery::register_native_resource) and are then
+/// found by that discovery like any other resource, without spawning a process or serializing through stdin.
+/// Only `get` and `schema` are required, the other operations return `NotSupported` unless the
+/// resource overrides them and reports the matching [`Capability`].
+pub trait NativeResource: Send + Sync {
+    /// The fully qualified type name of the resource.
+    fn type_name(&self) -> FullyQualifiedTypeName;
+
+    /// The version of the resource.
+    fn version(&self) -> ResourceVersion;
+
+    /// The description of the resource.
+    fn description(&self) -> Option<String> {
+        None
+    }
+
+    /// The capabilities of the resource.
+    fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::Get]
+    }
+
+    /// Get the JSON schema for the properties of the resource.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the schema can't be generated.
+    fn schema(&self) -> Result<Value, DscError>;
+
+    /// Get the actual state of the resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The properties identifying the instance to get.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails.
+    fn get(&self, filter: &Value) -> Result<Value, DscError>;
+
+    /// Set the resource to the desired state and return the state after the operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `desired` - The desired state of the resource.
+    /// * `execution_type` - Whether to apply the change or only report what would change.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails or doesn't support set.
+    fn set(&self, desired: &Value, execution_type: &ExecutionKind) -> Result<Value, DscError> {
+        let _ = (desired, execution_type);
+        Err(DscError::NotSupported(t!("dscresources.nativeResource.operationNotSupported", resource = self.type_name(), operation = "set").to_string()))
+    }
+
+    /// Get the actual state of the resource to compare against the desired state.
+    ///
+    /// # Arguments
+    ///
+    /// * `desired` - The desired state of the resource.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails.
+    fn test(&self, desired: &Value) -> Result<Value, DscError> {
+        self.get(desired)
+    }
+
+    /// Delete the resource instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The properties identifying the instance to delete.
+    /// * `execution_type` - Whether to apply the change or only report what would change.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails or doesn't support delete.
+    fn delete(&self, filter: &Value, execution_type: &ExecutionKind) -> Result<(), DscError> {
+        let _ = (filter, execution_type);
+        Err(DscError::NotSupported(t!("dscresources.nativeResource.operationNotSupported", resource = self.type_name(), operation = "delete").to_string()))
+    }
+
+    /// Export all instances of the resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Optional properties to filter the exported instances.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the resource fails or doesn't support export.
+    fn export(&self, filter: Option<&Value>) -> Result<Vec<Value>, DscError> {
+        let _ = filter;
+        Err(DscError::NotSupported(t!("dscresources.nativeResource.operationNotSupported", resource = self.type_name(), operation = "export").to_string()))
+    }
+}
+
+impl fmt::Debug for dyn NativeResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeResource").field("type_name", &self.type_name()).finish()
+    }
+}
+
+/// Check whether a `DscResource` is implemented in-process.
+#[must_use]
+pub fn is_native_resource(resource: &DscResource) -> bool {
+    matches!(&resource.implemented_as, Some(ImplementedAs::Custom(name)) if name == NATIVE_IMPLEMENTATION)
+}
+
+/// Create the `DscResource` that invokes an in-process resource.
+///
+/// # Arguments
+///
+/// * `native` - The in-process resource.
+#[must_use]
+pub fn to_dsc_resource(native: Arc<dyn NativeResource>) -> DscResource {
+    let mut resource = DscResource::new();
+    resource.type_name = native.type_name();
+    resource.kind = Kind::Resource;
+    resource.version = native.version();
+    resource.description = native.description();
+    let mut capabilities = native.capabilities();
+    capabilities.sort();
+    capabilities.dedup();
+    resource.capabilities = capabilities;
+    resource.implemented_as = Some(ImplementedAs::Custom(NATIVE_IMPLEMENTATION.to_string()));
+    resource.schema = match native.schema() {
+        Ok(Value::Object(schema)) => Some(schema),
+        _ => None,
+    };
+    resource.native = Some(native);
+    resource
+}
+
+fn get_native_resource(resource: &DscResource) -> Result<&Arc<dyn NativeResource>, DscError> {
+    match &resource.native {
+        Some(native) => Ok(native),
+        None => Err(DscError::ResourceNotFound(resource.type_name.to_string(), resource.version.to_string())),
+    }
+}
+
+fn parse_input(input: &str) -> Result<Value, DscError> {
+    if input.trim().is_empty() {
+        return Ok(Value::Object(serde_json::Map::new()));
+    }
+    Ok(serde_json::from_str(input)?)
+}
+
+/// Invoke the get operation on an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered or fails.
+pub fn invoke_get(resource: &DscResource, filter: &str) -> Result<GetResult, DscError> {
+    let native = get_native_resource(resource)?;
+    let actual_state = native.get(&parse_input(filter)?)?;
+    Ok(GetResult::Resource(ResourceGetResponse { actual_state }))
+}
+
+/// Invoke the set operation on an in-process resource.
+///
+/// Unless `skip_test` is set, the resource is tested first and left unchanged if already in the desired state.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered, doesn't support set, or fails.
+pub fn invoke_set(resource: &DscResource, desired: &str, skip_test: bool, execution_type: &ExecutionKind) -> Result<SetResult, DscError> {
+    let native = get_native_resource(resource)?;
+    if !resource.capabilities.contains(&Capability::Set) {
+        return Err(DscError::NotSupported(t!("dscresources.nativeResource.operationNotSupported", resource = resource.type_name, operation = "set").to_string()));
+    }
+    let desired = parse_input(desired)?;
+    let before_state = native.test(&desired)?;
+    if !skip_test && get_diff(&desired, &before_state).is_empty() {
+        return Ok(SetResult::Resource(ResourceSetResponse {
+            before_state: before_state.clone(),
+            after_state: before_state,
+            changed_properties: None,
+        }));
+    }
+
+    let after_state = native.set(&desired, execution_type)?;
+    let diff = get_diff(&before_state, &after_state);
+    Ok(SetResult::Resource(ResourceSetResponse {
+        before_state,
+        after_state,
+        changed_properties: if diff.is_empty() { None } else { Some(diff) },
+    }))
+}
+
+/// Invoke the test operation on an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered or fails.
+pub fn invoke_test(resource: &DscResource, expected: &str) -> Result<TestResult, DscError> {
+    let native = get_native_resource(resource)?;
+    let desired_state = parse_input(expected)?;
+    let actual_state = native.test(&desired_state)?;
+    let diff_properties = get_diff(&desired_state, &actual_state);
+    Ok(TestResult::Resource(ResourceTestResponse {
+        desired_state: redact(&desired_state),
+        actual_state,
+        in_desired_state: diff_properties.is_empty(),
+        diff_properties,
+    }))
+}
+
+/// Invoke the delete operation on an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered, doesn't support delete, or fails.
+pub fn invoke_delete(resource: &DscResource, filter: &str, execution_type: &ExecutionKind) -> Result<DeleteResultKind, DscError> {
+    let native = get_native_resource(resource)?;
+    if !resource.capabilities.contains(&Capability::Delete) {
+        return Err(DscError::NotImplemented("delete".to_string()));
+    }
+    native.delete(&parse_input(filter)?, execution_type)?;
+    Ok(DeleteResultKind::ResourceActual)
+}
+
+/// Validate the input against the schema of an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered or the schema can't be generated.
+pub fn invoke_validate(resource: &DscResource, config: &str) -> Result<ValidateResult, DscError> {
+    let native = get_native_resource(resource)?;
+    let schema = native.schema()?;
+    match validate_json(&resource.type_name, &schema, &parse_input(config)?) {
+        Ok(()) => Ok(ValidateResult { valid: true, reason: None }),
+        Err(err) => Ok(ValidateResult { valid: false, reason: Some(err.to_string()) }),
+    }
+}
+
+/// Get the schema of an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered or the schema can't be generated.
+pub fn get_schema(resource: &DscResource) -> Result<String, DscError> {
+    let native = get_native_resource(resource)?;
+    Ok(serde_json::to_string(&native.schema()?)?)
+}
+
+/// Invoke the export operation on an in-process resource.
+///
+/// # Errors
+///
+/// This function will return an error if the resource isn't registered, doesn't support export, or fails.
+pub fn invoke_export(resource: &DscResource, input: Option<&str>) -> Result<ExportResult, DscError> {
+    let native = get_native_resource(resource)?;
+    let filter = match input {
+        Some(input) if !input.trim().is_empty() => Some(parse_input(input)?),
+        _ => None,
+    };
+    let actual_state = native.export(filter.as_ref())?;
+    Ok(ExportResult { actual_state })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{Discovery, discovery_trait::DiscoveryFilter};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct TestNative {
+        state: Mutex<Value>,
+    }
+
+    impl NativeResource for TestNative {
+        fn type_name(&self) -> FullyQualifiedTypeName {
+            FullyQualifiedTypeName::parse("Test/Native").unwrap()
+        }
+
+        fn version(&self) -> ResourceVersion {
+            ResourceVersion::parse("1.0.0").unwrap()
+        }
+
+        fn capabilities(&self) -> Vec<Capability> {
+            vec![Capability::Get, Capability::Set, Capability::Test, Capability::Export]
+        }
+
+        fn schema(&self) -> Result<Value, DscError> {
+            Ok(json!({
+                "type": "object",
+                "properties": {
+                    "value": { "type": "string" }
+                }
+            }))
+        }
+
+        fn get(&self, _filter: &Value) -> Result<Value, DscError> {
+            Ok(self.state.lock().unwrap().clone())
+        }
+
+        fn set(&self, desired: &Value, execution_type: &ExecutionKind) -> Result<Value, DscError> {
+            if *execution_type == ExecutionKind::WhatIf {
+                return Ok(desired.clone());
+            }
+            *self.state.lock().unwrap() = desired.clone();
+            Ok(desired.clone())
+        }
+
+        fn export(&self, _filter: Option<&Value>) -> Result<Vec<Value>, DscError> {
+            Ok(vec![self.state.lock().unwrap().clone()])
+        }
+    }
+
+    #[test]
+    fn test_native_resource_operations() {
+        let resource = to_dsc_resource(Arc::new(TestNative { state: Mutex::new(json!({ "value": "before" })) }));
+        assert!(is_native_resource(&resource));
+        assert!(resource.schema.is_some());
+
+        let GetResult::Resource(get) = invoke_get(&resource, "").unwrap() else { panic!("expected resource result") };
+        assert_eq!(get.actual_state, json!({ "value": "before" }));
+
+        let TestResult::Resource(test) = invoke_test(&resource, r#"{"value":"after"}"#).unwrap() else { panic!("expected resource result") };
+        assert!(!test.in_desired_state);
+        assert_eq!(test.diff_properties, vec!["value".to_string()]);
+
+        let SetResult::Resource(set) = invoke_set(&resource, r#"{"value":"after"}"#, false, &ExecutionKind::Actual).unwrap() else { panic!("expected resource result") };
+        assert_eq!(set.before_state, json!({ "value": "before" }));
+        assert_eq!(set.after_state, json!({ "value": "after" }));
+        assert_eq!(set.changed_properties, Some(vec!["value".to_string()]));
+
+        let export = invoke_export(&resource, None).unwrap();
+        assert_eq!(export.actual_state, vec![json!({ "value": "after" })]);
+
+        assert!(!invoke_validate(&resource, r#"{"value":1}"#).unwrap().valid);
+        assert!(matches!(invoke_delete(&resource, "", &ExecutionKind::Actual), Err(DscError::NotImplemented(_))));
+
+        let mut resource = resource;
+        resource.native = None;
+        assert!(matches!(invoke_get(&resource, ""), Err(DscError::ResourceNotFound(..))));
+    }
+
+    #[test]
+    fn test_registration_is_scoped_to_discovery() {
+        let type_name = FullyQualifiedTypeName::parse("Test/Native").unwrap();
+        let filter = DiscoveryFilter::new(&type_name, None, None);
+        let mut discovery = Discovery::new();
+        discovery.register_native_resource(Arc::new(TestNative { state: Mutex::new(json!({ "value": "registered" })) }));
+        let resource = discovery.find_resource(&filter).unwrap().unwrap().clone();
+        let GetResult::Resource(get) = invoke_get(&resource, "").unwrap() else { panic!("expected resource result") };
+        assert_eq!(get.actual_state, json!({ "value": "registered" }));
+
+        assert!(Discovery::new().find_resource(&filter).unwrap().is_none());
+        assert!(discovery.unregister_native_resource(&type_name));
+        assert!(discovery.find_resource(&filter).unwrap().is_none());
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::discovery::trust::get_trust_policy;
+use crate::dscerror::DscError;
+use crate::types::ExitCodesMap;
+use crate::util::canonicalize_which;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace, warn};
+
+use super::command_resource::log_stderr_line;
+use super::resource_manifest::HostMethod;
+
+/// The JSON-RPC version used by the resource host protocol.
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// The notification sent to a host before its stdin is closed.
+const SHUTDOWN_METHOD: &str = "shutdown";
+
+/// How long to wait for a host to exit after shutdown before killing it.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for a host to respond to a request when the manifest doesn't specify a timeout.
+const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The running hosts; the map is only locked to find or add a host and each host has its own lock
+/// so operations on different hosts don't wait on each other.
+static RESOURCE_HOSTS: LazyLock<Mutex<HashMap<HostKey, Arc<Mutex<ResourceHost>>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static HOST_SCOPE_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HostKey {
+    executable: String,
+    args: Vec<String>,
+    directory: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+struct HostRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: HostRequestParams<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HostRequestParams<'a> {
+    /// The executable the operation would have invoked as a separate process.
+    executable: &'a str,
+    /// The arguments the operation would have passed to the executable.
+    args: &'a [String],
+    /// The text the operation would have written to stdin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input: Option<&'a str>,
+    /// The environment variables the operation would have set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<&'a HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostResponse {
+    id: Option<Value>,
+    result: Option<HostResult>,
+    error: Option<HostError>,
+}
+
+/// The result of an operation, equivalent to the output of a separate process.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct HostResult {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostError {
+    code: i32,
+    message: String,
+}
+
+struct ResourceHost {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: Receiver<String>,
+    next_id: u64,
+}
+
+impl ResourceHost {
+    fn start(key: &HostKey) -> Result<Self, DscError> {
+        get_trust_policy().verify_command(&key.executable, &key.args, &key.directory)?;
+        let executable = canonicalize_which(&key.executable, Some(&key.directory))?;
+        debug!("{}", t!("dscresources.resourceHost.starting", executable = executable, args = key.args : {:?}));
+        let mut command = Command::new(&executable);
+        command.args(&key.args)
+            .current_dir(&key.directory)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(err) => return Err(DscError::CommandOperation(err.to_string(), executable)),
+        };
+
+        let (Some(stdin), Some(stdout), Some(stderr)) = (child.stdin.take(), child.stdout.take(), child.stderr.take()) else {
+            let _ = child.kill();
+            return Err(DscError::CommandOperation(t!("dscresources.resourceHost.failedOpenPipes").to_string(), executable));
+        };
+
+        // the host lives across operations so its stderr is traced as it arrives
+        let child_id = child.id();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                log_stderr_line(&child_id, &line);
+            }
+        });
+
+        // stdout is read on a separate thread so a request can stop waiting for a host that doesn't respond
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin: Some(stdin),
+            stdout: receiver,
+            next_id: 1,
+        })
+    }
+
+    fn request(&mut self, method: &str, params: HostRequestParams, timeout: Duration) -> Result<HostResult, DscError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = serde_json::to_string(&HostRequest {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            method,
+            params,
+        })?;
+        // the request isn't traced as its input can contain secure values
+        trace!("{}", t!("dscresources.resourceHost.sendingRequest", id = self.child.id(), method = method, request_id = id));
+        let Some(stdin) = self.stdin.as_mut() else {
+            return Err(DscError::CommandOperation(t!("dscresources.resourceHost.hostExited").to_string(), method.to_string()));
+        };
+        writeln!(stdin, "{request}")?;
+        stdin.flush()?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let line = match self.stdout.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(DscError::CommandOperation(t!("dscresources.resourceHost.responseTimeout", seconds = timeout.as_secs()).to_string(), method.to_string()));
+                },
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(DscError::CommandOperation(t!("dscresources.resourceHost.hostExited").to_string(), method.to_string()));
+                },
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let response: HostResponse = match serde_json::from_str(line) {
+                Ok(response) => response,
+                Err(err) => {
+                    return Err(DscError::Operation(t!("dscresources.resourceHost.invalidResponse", err = err).to_string()));
+                }
+            };
+            trace!("{}", t!("dscresources.resourceHost.receivedResponse", id = self.child.id(), request_id = response.id.clone().unwrap_or_default()));
+            // skip notifications and stale responses so the stream stays in sync
+            if response.id != Some(Value::from(id)) {
+                continue;
+            }
+            if let Some(error) = response.error {
+                return Err(DscError::Command(method.to_string(), error.code, error.message));
+            }
+            return Ok(response.result.unwrap_or_default());
+        }
+    }
+
+    fn stop(&mut self) {
+        let Some(mut stdin) = self.stdin.take() else {
+            return;
+        };
+        let child_id = self.child.id();
+        debug!("{}", t!("dscresources.resourceHost.stopping", id = child_id));
+        let notification = json!({ "jsonrpc": JSONRPC_VERSION, "method": SHUTDOWN_METHOD });
+        let _ = writeln!(stdin, "{notification}");
+        drop(stdin);
+
+        let start = Instant::now();
+        while start.elapsed() < SHUTDOWN_TIMEOUT {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        warn!("{}", t!("dscresources.resourceHost.killing", id = child_id));
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Keeps resource hosts alive until the outermost scope is dropped.
+///
+/// A configuration run creates a scope so hosts started during the run are reused by every
+/// resource instance and stopped when the run completes, including nested runs.
+pub struct ResourceHostScope {}
+
+impl ResourceHostScope {
+    #[must_use]
+    pub fn enter() -> Self {
+        HOST_SCOPE_DEPTH.fetch_add(1, Ordering::SeqCst);
+        Self {}
+    }
+}
+
+impl Drop for ResourceHostScope {
+    fn drop(&mut self) {
+        if HOST_SCOPE_DEPTH.fetch_sub(1, Ordering::SeqCst) == 1 {
+            stop_resource_hosts();
+        }
+    }
+}
+
+/// Invoke an operation through the persistent host of a resource.
+///
+/// The host is started on first use and reused for later operations until the current
+/// [`ResourceHostScope`] ends.  Outside of a scope the host is stopped after the operation.
+///
+/// # Arguments
+///
+/// * `host` - The host definition from the resource manifest.
+/// * `method` - The name of the operation, such as `get` or `set`.
+/// * `executable` - The executable the operation would have invoked.
+/// * `args` - The arguments the operation would have passed.
+/// * `input` - The text the operation would have written to stdin.
+/// * `directory` - The directory of the resource manifest.
+/// * `env` - The environment variables the operation would have set.
+/// * `exit_codes` - Descriptions of exit codes defined by the manifest.
+///
+/// # Errors
+///
+/// Error is returned if the host can't be started, exits, returns an error, or the operation returns a non-zero exit code.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::implicit_hasher)]
+pub fn invoke_host(host: &HostMethod, method: &str, executable: &str, args: Option<&Vec<String>>, input: Option<&str>, directory: &Path, env: Option<&HashMap<String, String>>, exit_codes: &ExitCodesMap) -> Result<(i32, String, String), DscError> {
+    let key = HostKey {
+        executable: host.executable.clone(),
+        args: host.args.clone().unwrap_or_default(),
+        directory: directory.to_path_buf(),
+    };
+    let params = HostRequestParams {
+        executable,
+        args: args.map(Vec::as_slice).unwrap_or_default(),
+        input,
+        env,
+    };
+
+    let timeout = host.timeout.map_or(DEFAULT_RESPONSE_TIMEOUT, Duration::from_secs);
+
+    let resource_host = {
+        let mut hosts = RESOURCE_HOSTS.lock().unwrap();
+        if let Some(resource_host) = hosts.get(&key) {
+            resource_host.clone()
+        } else {
+            let resource_host = Arc::new(Mutex::new(ResourceHost::start(&key)?));
+            hosts.insert(key.clone(), resource_host.clone());
+            resource_host
+        }
+    };
+    let result = {
+        // a host handles one request at a time, so only this host is locked for the round trip
+        let mut locked_host = resource_host.lock().unwrap();
+        let result = locked_host.request(method, params, timeout);
+        if let Err(DscError::CommandOperation(..) | DscError::Io(_)) = &result {
+            // the host is unusable so remove it and start a new one on the next operation
+            let mut hosts = RESOURCE_HOSTS.lock().unwrap();
+            if hosts.get(&key).is_some_and(|current| Arc::ptr_eq(current, &resource_host)) {
+                hosts.remove(&key);
+            }
+            drop(hosts);
+            locked_host.stop();
+        }
+        result
+    };
+    if HOST_SCOPE_DEPTH.load(Ordering::SeqCst) == 0 {
+        stop_resource_hosts();
+    }
+    let result = result?;
+
+    if result.exit_code != 0 {
+        if !exit_codes.is_empty_or_default()
+            && let Some(error_message) = exit_codes.get_code(result.exit_code) {
+                return Err(DscError::CommandExitFromManifest(executable.to_string(), result.exit_code, error_message.clone()));
+            }
+        return Err(DscError::Command(executable.to_string(), result.exit_code, result.stderr));
+    }
+    Ok((result.exit_code, result.stdout, result.stderr))
+}
+
+/// Stop all running resource hosts.
+pub fn stop_resource_hosts() {
+    let hosts: Vec<Arc<Mutex<ResourceHost>>> = RESOURCE_HOSTS.lock().unwrap().drain().map(|(_, host)| host).collect();
+    for host in hosts {
+        host.lock().unwrap().stop();
+    }
+}
@@ -94,6 +94,9 @@ pub struct ResourceManifest {
     /// Details how to get the schema of the resource.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<SchemaKind>,
+    /// Details how to start a persistent host that handles the operations of the resource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<HostMethod>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Map<String, Value>>,
 }
@@ -353,6 +356,19 @@ pub struct ResolveMethod {
     pub input: Option<InputKind>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
+#[dsc_repo_schema(base_name = "manifest.host", folder_path = "resource")]
+pub struct HostMethod {
+    /// The command to run to start the host.  The host reads JSON-RPC requests from stdin and
+    /// writes responses to stdout until stdin is closed.
+    pub executable: String,
+    /// The arguments to pass to the command to start the host.
+    pub args: Option<Vec<String>>,
+    /// The number of seconds to wait for the host to respond to a request before it's stopped.
+    /// Defaults to 300 seconds.
+    pub timeout: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
 #[dsc_repo_schema(base_name = "manifest.adapter", folder_path = "resource")]
 pub struct Adapter {
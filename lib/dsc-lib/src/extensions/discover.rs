@@ -128,6 +128,10 @@ impl DscExtension {
                             // Currently we don't support extensions discovering other extensions
                             let manifests = match load_manifest(&manifest_path) {
                                 Ok(manifests) => manifests,
+                                Err(DscError::Untrusted(reason)) => {
+                                    warn!("{}", t!("extensions.dscextension.untrustedManifest", extension = self.type_name, path = manifest_path.display(), reason = reason));
+                                    continue;
+                                },
                                 Err(err) => {
                                     info!("{}", t!("extensions.dscextension.failedLoadManifest", extension = self.type_name, err = err));
                                     continue;
@@ -1,6 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use crate::extensions::function::ExtensionFunction;
 use crate::extensions::import::ImportMethod;
 use crate::schemas::{dsc_repo::DscRepoSchema, transforms::idiomaticize_string_enum};
 use crate::types::{FullyQualifiedTypeName, SemanticVersion};
@@ -35,6 +36,9 @@ pub struct DscExtension {
     pub author: Option<String>,
     /// The manifest of the extension.
     pub manifest: Value,
+    /// The functions provided by the extension, parsed once when the manifest is loaded.
+    #[serde(skip)]
+    pub functions: Vec<ExtensionFunction>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
@@ -48,6 +52,8 @@ pub enum Capability {
     Secret,
     /// The extension imports configuration from a different format.
     Import,
+    /// The extension provides functions for expressions.
+    Function,
 }
 
 impl Display for Capability {
@@ -56,6 +62,7 @@ impl Display for Capability {
             Capability::Discover => write!(f, "Discover"),
             Capability::Secret => write!(f, "Secret"),
             Capability::Import => write!(f, "Import"),
+            Capability::Function => write!(f, "Function"),
         }
     }
 }
@@ -74,6 +81,7 @@ impl DscExtension {
             directory: PathBuf::new(),
             author: None,
             manifest: Value::Null,
+            functions: Vec::new(),
         }
     }
 }
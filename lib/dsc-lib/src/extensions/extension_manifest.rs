@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 use crate::dscerror::DscError;
-use crate::extensions::{discover::DiscoverMethod, import::ImportMethod, secret::SecretMethod};
+use crate::extensions::{discover::DiscoverMethod, function::FunctionMethod, import::ImportMethod, secret::SecretMethod};
 use crate::schemas::dsc_repo::DscRepoSchema;
 use crate::types::{ExitCodesMap, FullyQualifiedTypeName, SemanticVersion, TagList};
 
@@ -53,6 +53,9 @@ pub struct ExtensionManifest {
     pub import_parameters: Option<ImportMethod>,
     /// Details how to call the Secret method of the extension.
     pub secret: Option<SecretMethod>,
+    /// Details how to call the functions provided by the extension.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionMethod>,
     /// Mapping of exit codes to descriptions.  Zero is always success and non-zero is always failure.
     #[serde(skip_serializing_if = "ExitCodesMap::is_empty_or_default", default)]
     pub exit_codes: ExitCodesMap,
@@ -0,0 +1,186 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::{
+    dscerror::DscError,
+    dscresources::command_resource::invoke_command,
+    extensions::{
+        dscextension::{
+            Capability,
+            DscExtension,
+        },
+        extension_manifest::ExtensionManifest,
+    },
+    functions::{FunctionArgKind, FunctionCategory, FunctionMetadata},
+    schemas::dsc_repo::DscRepoSchema
+};
+
+use rust_i18n::t;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tracing::{debug, warn};
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
+#[serde(deny_unknown_fields)]
+#[dsc_repo_schema(base_name = "manifest.function", folder_path = "extension")]
+pub struct FunctionMethod {
+    /// The command to run to invoke a function.
+    pub executable: String,
+    /// The arguments to pass to the command to invoke a function.
+    pub args: Option<Vec<FunctionMethodArgKind>>,
+    /// The functions provided by the extension.
+    pub functions: Vec<ExtensionFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FunctionMethodArgKind {
+    /// The argument is a string.
+    String(String),
+    /// The argument accepts the function name.
+    Name {
+        /// The argument that accepts the function name.
+        #[serde(rename = "nameArg")]
+        name_arg: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ExtensionFunction {
+    /// The namespaced name of the function, such as `contoso.hash`.
+    pub name: String,
+    /// The description of the function.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The syntax of the function.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub syntax: Option<String>,
+    /// The minimum number of arguments the function accepts.
+    pub min_args: usize,
+    /// The maximum number of arguments the function accepts.
+    pub max_args: usize,
+    /// The accepted types for each argument by position.
+    #[serde(default)]
+    pub accepted_arg_ordered_types: Vec<Vec<FunctionArgKind>>,
+    /// The accepted types for arguments after those in `acceptedArgOrderedTypes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_arg_accepted_types: Option<Vec<FunctionArgKind>>,
+    /// The types the function can return.
+    pub return_types: Vec<FunctionArgKind>,
+}
+
+impl ExtensionFunction {
+    /// Get the metadata used by the function dispatcher to validate arguments.
+    #[must_use]
+    pub fn get_metadata(&self) -> FunctionMetadata {
+        FunctionMetadata {
+            name: self.name.clone(),
+            description: self.description.clone().unwrap_or_default(),
+            syntax: self.syntax.clone().unwrap_or_default(),
+            constraints: None,
+            category: vec![FunctionCategory::Extension],
+            min_args: self.min_args,
+            max_args: self.max_args,
+            accepted_arg_ordered_types: self.accepted_arg_ordered_types.clone(),
+            remaining_arg_accepted_types: self.remaining_arg_accepted_types.clone(),
+            return_types: self.return_types.clone(),
+        }
+    }
+}
+
+impl DscExtension {
+    /// Get the functions provided by the extension.
+    ///
+    /// # Returns
+    ///
+    /// The functions declared in the manifest or an empty list if the extension doesn't support the function capability.
+    #[must_use]
+    pub fn get_functions(&self) -> &[ExtensionFunction] {
+        if !self.capabilities.contains(&Capability::Function) {
+            return &[];
+        }
+        &self.functions
+    }
+
+    /// Invoke a function provided by the extension.
+    ///
+    /// The arguments are passed to the command as a JSON object with `name` and `args` properties
+    /// on stdin and the command is expected to write the JSON result to stdout.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function to invoke.
+    /// * `args` - The arguments to the function.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the value returned by the function.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the command fails, returns invalid JSON, or if the extension does not support the function capability.
+    pub fn invoke_function(&self, name: &str, args: &[Value]) -> Result<Value, DscError> {
+        if !self.capabilities.contains(&Capability::Function) {
+            return Err(DscError::UnsupportedCapability(self.type_name.to_string(), Capability::Function.to_string()));
+        }
+
+        debug!("{}", t!("extensions.dscextension.invokingFunction", name = name, extension = self.type_name));
+        let extension = match serde_json::from_value::<ExtensionManifest>(self.manifest.clone()) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                return Err(DscError::Manifest(self.type_name.to_string(), err));
+            }
+        };
+        let Some(function) = extension.function else {
+            return Err(DscError::UnsupportedCapability(self.type_name.to_string(), Capability::Function.to_string()));
+        };
+        if let Some(deprecation_message) = extension.deprecation_message.as_ref() {
+            warn!("{}", t!("extensions.dscextension.deprecationMessage", extension = self.type_name, message = deprecation_message));
+        }
+        let command_args = process_function_args(function.args.as_ref(), name);
+        let input = json!({
+            "name": name,
+            "args": args,
+        }).to_string();
+        let (_exit_code, stdout, _stderr) = invoke_command(
+            &function.executable,
+            command_args,
+            Some(&input),
+            Some(&self.directory),
+            None,
+            extension.exit_codes.as_ref(),
+        )?;
+        let stdout = stdout.trim();
+        if stdout.is_empty() {
+            return Err(DscError::Function(name.to_string(), t!("extensions.dscextension.functionNoResult", extension = self.type_name).to_string()));
+        }
+        match serde_json::from_str::<Value>(stdout) {
+            Ok(result) => Ok(result),
+            Err(err) => Err(DscError::Function(name.to_string(), t!("extensions.dscextension.functionInvalidResult", extension = self.type_name, err = err).to_string())),
+        }
+    }
+}
+
+fn process_function_args(args: Option<&Vec<FunctionMethodArgKind>>, name: &str) -> Option<Vec<String>> {
+    let Some(arg_values) = args else {
+        debug!("{}", t!("dscresources.commandResource.noArgs"));
+        return None;
+    };
+
+    let mut processed_args = Vec::<String>::new();
+    for arg in arg_values {
+        match arg {
+            FunctionMethodArgKind::String(s) => {
+                processed_args.push(s.clone());
+            },
+            FunctionMethodArgKind::Name { name_arg } => {
+                processed_args.push(name_arg.to_string());
+                processed_args.push(name.to_string());
+            },
+        }
+    }
+
+    Some(processed_args)
+}
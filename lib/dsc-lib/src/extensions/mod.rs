@@ -4,5 +4,6 @@
 pub mod discover;
 pub mod dscextension;
 pub mod extension_manifest;
+pub mod function;
 pub mod import;
 pub mod secret;
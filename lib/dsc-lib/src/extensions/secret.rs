@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use crate::{
-    configure::parameters::SecureString,
+    configure::{config_doc::SecretDirective, parameters::SecureString},
     dscerror::DscError,
     dscresources::{
         command_resource::invoke_command,
@@ -14,12 +14,16 @@ use crate::{
         },
         extension_manifest::ExtensionManifest,
     },
-    schemas::dsc_repo::DscRepoSchema
+    schemas::dsc_repo::DscRepoSchema,
+    scrub::register_secure_value,
+    types::FullyQualifiedTypeName,
 };
 
 use rust_i18n::t;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Debug};
 use tracing::{debug, warn};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
@@ -115,6 +119,141 @@ impl DscExtension {
     }
 }
 
+/// Counts of the secrets retrieved during an operation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretMetrics {
+    /// The number of secrets requested.
+    pub requests: u32,
+    /// The number of requests returned from the cache.
+    pub cache_hits: u32,
+    /// The number of times an extension was called to retrieve a secret.
+    pub provider_calls: u32,
+}
+
+/// Secrets retrieved during an operation, so each secret is only retrieved from the extensions once.
+#[derive(Clone, Default)]
+pub struct SecretCache {
+    secrets: HashMap<(String, Option<String>), SecureString>,
+    default_vault: Option<String>,
+    provider_order: Vec<FullyQualifiedTypeName>,
+    metrics: SecretMetrics,
+}
+
+// the cached values are secrets so they are never included
+impl Debug for SecretCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretCache")
+            .field("secrets", &self.secrets.len())
+            .field("default_vault", &self.default_vault)
+            .field("provider_order", &self.provider_order)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl SecretCache {
+    /// Create a cache using the options of the `secrets` directive.
+    ///
+    /// # Arguments
+    ///
+    /// * `directive` - The `secrets` directive of the configuration, if any.
+    #[must_use]
+    pub fn new(directive: Option<&SecretDirective>) -> Self {
+        Self {
+            default_vault: directive.and_then(|directive| directive.default_vault.clone()),
+            provider_order: directive.and_then(|directive| directive.provider_order.clone()).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Get the counts of the secrets retrieved so far.
+    #[must_use]
+    pub fn metrics(&self) -> &SecretMetrics {
+        &self.metrics
+    }
+
+    /// Retrieve a secret from the cache or the extensions supporting secrets.
+    ///
+    /// If no vault is specified, the default vault is used. If no provider order is set, every
+    /// extension is queried and the secret must have the same value in each extension that returns it.
+    /// Otherwise, the extensions are queried in the provider order, followed by any other extensions,
+    /// and the first one returning the secret is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `extensions` - The discovered extensions.
+    /// * `name` - The name of the secret to retrieve.
+    /// * `vault` - An optional vault name to use for the secret.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no extension supports secrets, the secret isn't found, or
+    /// extensions return different values for the secret.
+    pub fn get_secret(&mut self, extensions: &[DscExtension], name: &str, vault: Option<&str>) -> Result<SecureString, DscError> {
+        self.metrics.requests += 1;
+        let vault = vault.or(self.default_vault.as_deref()).map(str::to_string);
+        let key = (name.to_string(), vault);
+        if let Some(secret) = self.secrets.get(&key) {
+            debug!("{}", t!("extensions.secret.cachedSecret", name = name));
+            self.metrics.cache_hits += 1;
+            return Ok(secret.clone());
+        }
+
+        let secret = self.retrieve_secret(extensions, name, key.1.as_deref())?;
+        register_secure_value(&Value::String(secret.secure_string.clone()));
+        self.secrets.insert(key, secret.clone());
+        Ok(secret)
+    }
+
+    fn retrieve_secret(&mut self, extensions: &[DscExtension], name: &str, vault: Option<&str>) -> Result<SecureString, DscError> {
+        let mut extensions = extensions.iter()
+            .filter(|ext| ext.capabilities.contains(&Capability::Secret))
+            .collect::<Vec<_>>();
+        if extensions.is_empty() {
+            return Err(DscError::Extension(t!("extensions.secret.noExtensions").to_string()));
+        }
+
+        let ordered = !self.provider_order.is_empty();
+        if ordered {
+            for provider in &self.provider_order {
+                if !extensions.iter().any(|ext| ext.type_name == *provider) {
+                    warn!("{}", t!("extensions.secret.providerNotFound", provider = provider));
+                }
+            }
+            // extensions not in the order keep their discovery order after the ordered ones
+            extensions.sort_by_key(|ext| self.provider_order.iter().position(|provider| ext.type_name == *provider).unwrap_or(usize::MAX));
+        }
+
+        let mut result: Option<String> = None;
+        for extension in extensions {
+            self.metrics.provider_calls += 1;
+            match extension.secret(name, vault) {
+                Ok(Some(secret_value)) => {
+                    if result.as_ref().is_some_and(|result| *result != secret_value) {
+                        return Err(DscError::Extension(t!("extensions.secret.multipleSecrets", name = name).to_string()));
+                    }
+                    result = Some(secret_value);
+                    if ordered {
+                        debug!("{}", t!("extensions.secret.secretFromProvider", name = name, extension = extension.type_name));
+                        break;
+                    }
+                },
+                Ok(None) => {},
+                Err(err) => {
+                    warn!("{}", t!("extensions.secret.extensionReturnedError", extension = extension.type_name.clone(), error = err));
+                }
+            }
+        }
+
+        let Some(result) = result else {
+            return Err(DscError::Extension(t!("extensions.secret.secretNotFound", name = name).to_string()));
+        };
+        serde_json::from_str::<SecureString>(&result)
+            .map_err(|_err| DscError::Extension(t!("extensions.secret.invalidSecretFormat", name = name).to_string()))
+    }
+}
+
 fn process_secret_args(args: Option<&Vec<SecretArgKind>>, name: &str, vault: Option<&str>) -> Option<Vec<String>> {
     let Some(arg_values) = args else {
         debug!("{}", t!("dscresources.commandResource.noArgs"));
@@ -142,3 +281,34 @@ fn process_secret_args(args: Option<&Vec<SecretArgKind>>, name: &str, vault: Opt
 
     Some(processed_args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_secret_is_not_retrieved_again() {
+        let mut cache = SecretCache::default();
+        cache.secrets.insert(("MySecret".to_string(), None), SecureString { secure_string: "Hello".to_string() });
+        let secret = cache.get_secret(&[], "MySecret", None).unwrap();
+        assert_eq!(secret.secure_string, "Hello");
+        assert_eq!(cache.metrics(), &SecretMetrics { requests: 1, cache_hits: 1, provider_calls: 0 });
+    }
+
+    #[test]
+    fn default_vault_is_used_without_vault() {
+        let directive = SecretDirective { default_vault: Some("Vault1".to_string()), provider_order: None };
+        let mut cache = SecretCache::new(Some(&directive));
+        cache.secrets.insert(("MySecret".to_string(), Some("Vault1".to_string())), SecureString { secure_string: "Hello".to_string() });
+        assert!(cache.get_secret(&[], "MySecret", None).is_ok());
+        assert!(cache.get_secret(&[], "MySecret", Some("Vault2")).is_err());
+        assert_eq!(cache.metrics().cache_hits, 1);
+    }
+
+    #[test]
+    fn debug_does_not_include_secrets() {
+        let mut cache = SecretCache::default();
+        cache.secrets.insert(("MySecret".to_string(), None), SecureString { secure_string: "Hello".to_string() });
+        assert!(!format!("{cache:?}").contains("Hello"));
+    }
+}
@@ -5,6 +5,8 @@ use std::collections::HashMap;
 
 use crate::DscError;
 use crate::configure::context::{Context, ProcessMode};
+use crate::extensions::dscextension::DscExtension;
+use crate::extensions::function::ExtensionFunction;
 use crate::functions::user_function::invoke_user_function;
 use crate::schemas::dsc_repo::DscRepoSchema;
 use rust_i18n::t;
@@ -65,6 +67,7 @@ pub mod not;
 pub mod null;
 pub mod object_keys;
 pub mod or;
+pub mod outputs;
 pub mod parameters;
 pub mod parse_cidr;
 pub mod path;
@@ -100,7 +103,7 @@ pub mod variables;
 pub mod try_which;
 
 /// The kind of argument that a function accepts.
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, JsonSchema, DscRepoSchema)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Deserialize, Serialize, JsonSchema, DscRepoSchema)]
 #[dsc_repo_schema(base_name = "argKind", folder_path = "definitions/functions/builtin")]
 #[serde(rename_all = "camelCase")]
 pub enum FunctionArgKind {
@@ -217,6 +220,7 @@ impl FunctionDispatcher {
             Box::new(null::Null{}),
             Box::new(object_keys::ObjectKeys{}),
             Box::new(or::Or{}),
+            Box::new(outputs::Outputs{}),
             Box::new(parameters::Parameters{}),
             Box::new(parse_cidr::ParseCidr{}),
             Box::new(path::Path{}),
@@ -271,15 +275,36 @@ impl FunctionDispatcher {
     /// This function will return an error if the function fails to execute.
     pub fn invoke(&self, name: &str, args: &[Value], context: &Context) -> Result<Value, DscError> {
         let Some(function) = self.functions.get(name) else {
-            // if function name contains a period, it might be a user function
+            // if function name contains a period, it might be a user function or provided by an extension
             if name.contains('.') {
+                if !context.user_functions.contains_key(name)
+                    && let Some((extension, extension_function)) = find_extension_function(name, context) {
+                        Self::validate_args(name, args, &extension_function.get_metadata())?;
+                        let result = extension.invoke_function(name, args)?;
+                        Self::check_return_type(name, &result, &extension_function.return_types)?;
+                        return Ok(result);
+                    }
                 return invoke_user_function(name, args, context);
             }
             return Err(DscError::Parser(t!("functions.unknownFunction", name = name).to_string()));
         };
 
         let metadata = function.get_metadata();
+        Self::validate_args(name, args, &metadata)?;
 
+        let accepts_lambda = metadata.accepted_arg_ordered_types.iter().any(|types| types.contains(&FunctionArgKind::Lambda))
+            || metadata.remaining_arg_accepted_types.as_ref().is_some_and(|types| types.contains(&FunctionArgKind::Lambda));
+
+        if accepts_lambda {
+            let mut lambda_context = context.clone();
+            lambda_context.process_mode = ProcessMode::Lambda;
+            function.invoke(args, &lambda_context)
+        } else {
+            function.invoke(args, context)
+        }
+    }
+
+    fn validate_args(name: &str, args: &[Value], metadata: &FunctionMetadata) -> Result<(), DscError> {
         // check if arg number are valid
         let min_args = metadata.min_args;
         let max_args = metadata.max_args;
@@ -311,17 +336,7 @@ impl FunctionDispatcher {
                 Self::check_arg_against_expected_types(name, value, remaining_arg_types)?;
             }
         }
-
-        let accepts_lambda = metadata.accepted_arg_ordered_types.iter().any(|types| types.contains(&FunctionArgKind::Lambda))
-            || metadata.remaining_arg_accepted_types.as_ref().is_some_and(|types| types.contains(&FunctionArgKind::Lambda));
-
-        if accepts_lambda {
-            let mut lambda_context = context.clone();
-            lambda_context.process_mode = ProcessMode::Lambda;
-            function.invoke(args, &lambda_context)
-        } else {
-            function.invoke(args, context)
-        }
+        Ok(())
     }
 
     fn check_arg_against_expected_types(name: &str, arg: &Value, expected_types: &[FunctionArgKind]) -> Result<(), DscError> {
@@ -345,6 +360,47 @@ impl FunctionDispatcher {
         Ok(())
     }
 
+    fn check_return_type(name: &str, result: &Value, return_types: &[FunctionArgKind]) -> Result<(), DscError> {
+        let result_type = match result {
+            Value::Array(_) => FunctionArgKind::Array,
+            Value::Bool(_) => FunctionArgKind::Boolean,
+            Value::Null => FunctionArgKind::Null,
+            Value::Number(_) => FunctionArgKind::Number,
+            Value::Object(_) => FunctionArgKind::Object,
+            Value::String(_) => FunctionArgKind::String,
+        };
+        if !return_types.contains(&result_type) {
+            return Err(DscError::Function(name.to_string(), t!("functions.invalidReturnType", result_type = result_type, return_types = return_types.iter().map(std::string::ToString::to_string).collect::<Vec<_>>().join(", ")).to_string()));
+        }
+        Ok(())
+    }
+
+    /// List the functions provided by extensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `extensions` - The discovered extensions.
+    #[must_use]
+    pub fn list_extension_functions(extensions: &[DscExtension]) -> Vec<FunctionDefinition> {
+        extensions.iter()
+            .flat_map(DscExtension::get_functions)
+            .map(|function| {
+                let metadata = function.get_metadata();
+                FunctionDefinition {
+                    category: metadata.category,
+                    name: metadata.name,
+                    description: metadata.description,
+                    syntax: metadata.syntax,
+                    constraints: metadata.constraints,
+                    min_args: metadata.min_args,
+                    max_args: metadata.max_args,
+                    accepted_arg_ordered_types: metadata.accepted_arg_ordered_types,
+                    remaining_arg_accepted_types: metadata.remaining_arg_accepted_types,
+                    return_types: metadata.return_types,
+                }
+            }).collect()
+    }
+
     #[must_use]
     pub fn list(&self) -> Vec<FunctionDefinition> {
         self.functions.iter().map(|(name, function)| {
@@ -365,6 +421,25 @@ impl FunctionDispatcher {
     }
 }
 
+/// Find the extension that provides a function.
+///
+/// # Arguments
+///
+/// * `name` - The namespaced name of the function.
+/// * `context` - The context containing the discovered extensions.
+///
+/// # Returns
+///
+/// The extension and its definition of the function if an extension provides it.
+fn find_extension_function<'a>(name: &str, context: &'a Context) -> Option<(&'a DscExtension, &'a ExtensionFunction)> {
+    context.extensions.iter()
+        .find_map(|extension| {
+            extension.get_functions().iter()
+                .find(|function| function.name == name)
+                .map(|function| (extension, function))
+        })
+}
+
 impl Default for FunctionDispatcher {
     fn default() -> Self {
         Self::new()
@@ -401,6 +476,7 @@ pub enum FunctionCategory {
     Comparison,
     Date,
     Deployment,
+    Extension,
     Lambda,
     Logical,
     Numeric,
@@ -418,6 +494,7 @@ impl Display for FunctionCategory {
             FunctionCategory::Comparison => write!(f, "Comparison"),
             FunctionCategory::Date => write!(f, "Date"),
             FunctionCategory::Deployment => write!(f, "Deployment"),
+            FunctionCategory::Extension => write!(f, "Extension"),
             FunctionCategory::Lambda => write!(f, "Lambda"),
             FunctionCategory::Logical => write!(f, "Logical"),
             FunctionCategory::Numeric => write!(f, "Numeric"),
@@ -431,12 +508,13 @@ impl Display for FunctionCategory {
 
 impl FunctionCategory {
     /// All defined function categories.
-    pub const ALL: [FunctionCategory; 12] = [
+    pub const ALL: [FunctionCategory; 13] = [
         FunctionCategory::Array,
         FunctionCategory::Cidr,
         FunctionCategory::Comparison,
         FunctionCategory::Date,
         FunctionCategory::Deployment,
+        FunctionCategory::Extension,
         FunctionCategory::Lambda,
         FunctionCategory::Logical,
         FunctionCategory::Numeric,
@@ -457,6 +535,7 @@ impl std::str::FromStr for FunctionCategory {
             "comparison" => Ok(FunctionCategory::Comparison),
             "date" => Ok(FunctionCategory::Date),
             "deployment" => Ok(FunctionCategory::Deployment),
+            "extension" => Ok(FunctionCategory::Extension),
             "lambda" => Ok(FunctionCategory::Lambda),
             "logical" => Ok(FunctionCategory::Logical),
             "numeric" => Ok(FunctionCategory::Numeric),
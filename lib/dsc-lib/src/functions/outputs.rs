@@ -0,0 +1,71 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::DscError;
+use crate::configure::context::{Context, ProcessMode};
+use crate::functions::{FunctionArgKind, Function, FunctionCategory, FunctionMetadata};
+use rust_i18n::t;
+use serde_json::Value;
+use tracing::debug;
+
+#[derive(Debug, Default)]
+pub struct Outputs {}
+
+impl Function for Outputs {
+    fn get_metadata(&self) -> FunctionMetadata {
+        FunctionMetadata {
+            name: "outputs".to_string(),
+            description: t!("functions.outputs.description").to_string(),
+            syntax: t!("functions.outputs.syntax").to_string(),
+            constraints: None,
+            category: vec![FunctionCategory::Deployment],
+            min_args: 1,
+            max_args: 1,
+            accepted_arg_ordered_types: vec![vec![FunctionArgKind::String]],
+            remaining_arg_accepted_types: None,
+            return_types: vec![FunctionArgKind::Object],
+        }
+    }
+
+    fn invoke(&self, args: &[Value], context: &Context) -> Result<Value, DscError> {
+        debug!("{}", t!("functions.outputs.invoked"));
+        if context.process_mode == ProcessMode::UserFunction {
+            return Err(DscError::Parser(t!("functions.outputs.unavailableInUserFunction").to_string()));
+        }
+
+        if let Some(name) = args[0].as_str() {
+            if let Some(outputs) = context.configuration_outputs.get(name) {
+                Ok(Value::Object(outputs.clone()))
+            } else {
+                Err(DscError::Parser(t!("functions.outputs.nameNotFound", name = name).to_string()))
+            }
+        } else {
+            Err(DscError::Parser(t!("functions.invalidArguments").to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configure::context::Context;
+    use crate::parser::Statement;
+    use serde_json::{Map, Value};
+
+    #[test]
+    fn valid_outputs() {
+        let mut parser = Statement::new().unwrap();
+        let mut context = Context::new();
+        let mut outputs = Map::new();
+        outputs.insert("port".to_string(), Value::from(8080));
+        context.configuration_outputs.insert("inc".to_string(), outputs);
+        let result = parser.parse_and_execute("[outputs('inc').port]", &context).unwrap();
+        assert_eq!(result, 8080);
+    }
+
+    #[test]
+    fn invalid_name() {
+        let mut parser = Statement::new().unwrap();
+        let result = parser.parse_and_execute("[outputs('inc')]", &Context::new());
+        assert!(result.is_err());
+    }
+}
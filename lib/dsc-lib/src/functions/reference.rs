@@ -5,7 +5,7 @@ use crate::DscError;
 use crate::configure::context::{Context, ProcessMode};
 use crate::functions::{FunctionArgKind, Function, FunctionCategory, FunctionMetadata};
 use rust_i18n::t;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::debug;
 
 #[derive(Debug, Default)]
@@ -43,6 +43,9 @@ impl Function for Reference {
         if let Some(key) = args[0].as_str() {
             if context.references.contains_key(key) {
                 Ok(context.references[key].clone())
+            } else if let Some(outputs) = context.configuration_outputs.get(key) {
+                // an included configuration can be referenced by name to access its outputs
+                Ok(json!({ "outputs": outputs }))
             } else {
                 Err(DscError::Parser(t!("functions.reference.keyNotFound", key = key).to_string()))
             }
@@ -66,6 +69,17 @@ mod tests {
         assert_eq!(result, "baz");
     }
 
+    #[test]
+    fn included_configuration_outputs() {
+        let mut parser = Statement::new().unwrap();
+        let mut context = Context::new();
+        let mut outputs = serde_json::Map::new();
+        outputs.insert("port".to_string(), 8080.into());
+        context.configuration_outputs.insert("inc".to_string(), outputs);
+        let result = parser.parse_and_execute("[reference('inc').outputs.port]", &context).unwrap();
+        assert_eq!(result, 8080);
+    }
+
     #[test]
     fn invalid_resourceid() {
         let mut parser = Statement::new().unwrap();
@@ -2,13 +2,11 @@
 // Licensed under the MIT License.
 
 use crate::DscError;
-use crate::configure::{context::Context, parameters::SecureString};
-use crate::extensions::dscextension::Capability;
+use crate::configure::context::Context;
 use crate::functions::{FunctionArgKind, FunctionCategory, FunctionMetadata};
 use rust_i18n::t;
 use serde_json::Value;
 use super::Function;
-use tracing::warn;
 
 #[derive(Debug, Default)]
 pub struct Secret {}
@@ -42,38 +40,12 @@ impl Function for Secret {
             None
         };
 
-        // we query all extensions supporting the secret method to see if any of them can provide the secret.
-        // if none can or if multiple provide different values, we return an error.
-        let extensions = context.extensions.iter()
-            .filter(|ext| ext.capabilities.contains(&Capability::Secret))
-            .collect::<Vec<_>>();
-        let mut secret_returned = false;
-        let mut result: String = String::new();
-        if extensions.is_empty() {
-            return Err(DscError::Function("secret".to_string(), t!("functions.secret.noExtensions").to_string()));
-        }
-        for extension in extensions {
-            match extension.secret(&secret_name, vault_name.as_deref()) {
-                Ok(secret_result) => {
-                    if let Some(secret_value) = secret_result {
-                        if secret_returned && result != secret_value {
-                            return Err(DscError::Function("secret".to_string(), t!("functions.secret.multipleSecrets", name = secret_name.clone()).to_string()));
-                        }
-                        result = secret_value;
-                        secret_returned = true;
-                    }
-                },
-                Err(err) => {
-                    warn!("{}", t!("functions.secret.extensionReturnedError", extension = extension.type_name.clone(), error = err));
-                }
-            }
-        }
-        if secret_returned {
-            let secure_string = serde_json::from_str::<SecureString>(&result).map_err(|_err| DscError::Function("secret".to_string(), t!("functions.secret.invalidSecretFormat", name = secret_name.clone()).to_string()))?;
-            Ok(serde_json::to_value(secure_string)?)
-        } else {
-            Err(DscError::Function("secret".to_string(), t!("functions.secret.secretNotFound", name = secret_name).to_string()))
-        }
+        // extensions supporting secrets are queried to see if any of them can provide the secret unless it was already retrieved
+        let secure_string = context.secrets.borrow_mut().get_secret(&context.extensions, &secret_name, vault_name.as_deref()).map_err(|err| match err {
+            DscError::Extension(message) => DscError::Function("secret".to_string(), message),
+            err => err,
+        })?;
+        Ok(serde_json::to_value(secure_string)?)
     }
 }
 
@@ -8,8 +8,9 @@ use crate::types::TypeNameFilter;
 
 use configure::config_doc::ExecutionKind;
 use dscerror::DscError;
-use dscresources::{dscresource::{DscResource, Invoke}, invoke_result::{GetResult, SetResult, TestResult}};
+use dscresources::{dscresource::{DscResource, Invoke}, invoke_result::{GetResult, SetResult, TestResult}, native_resource::NativeResource};
 use rust_i18n::i18n;
+use std::sync::Arc;
 
 pub mod configure;
 pub mod discovery;
@@ -19,6 +20,7 @@ pub mod extensions;
 pub mod functions;
 pub mod parser;
 pub mod progress;
+pub mod scrub;
 pub mod types;
 pub mod util;
 
@@ -68,6 +70,19 @@ impl DscManager {
     pub fn find_resources(&mut self, required_resource_types: &[DiscoveryFilter], progress_format: ProgressFormat) -> Result<(), DscError> {
         self.discovery.find_resources(required_resource_types, progress_format)
     }
+
+    /// Register a resource implemented in-process so it's found and invoked without a child process.
+    ///
+    /// The registration only applies to this `DscManager` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource to register.
+    ///
+    pub fn register_native_resource(&mut self, resource: Arc<dyn NativeResource>) {
+        self.discovery.register_native_resource(resource);
+    }
+
     /// Invoke the get operation on a resource.
     ///
     /// # Arguments
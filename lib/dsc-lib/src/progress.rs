@@ -2,6 +2,13 @@
 // Licensed under the MIT License.
 
 use crate::DscError;
+use crate::configure::config_progress::{
+    ConfigurationProgressListener,
+    ConfigurationResourceCompletedEvent,
+    ConfigurationResourceCompletionStatus,
+    ConfigurationResourceStartedEvent,
+};
+use crate::scrub::scrub;
 use crate::types::FullyQualifiedTypeName;
 
 use clap::ValueEnum;
@@ -9,6 +16,7 @@ use indicatif::ProgressStyle;
 use rust_i18n::t;
 use serde::Serialize;
 use serde_json::Value;
+use std::sync::Arc;
 use tracing_indicatif::span_ext::IndicatifSpanExt;
 use tracing::{trace, warn_span};
 use tracing::span::Span;
@@ -76,7 +84,8 @@ impl Progress {
 pub struct ProgressBar {
     progress_value:  Progress,
     console_bar: Span,
-    format: ProgressFormat
+    format: ProgressFormat,
+    listener: Option<Arc<dyn ConfigurationProgressListener>>,
 }
 
 impl ProgressBar {
@@ -109,10 +118,21 @@ impl ProgressBar {
         Ok(ProgressBar {
             progress_value: Progress::new(total_items),
             console_bar: bar,
-            format
+            format,
+            listener: None,
         })
     }
 
+    /// Set a listener that receives an event when each resource starts and completes
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to notify, this is independent of the `ProgressFormat`
+    ///
+    pub fn set_listener(&mut self, listener: Option<Arc<dyn ConfigurationProgressListener>>) {
+        self.listener = listener;
+    }
+
     /// Increment the progress bar by the specified amount and write the progress
     ///
     /// # Arguments
@@ -120,11 +140,26 @@ impl ProgressBar {
     /// * `delta` - The amount to increment the progress bar by
     ///
     pub fn write_increment(&mut self, delta: u64) {
+        self.progress_value.completed_items += delta;
+        if let Some(listener) = &self.listener
+            && let Some(resource) = &self.progress_value.resource_name {
+            let (status, errors) = match &self.progress_value.failure {
+                Some(failure) => (ConfigurationResourceCompletionStatus::Failure, Some(vec![failure.message.clone()])),
+                None => (ConfigurationResourceCompletionStatus::Success, None),
+            };
+            let event = ConfigurationResourceCompletedEvent {
+                resource: resource.clone(),
+                parent: None,
+                status,
+                errors,
+            };
+            listener.resource_completed(&event, &self.progress_value);
+        }
+
         if self.format == ProgressFormat::None {
             return;
         }
 
-        self.progress_value.completed_items += delta;
         if self.format == ProgressFormat::Json {
             self.write_json();
         } else {
@@ -145,6 +180,13 @@ impl ProgressBar {
         self.progress_value.resource_type = Some(resource_type.clone());
         self.progress_value.result = None;
         self.progress_value.failure = None;
+        if let Some(listener) = &self.listener {
+            let event = ConfigurationResourceStartedEvent {
+                resource: name.to_string(),
+                parent: None,
+            };
+            listener.resource_started(&event, &self.progress_value);
+        }
     }
 
     /// Set the result of the operation. This will clear any error.
@@ -204,9 +246,51 @@ impl ProgressBar {
 
     fn write_json(&mut self) {
         if let Ok(json) = serde_json::to_string(&self.progress_value) {
-            eprintln!("{json}");
+            eprintln!("{}", scrub(&json));
         } else {
             trace!("{}", t!("progress.failedToSerialize", json = self.progress_value : {:?}));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingListener {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ConfigurationProgressListener for RecordingListener {
+        fn resource_started(&self, event: &ConfigurationResourceStartedEvent, progress: &Progress) {
+            self.events.lock().unwrap().push(format!("started {} {}/{}", event.resource, progress.completed_items, progress.total_items));
+        }
+
+        fn resource_completed(&self, event: &ConfigurationResourceCompletedEvent, progress: &Progress) {
+            self.events.lock().unwrap().push(format!("completed {} {:?} {}/{}", event.resource, event.status, progress.completed_items, progress.total_items));
+        }
+    }
+
+    #[test]
+    fn listener_receives_resource_events() {
+        let listener = Arc::new(RecordingListener::default());
+        let mut progress = ProgressBar::new(2, ProgressFormat::None).unwrap();
+        progress.set_listener(Some(listener.clone()));
+
+        let resource_type = "Test/Echo".parse::<FullyQualifiedTypeName>().unwrap();
+        progress.set_resource("first", &resource_type);
+        progress.write_increment(1);
+        progress.set_resource("second", &resource_type);
+        progress.set_failure(Some(Failure { message: "failed".to_string(), exit_code: 1 }));
+        progress.write_increment(1);
+
+        assert_eq!(*listener.events.lock().unwrap(), vec![
+            "started first 0/2",
+            "completed first Success 1/2",
+            "started second 1/2",
+            "completed second Failure 2/2",
+        ]);
+    }
+}
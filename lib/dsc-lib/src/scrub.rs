@@ -0,0 +1,205 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::configure::parameters::SECURE_VALUE_REDACTED;
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    sync::{LazyLock, Mutex, PoisonError, RwLock},
+};
+
+/// The environment variable that disables scrubbing secure values from results, traces are always scrubbed.
+pub const DSC_SHOW_SECURE_VALUES: &str = "DSC_SHOW_SECURE_VALUES";
+
+/// Secure values shorter than this aren't scrubbed as they would match unrelated text.
+const MIN_SCRUB_LENGTH: usize = 4;
+
+/// The plaintext of the secure values used by this process, longest first.
+static SECURE_VALUES: LazyLock<RwLock<Vec<String>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// The number of operations in progress within a `SecureValueScope`.
+static ACTIVE_SCOPES: Mutex<usize> = Mutex::new(0);
+
+/// Keeps the secure values registered during an operation until the operation completes.
+///
+/// Long running processes like the MCP server and the agent run many operations, so the registered
+/// values are cleared once the last operation in progress drops its scope.  Values registered by
+/// operations running concurrently are kept until all of them complete.
+pub struct SecureValueScope {
+    _private: (),
+}
+
+impl SecureValueScope {
+    /// Start a scope for an operation.
+    #[must_use]
+    pub fn new() -> Self {
+        *ACTIVE_SCOPES.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        Self { _private: () }
+    }
+}
+
+impl Default for SecureValueScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SecureValueScope {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_SCOPES.lock().unwrap_or_else(PoisonError::into_inner);
+        *active = active.saturating_sub(1);
+        if *active == 0 {
+            SECURE_VALUES.write().unwrap_or_else(PoisonError::into_inner).clear();
+        }
+    }
+}
+
+fn add_secure_value(value: &str) {
+    if value.chars().count() < MIN_SCRUB_LENGTH {
+        return;
+    }
+
+    // the value can also appear escaped within JSON
+    let mut candidates = vec![value.to_string()];
+    if let Ok(json) = serde_json::to_string(value) {
+        let escaped = &json[1..json.len() - 1];
+        if escaped != value {
+            candidates.push(escaped.to_string());
+        }
+    }
+
+    let mut values = SECURE_VALUES.write().unwrap_or_else(PoisonError::into_inner);
+    for candidate in candidates {
+        if !values.contains(&candidate) {
+            values.push(candidate);
+        }
+    }
+    // replace longer values first so a value containing another is fully scrubbed
+    values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+}
+
+/// Track the plaintext of a secure value so it can be scrubbed from output.
+///
+/// Every string within the value is tracked, so a wrapped `secureString` or `secureObject` can be passed directly.
+///
+/// # Arguments
+///
+/// * `value` - The secure value.
+pub fn register_secure_value(value: &Value) {
+    match value {
+        Value::String(value) => add_secure_value(value),
+        Value::Array(array) => array.iter().for_each(register_secure_value),
+        Value::Object(map) => map.values().for_each(register_secure_value),
+        _ => {},
+    }
+}
+
+/// Replace every tracked secure value in the text with the redacted placeholder.
+///
+/// # Arguments
+///
+/// * `text` - The text to scrub.
+///
+/// # Returns
+///
+/// The text, only copied if it contained a secure value.
+#[must_use]
+pub fn scrub(text: &str) -> Cow<'_, str> {
+    let values = SECURE_VALUES.read().unwrap_or_else(PoisonError::into_inner);
+    if !values.iter().any(|value| text.contains(value.as_str())) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut scrubbed = text.to_string();
+    for value in values.iter() {
+        if scrubbed.contains(value.as_str()) {
+            scrubbed = scrubbed.replace(value.as_str(), SECURE_VALUE_REDACTED);
+        }
+    }
+    Cow::Owned(scrubbed)
+}
+
+/// Replace every tracked secure value in the strings of a JSON value with the redacted placeholder.
+///
+/// # Arguments
+///
+/// * `value` - The JSON value to scrub.
+#[must_use]
+pub fn scrub_value(value: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(scrub(text).into_owned()),
+        Value::Array(array) => Value::Array(array.iter().map(scrub_value).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(key, value)| (key.clone(), scrub_value(value))).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// Replace every tracked secure value in a result unless scrubbing was disabled with `DSC_SHOW_SECURE_VALUES`.
+///
+/// # Arguments
+///
+/// * `value` - The JSON value of the result.
+#[must_use]
+pub fn scrub_result(value: Value) -> Value {
+    if show_secure_values() {
+        value
+    } else {
+        scrub_value(&value)
+    }
+}
+
+/// Check whether scrubbing secure values from results was disabled with the `DSC_SHOW_SECURE_VALUES` environment variable.
+#[must_use]
+pub fn show_secure_values() -> bool {
+    std::env::var(DSC_SHOW_SECURE_VALUES).is_ok_and(|value| value.eq_ignore_ascii_case("true") || value == "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Tests share the process-wide registry, so a test clearing it can't run alongside the others.
+    static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn registered_value_is_scrubbed() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        register_secure_value(&json!({ "secureString": "scrubTestValue1" }));
+        assert_eq!(scrub("the value is scrubTestValue1!"), "the value is <secureValue>!");
+        assert_eq!(scrub_value(&json!({ "output": ["scrubTestValue1"] })), json!({ "output": ["<secureValue>"] }));
+    }
+
+    #[test]
+    fn escaped_value_is_scrubbed() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        register_secure_value(&json!("scrub\"Test\\Value2"));
+        let json = serde_json::to_string(&json!({ "output": "scrub\"Test\\Value2" })).unwrap();
+        assert_eq!(scrub(&json), r#"{"output":"<secureValue>"}"#);
+    }
+
+    #[test]
+    fn short_value_is_not_scrubbed() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        register_secure_value(&json!("ab"));
+        assert_eq!(scrub("abc"), "abc");
+    }
+
+    #[test]
+    fn text_without_secure_values_is_not_copied() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        assert!(matches!(scrub("nothing to see here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn values_are_cleared_when_last_scope_ends() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let outer = SecureValueScope::new();
+        let inner = SecureValueScope::new();
+        register_secure_value(&json!("scrubTestValue3"));
+        drop(inner);
+        assert_eq!(scrub("scrubTestValue3"), SECURE_VALUE_REDACTED);
+        drop(outer);
+        assert_eq!(scrub("scrubTestValue3"), "scrubTestValue3");
+    }
+}
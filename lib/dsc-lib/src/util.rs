@@ -4,6 +4,7 @@
 use crate::dscerror::DscError;
 use rust_i18n::t;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::{
     fs,
     fs::{canonicalize, File},
@@ -252,6 +253,39 @@ fn load_value_from_json(path: &PathBuf, value_name: &str) -> Result<serde_json::
     Err(DscError::NotSupported(value_name.to_string()))
 }
 
+/// Computes the SHA-256 hash of a file.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file.
+///
+/// # Returns
+///
+/// The hash as a `sha256:` prefixed lowercase hex string.
+///
+/// # Errors
+///
+/// Will return `Err` if the file can't be read.
+pub fn get_file_hash(path: &Path) -> Result<String, DscError> {
+    let contents = fs::read(path)?;
+    Ok(get_content_hash(&contents))
+}
+
+/// Computes the SHA-256 hash of content that was already read.
+///
+/// # Arguments
+///
+/// * `contents` - The content to hash.
+///
+/// # Returns
+///
+/// The hash as a `sha256:` prefixed lowercase hex string.
+#[must_use]
+pub fn get_content_hash(contents: &[u8]) -> String {
+    let digest = Sha256::digest(contents);
+    format!("sha256:{digest:x}")
+}
+
 /// Gets path to the current dsc process.
 /// If dsc is started using a symlink, this functon returns target of the symlink.
 ///
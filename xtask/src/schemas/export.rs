@@ -106,6 +106,7 @@ pub(crate) fn export_schemas(
         dsc_lib::dscresources::resource_manifest::ExportMethod,
         dsc_lib::dscresources::resource_manifest::GetArgKind,
         dsc_lib::dscresources::resource_manifest::GetMethod,
+        dsc_lib::dscresources::resource_manifest::HostMethod,
         dsc_lib::dscresources::resource_manifest::InputKind,
         dsc_lib::dscresources::resource_manifest::Kind,
         dsc_lib::dscresources::resource_manifest::ResolveMethod,
@@ -121,6 +122,7 @@ pub(crate) fn export_schemas(
         dsc_lib::extensions::dscextension::Capability,
         dsc_lib::extensions::dscextension::DscExtension,
         dsc_lib::extensions::extension_manifest::ExtensionManifest,
+        dsc_lib::extensions::function::FunctionMethod,
         dsc_lib::extensions::import::ImportMethod,
         dsc_lib::extensions::secret::SecretMethod,
         dsc_lib::functions::FunctionArgKind,